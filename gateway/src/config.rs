@@ -1,6 +1,107 @@
+use crate::p2p::P2PCredentials;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
+use thiserror::Error;
+
+/// Compiled-in signaling server, used when no `P2P_SIGNALING_URL` env var
+/// and no `gateway.toml` override are present.
+pub const DEFAULT_SIGNALING_URL: &str = "wss://cf-wbrtc-auth.m-tama-ramu.workers.dev/ws/app";
+
+/// Log output format for the global `tracing` subscriber, selected via
+/// `GATEWAY_LOG_FORMAT` (env), `log_format` (config file), or
+/// [`GatewayConfig::log_format`]. `Json` switches
+/// `tracing_subscriber::fmt::layer()` to `.json()` so a log aggregator can
+/// parse fields (like request/peer IDs) instead of reading text lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        Self::Text
+    }
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown log format: {}. Use 'text' or 'json'", s)),
+        }
+    }
+}
+
+/// Errors returned by [`GatewayConfig::validate`]
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Invalid gRPC address {0:?}: {1}")]
+    InvalidGrpcAddr(String, String),
+
+    #[error("Invalid metrics address {0:?}: {1}")]
+    InvalidMetricsAddr(String, String),
+
+    #[error("Download path {0:?}: parent directory could not be created: {1}")]
+    DownloadPathNotCreatable(PathBuf, String),
+
+    #[error("{0} is set but empty")]
+    EmptyP2PUrl(&'static str),
+
+    #[error("P2P mode requires credentials at {0:?}; run `gateway --p2p-setup` first")]
+    MissingP2PCredentials(PathBuf),
+
+    #[error("Config file {0:?} could not be read: {1}")]
+    ConfigFileUnreadable(PathBuf, String),
+
+    #[error("Config file {0:?} is invalid: {1}")]
+    ConfigFileInvalid(PathBuf, String),
+}
+
+/// Overrides recognized in a `gateway.toml`/`gateway.json` file. Every field
+/// is optional so a deployment only needs to list what it's changing;
+/// unrecognized keys are ignored so the file can grow more sections later
+/// without breaking older binaries.
+#[derive(Debug, Default, Deserialize)]
+struct FileOverrides {
+    grpc_addr: Option<String>,
+    download_path: Option<PathBuf>,
+    max_concurrent_jobs: Option<usize>,
+    job_timeout_secs: Option<u64>,
+    account_delay_secs: Option<u64>,
+    default_headless: Option<bool>,
+    scraper_pool_size: Option<usize>,
+    scrape_retry_count: Option<u32>,
+    scrape_retry_delay_secs: Option<u64>,
+    scrape_account_timeout_secs: Option<u64>,
+    webhook_timeout_secs: Option<u64>,
+    webhook_retry_count: Option<u32>,
+    stream_download_chunk_size: Option<usize>,
+    shutdown_grace_secs: Option<u64>,
+    log_format: Option<LogFormat>,
+    enable_reflection: Option<bool>,
+    enable_metrics: Option<bool>,
+    metrics_addr: Option<String>,
+    p2p_rate_limit_rps: Option<f64>,
+    p2p_rate_limit_burst: Option<f64>,
+    p2p_peer_idle_timeout_secs: Option<u64>,
+    p2p_large_message_threshold_bytes: Option<usize>,
+    p2p_slow_request_threshold_secs: Option<u64>,
+    p2p_max_peers: Option<usize>,
+    p2p_peer_recreate_max_retries: Option<u32>,
+    signaling_url: Option<String>,
+    stun_servers: Option<Vec<String>>,
+    update_owner: Option<String>,
+    update_repo: Option<String>,
+    update_github_token: Option<String>,
+    update_api_base_url: Option<String>,
+    update_manifest_url: Option<String>,
+}
 
 /// Gateway service configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,11 +124,134 @@ pub struct GatewayConfig {
     /// Run browser in headless mode by default
     pub default_headless: bool,
 
+    /// Number of warm scraper/browser instances to keep pooled for
+    /// multi-account jobs, handed out per account and reused instead of
+    /// spinning up a fresh browser each time. See
+    /// [`crate::grpc::scraper_service::ScraperPool`].
+    pub scraper_pool_size: usize,
+
+    /// Number of extra attempts for a single account scrape after the
+    /// first failure, before it's marked failed. Only retryable errors
+    /// (timeouts, network issues) are retried; see
+    /// [`crate::scraper::ScraperErrorKind::is_retryable`].
+    pub scrape_retry_count: u32,
+
+    /// Delay in seconds between retry attempts for a failed account scrape
+    pub scrape_retry_delay_secs: u64,
+
+    /// Maximum time in seconds a single account's scrape may run before
+    /// it's cancelled and marked failed with a `Timeout` error, so one
+    /// hung account (e.g. a stuck site spinner) can't stall the rest of a
+    /// sequential job. Subject to `scrape_retry_count` like any other
+    /// retryable failure.
+    pub scrape_account_timeout_secs: u64,
+
+    /// Timeout in seconds for a single `callback_url` webhook delivery
+    /// attempt when a `scrape_multiple` job finishes. See
+    /// [`crate::job::webhook::send_webhook`].
+    pub webhook_timeout_secs: u64,
+
+    /// Number of extra attempts for a failed webhook delivery, before
+    /// giving up.
+    pub webhook_retry_count: u32,
+
+    /// Default chunk size in bytes for `StreamDownload`, used when a
+    /// request doesn't ask for a specific size. See
+    /// [`crate::grpc::scraper_service::STREAM_DOWNLOAD_MAX_CHUNK_SIZE`] for
+    /// the clamp applied to client-requested sizes.
+    pub stream_download_chunk_size: usize,
+
+    /// How long to wait, on shutdown, for in-flight `scrape_multiple`
+    /// background jobs to reach a safe point before exiting anyway. See
+    /// [`crate::job::shutdown::ShutdownCoordinator`].
+    pub shutdown_grace_secs: u64,
+
+    /// Output format for the global `tracing` subscriber, applied by
+    /// `run_server`, `run_p2p_client`, and `run_p2p_service` alike.
+    pub log_format: LogFormat,
+
     /// Service version
     pub version: String,
 
     /// Enable gRPC reflection
     pub enable_reflection: bool,
+
+    /// Serve a Prometheus `/metrics` endpoint (see [`crate::metrics`])
+    pub enable_metrics: bool,
+
+    /// Address the Prometheus `/metrics` endpoint listens on, when
+    /// `enable_metrics` is set
+    pub metrics_addr: String,
+
+    /// Requests/sec allowed per P2P peer on its DataChannel, before
+    /// `PeerRateLimiter` starts returning `ResourceExhausted`. See
+    /// [`crate::p2p::rate_limiter`].
+    pub p2p_rate_limit_rps: f64,
+
+    /// Burst size (max tokens) per P2P peer, on top of
+    /// `p2p_rate_limit_rps`'s steady-state refill rate
+    pub p2p_rate_limit_burst: f64,
+
+    /// How long a P2P peer's DataChannel can go without receiving data, or
+    /// sit in `Disconnected`/`Failed` state, before the reaper in
+    /// [`crate::p2p::runtime::P2PRuntime`] closes and removes it. Guards
+    /// against half-open WebRTC connections (e.g. an abandoned browser tab)
+    /// lingering in memory forever.
+    pub p2p_peer_idle_timeout_secs: u64,
+
+    /// Size in bytes, summed across a call's messages, above which
+    /// `TonicServiceBridge::call` logs a large-message warning for the
+    /// request or response side. See [`crate::p2p::grpc_handler::TonicServiceBridge`].
+    pub p2p_large_message_threshold_bytes: usize,
+
+    /// Call duration in seconds above which `TonicServiceBridge::call` logs
+    /// a slow-request warning.
+    pub p2p_slow_request_threshold_secs: u64,
+
+    /// Maximum number of simultaneously connected P2P peers. Once reached,
+    /// [`crate::p2p::runtime::P2PRuntime::on_offer`] rejects further offers
+    /// instead of creating a peer, so a burst of browser connections can't
+    /// exhaust the gateway's resources.
+    pub p2p_max_peers: usize,
+
+    /// Maximum number of times [`crate::p2p::runtime::P2PRuntime`] will
+    /// transparently recreate a peer whose WebRTC connection went
+    /// `Failed`/`Disconnected` (see [`crate::p2p::PeerRecreator`]) and
+    /// re-offer it to the same browser session, before giving up and
+    /// dropping it for good. Guards against looping forever re-offering a
+    /// peer whose network path is simply gone.
+    pub p2p_peer_recreate_max_retries: u32,
+
+    /// Signaling server URL used by the P2P paths
+    pub signaling_url: String,
+
+    /// STUN server URLs for NAT traversal, used as the fallback when
+    /// `P2P_STUN_SERVERS` isn't set
+    pub stun_servers: Vec<String>,
+
+    /// GitHub repository owner to check for updates
+    pub update_owner: String,
+
+    /// GitHub repository name to check for updates
+    pub update_repo: String,
+
+    /// GitHub API token used when checking for updates, from `GITHUB_TOKEN`.
+    /// Unauthenticated requests to the GitHub REST API are capped at 60/hour
+    /// per source IP, which a fleet of gateways sharing one egress IP can
+    /// burn through quickly; a token raises that to 5000/hour.
+    pub update_github_token: Option<String>,
+
+    /// GitHub REST API base URL used when checking for updates, from
+    /// `GITHUB_API_BASE_URL`. Defaults to `https://api.github.com`; point
+    /// this at a GitHub Enterprise instance's API base to check for updates
+    /// against an internal-only repository.
+    pub update_api_base_url: String,
+
+    /// URL of a static version manifest to check for updates against
+    /// instead of GitHub, from `UPDATE_MANIFEST_URL`. For air-gapped sites
+    /// that mirror releases on an internal server; takes priority over
+    /// `update_owner`/`update_repo` when set.
+    pub update_manifest_url: Option<String>,
 }
 
 impl Default for GatewayConfig {
@@ -39,16 +263,57 @@ impl Default for GatewayConfig {
             job_timeout_secs: 300,
             account_delay_secs: 2,
             default_headless: true,
+            scraper_pool_size: 3,
+            scrape_retry_count: 2,
+            scrape_retry_delay_secs: 5,
+            scrape_account_timeout_secs: 120,
+            webhook_timeout_secs: 10,
+            webhook_retry_count: 3,
+            stream_download_chunk_size: 32 * 1024,
+            shutdown_grace_secs: 30,
+            log_format: LogFormat::Text,
             version: env!("CARGO_PKG_VERSION").to_string(),
             enable_reflection: true,
+            enable_metrics: true,
+            metrics_addr: "127.0.0.1:9898".to_string(),
+            p2p_rate_limit_rps: crate::p2p::RateLimitConfig::default().requests_per_sec,
+            p2p_rate_limit_burst: crate::p2p::RateLimitConfig::default().burst,
+            p2p_peer_idle_timeout_secs: 300,
+            p2p_large_message_threshold_bytes: crate::p2p::grpc_handler::DEFAULT_LARGE_MESSAGE_THRESHOLD_BYTES,
+            p2p_slow_request_threshold_secs: crate::p2p::grpc_handler::DEFAULT_SLOW_REQUEST_THRESHOLD.as_secs(),
+            p2p_max_peers: crate::p2p::runtime::DEFAULT_MAX_PEERS,
+            p2p_peer_recreate_max_retries: crate::p2p::runtime::DEFAULT_PEER_RECREATE_MAX_RETRIES,
+            signaling_url: DEFAULT_SIGNALING_URL.to_string(),
+            stun_servers: crate::p2p::P2PConfig::default().stun_servers,
+            update_owner: "yhonda-ohishi-pub-dev".to_string(),
+            update_repo: "rust-router".to_string(),
+            update_github_token: None,
+            update_api_base_url: "https://api.github.com".to_string(),
+            update_manifest_url: None,
         }
     }
 }
 
 impl GatewayConfig {
-    /// Create configuration from environment variables
+    /// Create configuration from, in increasing precedence:
+    ///
+    /// 1. the compiled-in defaults ([`GatewayConfig::default`])
+    /// 2. a `gateway.toml` (or `gateway.json`) discovered next to the
+    ///    executable, via [`from_file`](Self::from_file)
+    /// 3. environment variables
+    ///
+    /// Each layer only overrides the fields it actually sets, so a
+    /// deployment's config file or env vars can be as small as a single
+    /// `signaling_url`. This lets the same binary be reused across
+    /// staging/production without a rebuild.
     pub fn from_env() -> Self {
-        let mut config = Self::default();
+        let mut config = match config_file_path() {
+            Some(path) => Self::from_file(&path).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring {}: {}", path.display(), e);
+                Self::default()
+            }),
+            None => Self::default(),
+        };
 
         if let Ok(addr) = std::env::var("GRPC_ADDR") {
             config.grpc_addr = addr;
@@ -80,9 +345,301 @@ impl GatewayConfig {
             config.default_headless = headless.to_lowercase() == "true" || headless == "1";
         }
 
+        if let Ok(size) = std::env::var("SCRAPER_POOL_SIZE") {
+            if let Ok(n) = size.parse() {
+                config.scraper_pool_size = n;
+            }
+        }
+
+        if let Ok(count) = std::env::var("SCRAPE_RETRY_COUNT") {
+            if let Ok(n) = count.parse() {
+                config.scrape_retry_count = n;
+            }
+        }
+
+        if let Ok(delay) = std::env::var("SCRAPE_RETRY_DELAY_SECS") {
+            if let Ok(n) = delay.parse() {
+                config.scrape_retry_delay_secs = n;
+            }
+        }
+
+        if let Ok(secs) = std::env::var("SCRAPE_ACCOUNT_TIMEOUT_SECS") {
+            if let Ok(n) = secs.parse() {
+                config.scrape_account_timeout_secs = n;
+            }
+        }
+
+        if let Ok(secs) = std::env::var("WEBHOOK_TIMEOUT_SECS") {
+            if let Ok(n) = secs.parse() {
+                config.webhook_timeout_secs = n;
+            }
+        }
+
+        if let Ok(count) = std::env::var("WEBHOOK_RETRY_COUNT") {
+            if let Ok(n) = count.parse() {
+                config.webhook_retry_count = n;
+            }
+        }
+
+        if let Ok(size) = std::env::var("STREAM_DOWNLOAD_CHUNK_SIZE") {
+            if let Ok(n) = size.parse() {
+                config.stream_download_chunk_size = n;
+            }
+        }
+
+        if let Ok(secs) = std::env::var("SHUTDOWN_GRACE_SECS") {
+            if let Ok(n) = secs.parse() {
+                config.shutdown_grace_secs = n;
+            }
+        }
+
+        if let Ok(format) = std::env::var("GATEWAY_LOG_FORMAT") {
+            if let Ok(format) = format.parse() {
+                config.log_format = format;
+            }
+        }
+
+        if let Ok(enabled) = std::env::var("ENABLE_METRICS") {
+            config.enable_metrics = enabled.to_lowercase() == "true" || enabled == "1";
+        }
+
+        if let Ok(addr) = std::env::var("METRICS_ADDR") {
+            config.metrics_addr = addr;
+        }
+
+        if let Ok(rps) = std::env::var("P2P_RATE_LIMIT_RPS") {
+            if let Ok(n) = rps.parse() {
+                config.p2p_rate_limit_rps = n;
+            }
+        }
+
+        if let Ok(burst) = std::env::var("P2P_RATE_LIMIT_BURST") {
+            if let Ok(n) = burst.parse() {
+                config.p2p_rate_limit_burst = n;
+            }
+        }
+
+        if let Ok(secs) = std::env::var("P2P_PEER_IDLE_TIMEOUT_SECS") {
+            if let Ok(n) = secs.parse() {
+                config.p2p_peer_idle_timeout_secs = n;
+            }
+        }
+
+        if let Ok(bytes) = std::env::var("P2P_LARGE_MESSAGE_THRESHOLD_BYTES") {
+            if let Ok(n) = bytes.parse() {
+                config.p2p_large_message_threshold_bytes = n;
+            }
+        }
+
+        if let Ok(secs) = std::env::var("P2P_SLOW_REQUEST_THRESHOLD_SECS") {
+            if let Ok(n) = secs.parse() {
+                config.p2p_slow_request_threshold_secs = n;
+            }
+        }
+
+        if let Ok(n) = std::env::var("P2P_MAX_PEERS") {
+            if let Ok(n) = n.parse() {
+                config.p2p_max_peers = n;
+            }
+        }
+
+        if let Ok(n) = std::env::var("P2P_PEER_RECREATE_MAX_RETRIES") {
+            if let Ok(n) = n.parse() {
+                config.p2p_peer_recreate_max_retries = n;
+            }
+        }
+
+        if let Ok(url) = std::env::var("P2P_SIGNALING_URL") {
+            config.signaling_url = url;
+        }
+
+        if let Ok(raw) = std::env::var("P2P_STUN_SERVERS") {
+            if let Ok(servers) = crate::p2p::parse_stun_servers(&raw) {
+                config.stun_servers = servers;
+            }
+        }
+
+        if let Ok(owner) = std::env::var("GITHUB_OWNER") {
+            config.update_owner = owner;
+        }
+
+        if let Ok(repo) = std::env::var("GITHUB_REPO") {
+            config.update_repo = repo;
+        }
+
+        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+            config.update_github_token = Some(token);
+        }
+
+        if let Ok(url) = std::env::var("GITHUB_API_BASE_URL") {
+            config.update_api_base_url = url;
+        }
+
+        if let Ok(url) = std::env::var("UPDATE_MANIFEST_URL") {
+            config.update_manifest_url = Some(url);
+        }
+
         config
     }
 
+    /// Load config overrides from a TOML or JSON file, layered on top of the
+    /// compiled defaults. The format is chosen by extension: `.json` is
+    /// parsed as JSON, anything else (including `gateway.toml`) as TOML.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::ConfigFileUnreadable(path.to_path_buf(), e.to_string()))?;
+
+        let is_json = path.extension().and_then(|ext| ext.to_str()) == Some("json");
+        let overrides: FileOverrides = if is_json {
+            serde_json::from_str(&contents)
+                .map_err(|e| ConfigError::ConfigFileInvalid(path.to_path_buf(), e.to_string()))?
+        } else {
+            toml::from_str(&contents)
+                .map_err(|e| ConfigError::ConfigFileInvalid(path.to_path_buf(), e.to_string()))?
+        };
+
+        let mut config = Self::default();
+        if let Some(addr) = overrides.grpc_addr {
+            config.grpc_addr = addr;
+        }
+        if let Some(path) = overrides.download_path {
+            config.download_path = path;
+        }
+        if let Some(n) = overrides.max_concurrent_jobs {
+            config.max_concurrent_jobs = n;
+        }
+        if let Some(n) = overrides.job_timeout_secs {
+            config.job_timeout_secs = n;
+        }
+        if let Some(n) = overrides.account_delay_secs {
+            config.account_delay_secs = n;
+        }
+        if let Some(headless) = overrides.default_headless {
+            config.default_headless = headless;
+        }
+        if let Some(n) = overrides.scraper_pool_size {
+            config.scraper_pool_size = n;
+        }
+        if let Some(n) = overrides.scrape_retry_count {
+            config.scrape_retry_count = n;
+        }
+        if let Some(n) = overrides.scrape_retry_delay_secs {
+            config.scrape_retry_delay_secs = n;
+        }
+        if let Some(n) = overrides.scrape_account_timeout_secs {
+            config.scrape_account_timeout_secs = n;
+        }
+        if let Some(n) = overrides.webhook_timeout_secs {
+            config.webhook_timeout_secs = n;
+        }
+        if let Some(n) = overrides.webhook_retry_count {
+            config.webhook_retry_count = n;
+        }
+        if let Some(n) = overrides.stream_download_chunk_size {
+            config.stream_download_chunk_size = n;
+        }
+        if let Some(n) = overrides.shutdown_grace_secs {
+            config.shutdown_grace_secs = n;
+        }
+        if let Some(format) = overrides.log_format {
+            config.log_format = format;
+        }
+        if let Some(enabled) = overrides.enable_reflection {
+            config.enable_reflection = enabled;
+        }
+        if let Some(enabled) = overrides.enable_metrics {
+            config.enable_metrics = enabled;
+        }
+        if let Some(addr) = overrides.metrics_addr {
+            config.metrics_addr = addr;
+        }
+        if let Some(n) = overrides.p2p_rate_limit_rps {
+            config.p2p_rate_limit_rps = n;
+        }
+        if let Some(n) = overrides.p2p_rate_limit_burst {
+            config.p2p_rate_limit_burst = n;
+        }
+        if let Some(n) = overrides.p2p_peer_idle_timeout_secs {
+            config.p2p_peer_idle_timeout_secs = n;
+        }
+        if let Some(n) = overrides.p2p_large_message_threshold_bytes {
+            config.p2p_large_message_threshold_bytes = n;
+        }
+        if let Some(n) = overrides.p2p_slow_request_threshold_secs {
+            config.p2p_slow_request_threshold_secs = n;
+        }
+        if let Some(n) = overrides.p2p_max_peers {
+            config.p2p_max_peers = n;
+        }
+        if let Some(n) = overrides.p2p_peer_recreate_max_retries {
+            config.p2p_peer_recreate_max_retries = n;
+        }
+        if let Some(url) = overrides.signaling_url {
+            config.signaling_url = url;
+        }
+        if let Some(servers) = overrides.stun_servers {
+            config.stun_servers = servers;
+        }
+        if let Some(owner) = overrides.update_owner {
+            config.update_owner = owner;
+        }
+        if let Some(repo) = overrides.update_repo {
+            config.update_repo = repo;
+        }
+        if let Some(token) = overrides.update_github_token {
+            config.update_github_token = Some(token);
+        }
+        if let Some(url) = overrides.update_api_base_url {
+            config.update_api_base_url = url;
+        }
+        if let Some(url) = overrides.update_manifest_url {
+            config.update_manifest_url = Some(url);
+        }
+        Ok(config)
+    }
+
+    /// Check that the configuration is actually usable, instead of letting
+    /// typo'd or missing settings silently fall back to defaults.
+    ///
+    /// Checks that `grpc_addr` parses and that `download_path`'s parent
+    /// exists or can be created. When `p2p_mode` is set, also checks that
+    /// `P2P_SIGNALING_URL` isn't set-but-empty and that a credentials file
+    /// is present, since P2P mode can't start without either.
+    pub fn validate(&self, p2p_mode: bool) -> Result<(), ConfigError> {
+        self.grpc_addr
+            .parse::<std::net::SocketAddr>()
+            .map_err(|e| ConfigError::InvalidGrpcAddr(self.grpc_addr.clone(), e.to_string()))?;
+
+        if self.enable_metrics {
+            self.metrics_addr
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| ConfigError::InvalidMetricsAddr(self.metrics_addr.clone(), e.to_string()))?;
+        }
+
+        if let Some(parent) = self.download_path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    ConfigError::DownloadPathNotCreatable(self.download_path.clone(), e.to_string())
+                })?;
+            }
+        }
+
+        if p2p_mode {
+            if let Ok(url) = std::env::var("P2P_SIGNALING_URL") {
+                if url.trim().is_empty() {
+                    return Err(ConfigError::EmptyP2PUrl("P2P_SIGNALING_URL"));
+                }
+            }
+
+            let creds_path = P2PCredentials::default_path();
+            if !creds_path.exists() {
+                return Err(ConfigError::MissingP2PCredentials(creds_path));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get job timeout as Duration
     pub fn job_timeout(&self) -> Duration {
         Duration::from_secs(self.job_timeout_secs)
@@ -92,6 +649,79 @@ impl GatewayConfig {
     pub fn account_delay(&self) -> Duration {
         Duration::from_secs(self.account_delay_secs)
     }
+
+    /// Get the delay between scrape retry attempts as Duration
+    pub fn scrape_retry_delay(&self) -> Duration {
+        Duration::from_secs(self.scrape_retry_delay_secs)
+    }
+
+    /// Get the per-account scrape timeout as Duration
+    pub fn scrape_account_timeout(&self) -> Duration {
+        Duration::from_secs(self.scrape_account_timeout_secs)
+    }
+
+    /// Get the shutdown grace period as Duration
+    pub fn shutdown_grace(&self) -> Duration {
+        Duration::from_secs(self.shutdown_grace_secs)
+    }
+
+    /// Project this config down to the subset `job::webhook::send_webhook`
+    /// needs.
+    pub fn webhook_config(&self) -> crate::job::WebhookConfig {
+        crate::job::WebhookConfig {
+            timeout: Duration::from_secs(self.webhook_timeout_secs),
+            retry_count: self.webhook_retry_count,
+        }
+    }
+
+    /// Project this config down to the subset `PeerRateLimiter` needs
+    pub fn p2p_rate_limit_config(&self) -> crate::p2p::RateLimitConfig {
+        crate::p2p::RateLimitConfig {
+            requests_per_sec: self.p2p_rate_limit_rps,
+            burst: self.p2p_rate_limit_burst,
+        }
+    }
+
+    /// Get the P2P peer idle timeout as Duration
+    pub fn p2p_peer_idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.p2p_peer_idle_timeout_secs)
+    }
+
+    /// Call duration above which `TonicServiceBridge::call` logs a
+    /// slow-request warning.
+    pub fn p2p_slow_request_threshold(&self) -> Duration {
+        Duration::from_secs(self.p2p_slow_request_threshold_secs)
+    }
+
+    /// Read `GATEWAY_LOG_FORMAT` directly, falling back to [`LogFormat::Text`]
+    /// if it's unset or unrecognized. Split out from [`GatewayConfig::from_env`]
+    /// so the tracing subscriber can be initialized with the right format
+    /// before the rest of the config (which may itself want to log a
+    /// warning, e.g. about an unreadable config file) is loaded.
+    pub fn log_format_from_env() -> LogFormat {
+        std::env::var("GATEWAY_LOG_FORMAT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// `gateway.toml` or `gateway.json` next to the running executable, if
+/// either exists there. TOML is preferred when both are present.
+fn config_file_path() -> Option<PathBuf> {
+    let dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+
+    let toml_path = dir.join("gateway.toml");
+    if toml_path.exists() {
+        return Some(toml_path);
+    }
+
+    let json_path = dir.join("gateway.json");
+    if json_path.exists() {
+        return Some(json_path);
+    }
+
+    None
 }
 
 #[cfg(test)]
@@ -104,5 +734,209 @@ mod tests {
         assert_eq!(config.grpc_addr, "[::1]:50051");
         assert_eq!(config.max_concurrent_jobs, 1);
         assert!(config.default_headless);
+        assert_eq!(config.scraper_pool_size, 3);
+        assert_eq!(config.scrape_retry_count, 2);
+        assert_eq!(config.scrape_retry_delay_secs, 5);
+        assert_eq!(config.scrape_account_timeout_secs, 120);
+        assert_eq!(config.webhook_timeout_secs, 10);
+        assert_eq!(config.webhook_retry_count, 3);
+        assert_eq!(config.stream_download_chunk_size, 32 * 1024);
+        assert_eq!(config.shutdown_grace_secs, 30);
+        assert_eq!(config.log_format, LogFormat::Text);
+        assert!(config.enable_metrics);
+        assert_eq!(config.metrics_addr, "127.0.0.1:9898");
+        assert_eq!(config.p2p_rate_limit_rps, 20.0);
+        assert_eq!(config.p2p_rate_limit_burst, 40.0);
+        assert_eq!(config.p2p_peer_idle_timeout_secs, 300);
+        assert_eq!(
+            config.p2p_large_message_threshold_bytes,
+            crate::p2p::grpc_handler::DEFAULT_LARGE_MESSAGE_THRESHOLD_BYTES
+        );
+        assert_eq!(config.p2p_slow_request_threshold_secs, 5);
+        assert_eq!(config.p2p_max_peers, crate::p2p::runtime::DEFAULT_MAX_PEERS);
+        assert_eq!(
+            config.p2p_peer_recreate_max_retries,
+            crate::p2p::runtime::DEFAULT_PEER_RECREATE_MAX_RETRIES
+        );
+    }
+
+    #[test]
+    fn test_log_format_from_env() {
+        std::env::set_var("GATEWAY_LOG_FORMAT", "json");
+        assert_eq!(GatewayConfig::log_format_from_env(), LogFormat::Json);
+
+        std::env::set_var("GATEWAY_LOG_FORMAT", "not-a-format");
+        assert_eq!(GatewayConfig::log_format_from_env(), LogFormat::Text);
+
+        std::env::remove_var("GATEWAY_LOG_FORMAT");
+        assert_eq!(GatewayConfig::log_format_from_env(), LogFormat::Text);
+    }
+
+    #[test]
+    fn test_from_file_overrides_log_format() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, r#"log_format = "json""#).unwrap();
+
+        let config = GatewayConfig::from_file(file.path()).unwrap();
+        assert_eq!(config.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn test_from_file_overrides_p2p_rate_limit() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "p2p_rate_limit_rps = 5.0\np2p_rate_limit_burst = 10.0").unwrap();
+
+        let config = GatewayConfig::from_file(file.path()).unwrap();
+        let rate_limit = config.p2p_rate_limit_config();
+        assert_eq!(rate_limit.requests_per_sec, 5.0);
+        assert_eq!(rate_limit.burst, 10.0);
+    }
+
+    #[test]
+    fn test_from_file_overrides_p2p_peer_idle_timeout() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "p2p_peer_idle_timeout_secs = 60").unwrap();
+
+        let config = GatewayConfig::from_file(file.path()).unwrap();
+        assert_eq!(config.p2p_peer_idle_timeout(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_validate_accepts_default_config() {
+        let config = GatewayConfig::default();
+        assert!(config.validate(false).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_grpc_addr() {
+        let mut config = GatewayConfig::default();
+        config.grpc_addr = "not-an-address".to_string();
+
+        match config.validate(false) {
+            Err(ConfigError::InvalidGrpcAddr(addr, _)) => assert_eq!(addr, "not-an-address"),
+            other => panic!("expected InvalidGrpcAddr, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_metrics_addr() {
+        let mut config = GatewayConfig::default();
+        config.metrics_addr = "not-an-address".to_string();
+
+        match config.validate(false) {
+            Err(ConfigError::InvalidMetricsAddr(addr, _)) => assert_eq!(addr, "not-an-address"),
+            other => panic!("expected InvalidMetricsAddr, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_ignores_invalid_metrics_addr_when_disabled() {
+        let mut config = GatewayConfig::default();
+        config.enable_metrics = false;
+        config.metrics_addr = "not-an-address".to_string();
+
+        assert!(config.validate(false).is_ok());
+    }
+
+    #[test]
+    fn test_validate_p2p_mode_rejects_empty_signaling_url() {
+        let config = GatewayConfig::default();
+        std::env::set_var("P2P_SIGNALING_URL", "");
+
+        match config.validate(true) {
+            Err(ConfigError::EmptyP2PUrl("P2P_SIGNALING_URL")) => {}
+            other => panic!("expected EmptyP2PUrl, got {:?}", other),
+        }
+
+        std::env::remove_var("P2P_SIGNALING_URL");
+    }
+
+    #[test]
+    fn test_from_file_overrides_signaling_and_stun() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"signaling_url = "wss://staging.example/ws/app"
+stun_servers = ["stun:stun.internal:3478"]"#
+        )
+        .unwrap();
+
+        let config = GatewayConfig::from_file(file.path()).unwrap();
+        assert_eq!(config.signaling_url, "wss://staging.example/ws/app");
+        assert_eq!(config.stun_servers, vec!["stun:stun.internal:3478"]);
+    }
+
+    #[test]
+    fn test_from_file_leaves_unset_fields_at_default() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, r#"signaling_url = "wss://staging.example/ws/app""#).unwrap();
+
+        let config = GatewayConfig::from_file(file.path()).unwrap();
+        assert_eq!(config.signaling_url, "wss://staging.example/ws/app");
+        assert_eq!(config.stun_servers, GatewayConfig::default().stun_servers);
+    }
+
+    #[test]
+    fn test_from_file_rejects_invalid_toml() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "not valid toml {{").unwrap();
+
+        match GatewayConfig::from_file(file.path()) {
+            Err(ConfigError::ConfigFileInvalid(_, _)) => {}
+            other => panic!("expected ConfigFileInvalid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_file_reads_json_by_extension() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gateway.json");
+        let mut file = std::fs::File::create(&path).unwrap();
+        write!(
+            file,
+            r#"{{"grpc_addr": "[::1]:9000", "update_owner": "acme", "update_repo": "widgets"}}"#
+        )
+        .unwrap();
+        drop(file);
+
+        let config = GatewayConfig::from_file(&path).unwrap();
+        assert_eq!(config.grpc_addr, "[::1]:9000");
+        assert_eq!(config.update_owner, "acme");
+        assert_eq!(config.update_repo, "widgets");
+        // Fields absent from the file keep their compiled defaults.
+        assert_eq!(config.max_concurrent_jobs, GatewayConfig::default().max_concurrent_jobs);
+    }
+
+    #[test]
+    fn test_from_file_overrides_concurrency_and_download_path() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"download_path = "/tmp/gateway-downloads"
+max_concurrent_jobs = 5
+job_timeout_secs = 120"#
+        )
+        .unwrap();
+
+        let config = GatewayConfig::from_file(file.path()).unwrap();
+        assert_eq!(config.download_path, PathBuf::from("/tmp/gateway-downloads"));
+        assert_eq!(config.max_concurrent_jobs, 5);
+        assert_eq!(config.job_timeout_secs, 120);
     }
 }
@@ -39,9 +39,13 @@ impl GatewayService for GatewayServiceImpl {
         &self,
         _request: Request<HealthCheckRequest>,
     ) -> Result<Response<HealthCheckResponse>, Status> {
+        let service_health = self.router.health_all().await;
+        let healthy = service_health.values().all(|&ok| ok);
+
         let response = HealthCheckResponse {
-            healthy: true,
+            healthy,
             version: env!("CARGO_PKG_VERSION").to_string(),
+            service_health,
         };
         Ok(Response::new(response))
     }
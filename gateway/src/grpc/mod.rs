@@ -17,9 +17,15 @@ pub mod pdf_server {
     pub use proto::pdf::*;
 }
 
+// Timecard proto
+pub mod timecard_server {
+    pub use proto::timecard::*;
+}
+
 pub mod gateway_service;
 pub mod scraper_service;
 pub mod pdf_service;
+pub mod print_queue;
 
 pub use scraper_service::EtcScraperService;
 pub use pdf_service::PdfGeneratorService;
@@ -1,6 +1,8 @@
 //! PDF Generator gRPC service implementation
 
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tonic::{Request, Response, Status};
 use tower::Service;
 
@@ -8,9 +10,13 @@ use crate::grpc::pdf_server::{
     GeneratePdfRequest, GeneratePdfResponse,
     PrintPdfRequest, PrintPdfResponse,
     PdfHealthRequest, PdfHealthResponse,
+    ListPrintersRequest, ListPrintersResponse,
+    GetPrintStatusRequest, GetPrintStatusResponse,
+    PrintJobStatus as ProtoPrintJobStatus,
     Item as ProtoItem, Ryohi as ProtoRyohi,
     pdf_generator_server::PdfGenerator,
 };
+use crate::grpc::print_queue::{PrintJobQueue, PrintJobStatus};
 
 // print-pdf-service からインポート
 use print_pdf_service::{
@@ -24,6 +30,7 @@ use print_pdf_service::{
 /// PDF Generator gRPC service implementation
 pub struct PdfGeneratorService {
     output_path: PathBuf,
+    print_jobs: Arc<RwLock<PrintJobQueue>>,
 }
 
 impl PdfGeneratorService {
@@ -31,12 +38,16 @@ impl PdfGeneratorService {
     pub fn new() -> Self {
         Self {
             output_path: std::env::temp_dir().join("gateway-pdf"),
+            print_jobs: Arc::new(RwLock::new(PrintJobQueue::new())),
         }
     }
 
     /// Create with custom output path
     pub fn with_output_path(output_path: PathBuf) -> Self {
-        Self { output_path }
+        Self {
+            output_path,
+            print_jobs: Arc::new(RwLock::new(PrintJobQueue::new())),
+        }
     }
 }
 
@@ -131,6 +142,81 @@ fn convert_ryohi(proto_ryohi: &ProtoRyohi) -> InternalRyohi {
     }
 }
 
+/// `template` values accepted by `GeneratePdf`. Only the default (empty)
+/// layout is currently wired through to `print_pdf_service` - it doesn't
+/// expose a layout-selection API yet, so a non-default template validates
+/// but still renders with the default layout until that crate adds one.
+const KNOWN_PDF_TEMPLATES: &[&str] = &["", "compact", "detailed"];
+
+/// `paper_size` values accepted by `GeneratePdf`. Empty defaults to "A4".
+const KNOWN_PAPER_SIZES: &[&str] = &["", "A4", "Letter"];
+
+/// `orientation` values accepted by `GeneratePdf`. Empty defaults to "portrait".
+const KNOWN_ORIENTATIONS: &[&str] = &["", "portrait", "landscape"];
+
+/// Max `items` accepted by `GeneratePdf`, to bound memory use and the size
+/// of the resulting PDF.
+const MAX_GENERATE_PDF_ITEMS: usize = 500;
+
+/// Validate `items` beyond emptiness: caps the count, rejects negative
+/// prices, and checks that any non-empty date string parses, so a bad
+/// client request fails fast with `Status::invalid_argument` naming the
+/// offending field instead of `convert_item`/`convert_ryohi` silently
+/// coercing it away.
+fn validate_generate_pdf_items(items: &[ProtoItem]) -> Result<(), Status> {
+    if items.len() > MAX_GENERATE_PDF_ITEMS {
+        return Err(Status::invalid_argument(format!(
+            "items: too many items ({}), max is {}",
+            items.len(),
+            MAX_GENERATE_PDF_ITEMS
+        )));
+    }
+
+    for (idx, item) in items.iter().enumerate() {
+        if item.price < 0 {
+            return Err(Status::invalid_argument(format!(
+                "items[{}].price: must not be negative, got {}",
+                idx, item.price
+            )));
+        }
+        if !item.start_date.is_empty() && parse_pdf_date(&item.start_date).is_err() {
+            return Err(Status::invalid_argument(format!(
+                "items[{}].start_date: invalid date '{}', expected YYYY-MM-DD",
+                idx, item.start_date
+            )));
+        }
+        if !item.end_date.is_empty() && parse_pdf_date(&item.end_date).is_err() {
+            return Err(Status::invalid_argument(format!(
+                "items[{}].end_date: invalid date '{}', expected YYYY-MM-DD",
+                idx, item.end_date
+            )));
+        }
+
+        for (ryohi_idx, ryohi) in item.ryohi.iter().enumerate() {
+            if ryohi.price < 0 {
+                return Err(Status::invalid_argument(format!(
+                    "items[{}].ryohi[{}].price: must not be negative, got {}",
+                    idx, ryohi_idx, ryohi.price
+                )));
+            }
+            if !ryohi.date.is_empty() && parse_pdf_date(&ryohi.date).is_err() {
+                return Err(Status::invalid_argument(format!(
+                    "items[{}].ryohi[{}].date: invalid date '{}', expected YYYY-MM-DD",
+                    idx, ryohi_idx, ryohi.date
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a date field in the `YYYY-MM-DD` format used throughout the
+/// request, matching the format timecard-service validates dates against.
+fn parse_pdf_date(date: &str) -> Result<chrono::NaiveDate, chrono::ParseError> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+}
+
 #[tonic::async_trait]
 impl PdfGenerator for PdfGeneratorService {
     /// Generate PDF only
@@ -144,6 +230,46 @@ impl PdfGenerator for PdfGeneratorService {
             return Err(Status::invalid_argument("At least one item is required"));
         }
 
+        validate_generate_pdf_items(&req.items)?;
+
+        if !KNOWN_PDF_TEMPLATES.contains(&req.template.as_str()) {
+            return Err(Status::invalid_argument(format!(
+                "Unknown template: {}",
+                req.template
+            )));
+        }
+        if !req.template.is_empty() {
+            tracing::warn!(
+                "template '{}' requested but print_pdf_service has no layout-selection API yet; using default layout",
+                req.template
+            );
+        }
+
+        if !KNOWN_PAPER_SIZES.contains(&req.paper_size.as_str()) {
+            return Err(Status::invalid_argument(format!(
+                "Unsupported paper_size: {}",
+                req.paper_size
+            )));
+        }
+        if !KNOWN_ORIENTATIONS.contains(&req.orientation.as_str()) {
+            return Err(Status::invalid_argument(format!(
+                "Unsupported orientation: {}",
+                req.orientation
+            )));
+        }
+        if !req.paper_size.is_empty() && req.paper_size != "A4" {
+            tracing::warn!(
+                "paper_size '{}' requested but print_pdf_service has no paper-size API yet; using default A4",
+                req.paper_size
+            );
+        }
+        if !req.orientation.is_empty() && req.orientation != "portrait" {
+            tracing::warn!(
+                "orientation '{}' requested but print_pdf_service has no orientation API yet; using default portrait",
+                req.orientation
+            );
+        }
+
         tracing::info!("GeneratePdf requested with {} items", req.items.len());
 
         // Convert proto items to internal items
@@ -203,7 +329,10 @@ impl PdfGenerator for PdfGeneratorService {
         }
     }
 
-    /// Generate PDF and print
+    /// Queue a PDF generation + print job and return its id immediately.
+    /// The print spooler can block for a while on a busy or offline
+    /// printer, so the actual work happens in a background task instead of
+    /// holding the gRPC call open; poll `GetPrintStatus` for progress.
     async fn print_pdf(
         &self,
         request: Request<PrintPdfRequest>,
@@ -214,6 +343,21 @@ impl PdfGenerator for PdfGeneratorService {
             return Err(Status::invalid_argument("At least one item is required"));
         }
 
+        if !req.printer_name.is_empty() {
+            // `list_system_printers` only returns results on platforms we know
+            // how to query (currently Windows); when it comes back empty we
+            // can't tell a genuinely unknown printer from "can't enumerate",
+            // so we skip validation rather than reject a name that might be
+            // perfectly valid.
+            let printers = list_system_printers();
+            if !printers.is_empty() && !printers.iter().any(|p| p == &req.printer_name) {
+                return Err(Status::not_found(format!(
+                    "Unknown printer: {}",
+                    req.printer_name
+                )));
+            }
+        }
+
         tracing::info!(
             "PrintPdf requested with {} items, printer: {:?}",
             req.items.len(),
@@ -227,7 +371,6 @@ impl PdfGenerator for PdfGeneratorService {
         // Convert proto items to internal items
         let items: Vec<InternalItem> = req.items.iter().map(convert_item).collect();
 
-        // Generate PDF
         let output_path = self.output_path.join(format!(
             "ryohi_{}.pdf",
             chrono::Local::now().format("%Y%m%d_%H%M%S")
@@ -237,41 +380,55 @@ impl PdfGenerator for PdfGeneratorService {
         if let Some(parent) = output_path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(parent).await {
                 tracing::error!("Failed to create output directory: {}", e);
-                return Ok(Response::new(PrintPdfResponse {
-                    success: false,
-                    message: format!("Failed to create output directory: {}", e),
-                    pdf_path: String::new(),
-                }));
+                return Err(Status::internal(format!("Failed to create output directory: {}", e)));
             }
         }
 
-        // Create PDF using internal service with print flag
-        let mut service = InternalPdfService::new();
-        let mut internal_req = InternalPdfRequest::new(items)
-            .with_output_path(&output_path)
-            .with_print(true);
+        let job_id = {
+            let mut jobs = self.print_jobs.write().await;
+            jobs.create_job()
+        };
 
-        if !req.printer_name.is_empty() {
-            internal_req = internal_req.with_printer_name(&req.printer_name);
-        }
+        let printer_name = req.printer_name.clone();
+        let print_jobs = Arc::clone(&self.print_jobs);
+        let background_job_id = job_id.clone();
+        tokio::spawn(async move {
+            {
+                let mut jobs = print_jobs.write().await;
+                jobs.set_printing(&background_job_id);
+            }
 
-        match service.call(internal_req).await {
-            Ok(result) => {
-                Ok(Response::new(PrintPdfResponse {
-                    success: true,
-                    message: "PDF generated and printed successfully".to_string(),
-                    pdf_path: result.pdf_path.to_string_lossy().to_string(),
-                }))
+            let mut service = InternalPdfService::new();
+            let mut internal_req = InternalPdfRequest::new(items)
+                .with_output_path(&output_path)
+                .with_print(true);
+            if !printer_name.is_empty() {
+                internal_req = internal_req.with_printer_name(&printer_name);
             }
-            Err(e) => {
-                tracing::error!("PDF print failed: {}", e);
-                Ok(Response::new(PrintPdfResponse {
-                    success: false,
-                    message: format!("PDF print failed: {}", e),
-                    pdf_path: String::new(),
-                }))
+
+            match service.call(internal_req).await {
+                Ok(result) => {
+                    tracing::info!("Print job {} completed", background_job_id);
+                    let mut jobs = print_jobs.write().await;
+                    jobs.set_completed(&background_job_id, result.pdf_path);
+                }
+                Err(e) => {
+                    // Covers an offline/busy printer along with any other
+                    // print-backend failure - the RPC already returned, so
+                    // this surfaces through GetPrintStatus instead.
+                    tracing::error!("Print job {} failed: {}", background_job_id, e);
+                    let mut jobs = print_jobs.write().await;
+                    jobs.set_failed(&background_job_id, e.to_string());
+                }
             }
-        }
+        });
+
+        Ok(Response::new(PrintPdfResponse {
+            success: true,
+            message: "Print job queued".to_string(),
+            pdf_path: String::new(),
+            job_id,
+        }))
     }
 
     /// Health check
@@ -293,4 +450,160 @@ impl PdfGenerator for PdfGeneratorService {
             sumatra_available,
         }))
     }
+
+    /// List installed printers, so clients can pick a valid `printer_name`
+    /// for `PrintPdf` instead of guessing.
+    async fn list_printers(
+        &self,
+        _request: Request<ListPrintersRequest>,
+    ) -> Result<Response<ListPrintersResponse>, Status> {
+        tracing::debug!("ListPrinters requested");
+
+        let printers = list_system_printers();
+        let default_printer = default_system_printer().unwrap_or_default();
+
+        Ok(Response::new(ListPrintersResponse {
+            printers,
+            default_printer,
+        }))
+    }
+
+    /// Report progress for a job queued by `PrintPdf`
+    async fn get_print_status(
+        &self,
+        request: Request<GetPrintStatusRequest>,
+    ) -> Result<Response<GetPrintStatusResponse>, Status> {
+        let req = request.into_inner();
+
+        let jobs = self.print_jobs.read().await;
+        let job = jobs
+            .get_job(&req.job_id)
+            .ok_or_else(|| Status::not_found(format!("Print job not found: {}", req.job_id)))?;
+
+        Ok(Response::new(GetPrintStatusResponse {
+            status: to_proto_print_job_status(job.status) as i32,
+            pdf_path: job
+                .pdf_path
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            error_message: job.error_message.clone().unwrap_or_default(),
+        }))
+    }
+}
+
+/// Convert an internal [`PrintJobStatus`] to its proto equivalent
+fn to_proto_print_job_status(status: PrintJobStatus) -> ProtoPrintJobStatus {
+    match status {
+        PrintJobStatus::Queued => ProtoPrintJobStatus::PrintQueued,
+        PrintJobStatus::Printing => ProtoPrintJobStatus::PrintPrinting,
+        PrintJobStatus::Completed => ProtoPrintJobStatus::PrintCompleted,
+        PrintJobStatus::Failed => ProtoPrintJobStatus::PrintFailed,
+    }
+}
+
+/// Enumerate printers installed on the host. There's no printer-enumeration
+/// API among the dependencies already in use, so - like
+/// `check_windows_user_session`/`check_chrome_available` in
+/// `scraper_service.rs` - this shells out to query the OS directly.
+#[cfg(windows)]
+fn list_system_printers() -> Vec<String> {
+    use std::process::Command;
+
+    match Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-Printer | Select-Object -ExpandProperty Name",
+        ])
+        .output()
+    {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(not(windows))]
+fn list_system_printers() -> Vec<String> {
+    vec![]
+}
+
+/// The host's default printer name, or `None` if it can't be determined.
+#[cfg(windows)]
+fn default_system_printer() -> Option<String> {
+    use std::process::Command;
+
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "(Get-CimInstance -ClassName Win32_Printer -Filter 'Default=true').Name",
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[cfg(not(windows))]
+fn default_system_printer() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item_with(price: i32, start_date: &str) -> ProtoItem {
+        ProtoItem {
+            start_date: start_date.to_string(),
+            price,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_generate_pdf_items_accepts_empty_optional_fields() {
+        let items = vec![item_with(1000, "")];
+        assert!(validate_generate_pdf_items(&items).is_ok());
+    }
+
+    #[test]
+    fn test_validate_generate_pdf_items_rejects_negative_price() {
+        let items = vec![item_with(-1, "")];
+        let err = validate_generate_pdf_items(&items).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("price"));
+    }
+
+    #[test]
+    fn test_validate_generate_pdf_items_rejects_unparseable_date() {
+        let items = vec![item_with(1000, "not-a-date")];
+        let err = validate_generate_pdf_items(&items).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("start_date"));
+    }
+
+    #[test]
+    fn test_validate_generate_pdf_items_rejects_too_many_items() {
+        let items: Vec<ProtoItem> = (0..MAX_GENERATE_PDF_ITEMS + 1)
+            .map(|_| item_with(1000, ""))
+            .collect();
+        let err = validate_generate_pdf_items(&items).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("too many items"));
+    }
 }
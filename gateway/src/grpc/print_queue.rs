@@ -0,0 +1,133 @@
+//! In-memory queue for `PrintPdf` background jobs.
+//!
+//! Printing can block on a busy or offline print spooler, so `PrintPdf`
+//! enqueues a job and returns its id immediately instead of blocking the
+//! gRPC call; `GetPrintStatus` reports progress. Deliberately smaller than
+//! `crate::job::JobQueue` - print jobs don't retry and don't share a
+//! "current job" slot, since each one runs in its own background task as
+//! soon as it's created.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+/// Status of a single print job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintJobStatus {
+    /// Job has been created but hasn't started printing yet
+    Queued,
+    /// Job has been handed to the print backend
+    Printing,
+    /// Job printed successfully
+    Completed,
+    /// Job failed (e.g. the printer is offline)
+    Failed,
+}
+
+/// State tracked for a single `PrintPdf` background job.
+#[derive(Debug, Clone)]
+pub struct PrintJob {
+    pub job_id: String,
+    pub status: PrintJobStatus,
+    pub pdf_path: Option<PathBuf>,
+    pub error_message: Option<String>,
+}
+
+impl PrintJob {
+    fn new(job_id: String) -> Self {
+        Self {
+            job_id,
+            status: PrintJobStatus::Queued,
+            pdf_path: None,
+            error_message: None,
+        }
+    }
+}
+
+/// Queue of in-flight/completed print jobs, keyed by job_id.
+#[derive(Debug, Default)]
+pub struct PrintJobQueue {
+    jobs: HashMap<String, PrintJob>,
+}
+
+impl PrintJobQueue {
+    /// Create a new empty print job queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, Queued print job and return its id.
+    pub fn create_job(&mut self) -> String {
+        let job_id = Uuid::new_v4().to_string();
+        self.jobs.insert(job_id.clone(), PrintJob::new(job_id.clone()));
+        job_id
+    }
+
+    /// Get a job by ID
+    pub fn get_job(&self, job_id: &str) -> Option<&PrintJob> {
+        self.jobs.get(job_id)
+    }
+
+    /// Mark a job as handed to the print backend
+    pub fn set_printing(&mut self, job_id: &str) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.status = PrintJobStatus::Printing;
+        }
+    }
+
+    /// Mark a job as completed, recording the generated PDF's path
+    pub fn set_completed(&mut self, job_id: &str, pdf_path: PathBuf) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.status = PrintJobStatus::Completed;
+            job.pdf_path = Some(pdf_path);
+        }
+    }
+
+    /// Mark a job as failed with an error message (e.g. printer offline)
+    pub fn set_failed(&mut self, job_id: &str, error: String) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.status = PrintJobStatus::Failed;
+            job.error_message = Some(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_job_starts_queued() {
+        let mut queue = PrintJobQueue::new();
+        let job_id = queue.create_job();
+
+        let job = queue.get_job(&job_id).unwrap();
+        assert_eq!(job.status, PrintJobStatus::Queued);
+    }
+
+    #[test]
+    fn test_job_transitions_to_completed() {
+        let mut queue = PrintJobQueue::new();
+        let job_id = queue.create_job();
+
+        queue.set_printing(&job_id);
+        assert_eq!(queue.get_job(&job_id).unwrap().status, PrintJobStatus::Printing);
+
+        queue.set_completed(&job_id, PathBuf::from("./out.pdf"));
+        let job = queue.get_job(&job_id).unwrap();
+        assert_eq!(job.status, PrintJobStatus::Completed);
+        assert_eq!(job.pdf_path, Some(PathBuf::from("./out.pdf")));
+    }
+
+    #[test]
+    fn test_job_transitions_to_failed() {
+        let mut queue = PrintJobQueue::new();
+        let job_id = queue.create_job();
+
+        queue.set_failed(&job_id, "printer offline".to_string());
+        let job = queue.get_job(&job_id).unwrap();
+        assert_eq!(job.status, PrintJobStatus::Failed);
+        assert_eq!(job.error_message.as_deref(), Some("printer offline"));
+    }
+}
@@ -1,22 +1,29 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 
-use chrono::Local;
-use tokio::sync::RwLock;
+use chrono::{Local, TimeZone};
+use tokio::sync::{broadcast, RwLock};
 use tokio_stream::Stream;
 use tonic::{Request, Response, Status};
 use tower::Service;
+use tracing::Instrument;
+use uuid::Uuid;
 
 use crate::config::GatewayConfig;
-use crate::job::{JobQueue, JobStatus};
+use crate::job::state::MAX_RETAINED_CSV_BYTES;
+use crate::job::{JobEvent, JobEventKind, JobQueue, JobStatus, ShutdownCoordinator};
+use crate::scraper::ScraperErrorKind;
+use crate::updater::{AutoUpdater, CachedUpdateCheck, UpdateConfig};
 use crate::grpc::scraper_server::etc_scraper_server::EtcScraper;
 use crate::grpc::scraper_server::{
-    DownloadedFile, GetDownloadedFilesRequest, GetDownloadedFilesResponse,
-    HealthRequest, HealthResponse, JobStatus as ProtoJobStatus,
-    ScrapeMultipleRequest, ScrapeMultipleResponse, ScrapeRequest, ScrapeResponse,
-    StreamDownloadChunk, StreamDownloadRequest,
-    SystemInfoRequest, SystemInfoResponse,
+    DownloadedFile, GetDownloadedFilesRequest, GetDownloadedFilesResponse, GetJobResultsRequest,
+    GetJobResultsResponse, HealthRequest, HealthResponse, JobAccountResult,
+    JobEvent as ProtoJobEvent, JobEventType, JobStatus as ProtoJobStatus, MergeSessionCsvRequest,
+    MergeSessionCsvResponse, ScrapeErrorCode, ScrapeMultipleRequest, ScrapeMultipleResponse,
+    ScrapeRequest, ScrapeResponse, StreamDownloadChunk, StreamDownloadRequest,
+    StreamDownloadZipChunk, StreamDownloadZipRequest, SystemInfoRequest, SystemInfoResponse,
+    WatchJobRequest,
 };
 
 // scraper-service クレートからインポート
@@ -25,16 +32,220 @@ use scraper_service::{
     ScrapeRequest as InternalScrapeRequest,
 };
 
+/// Bounded pool of warm [`InternalScraperService`] instances (each one owns a
+/// browser), reused across accounts within a job instead of constructing a
+/// fresh one per account. A checked-out instance is only returned to the
+/// pool after a successful scrape; one that failed is dropped so the next
+/// checkout starts from a clean browser instead of a possibly wedged one.
+pub struct ScraperPool {
+    idle: tokio::sync::Mutex<Vec<InternalScraperService>>,
+    permits: tokio::sync::Semaphore,
+}
+
+impl ScraperPool {
+    /// Create a pool that keeps at most `size` instances warm and limits
+    /// concurrent checkouts to `size`.
+    pub fn new(size: usize) -> Self {
+        Self {
+            idle: tokio::sync::Mutex::new(Vec::with_capacity(size)),
+            permits: tokio::sync::Semaphore::new(size),
+        }
+    }
+
+    /// Check out a warm instance, blocking until one of the `size` slots is
+    /// free. Reuses an idle instance if one is available, otherwise spins up
+    /// a new one.
+    async fn checkout(&self) -> PooledScraper<'_> {
+        let permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("ScraperPool semaphore is never closed");
+
+        let scraper = self
+            .idle
+            .lock()
+            .await
+            .pop()
+            .unwrap_or_else(InternalScraperService::new);
+
+        PooledScraper {
+            pool: self,
+            scraper: Some(scraper),
+            _permit: permit,
+        }
+    }
+}
+
+/// A checked-out [`InternalScraperService`], returned to its [`ScraperPool`]
+/// via [`PooledScraper::recycle`] rather than automatically on drop, since a
+/// failed scrape should not poison the pool with a broken browser instance.
+struct PooledScraper<'a> {
+    pool: &'a ScraperPool,
+    scraper: Option<InternalScraperService>,
+    _permit: tokio::sync::SemaphorePermit<'a>,
+}
+
+impl PooledScraper<'_> {
+    fn scraper(&mut self) -> &mut InternalScraperService {
+        self.scraper.as_mut().expect("scraper checked out exactly once")
+    }
+
+    async fn recycle(&mut self) {
+        if let Some(scraper) = self.scraper.take() {
+            self.pool.idle.lock().await.push(scraper);
+        }
+    }
+}
+
+/// Upper bound on the chunk size a `StreamDownload` client can request
+/// (`StreamDownloadZip` always sends fixed 64KB chunks), kept comfortably under the ~256KB message size most
+/// WebRTC DataChannel implementations handle without fragmenting.
+pub const STREAM_DOWNLOAD_MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Channel capacity for job progress events. Bounded so a subscriber that
+/// falls behind lags (and skips ahead) instead of leaking memory; generous
+/// enough to hold a full multi-account job's worth of events.
+const JOB_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Upper bound on the total `csv_content` bytes a single `GetJobResults`
+/// response will carry across all accounts combined. Without this, a job
+/// with many accounts each just under
+/// [`crate::job::state::MAX_RETAINED_CSV_BYTES`] could still add up to an
+/// unbounded response. Accounts beyond the budget get `truncated = true`
+/// and empty `csv_content`; `csv_path` is always still returned.
+const MAX_JOB_RESULTS_RESPONSE_BYTES: usize = 8 * 1024 * 1024;
+
 /// ETC Scraper gRPC service implementation
 pub struct EtcScraperService {
     config: GatewayConfig,
     job_queue: Arc<RwLock<JobQueue>>,
+    scraper_pool: Arc<ScraperPool>,
+    job_events: broadcast::Sender<JobEvent>,
+    shutdown: ShutdownCoordinator,
+    update_check: Arc<CachedUpdateCheck>,
 }
 
 impl EtcScraperService {
     /// Create a new EtcScraperService
     pub fn new(config: GatewayConfig, job_queue: Arc<RwLock<JobQueue>>) -> Self {
-        Self { config, job_queue }
+        Self::with_shutdown_coordinator(config, job_queue, ShutdownCoordinator::new())
+    }
+
+    /// Create a new EtcScraperService sharing `shutdown` with the server
+    /// driving graceful shutdown, so `scrape_multiple` stops accepting new
+    /// jobs and the running one is tracked as in-flight.
+    pub fn with_shutdown_coordinator(
+        config: GatewayConfig,
+        job_queue: Arc<RwLock<JobQueue>>,
+        shutdown: ShutdownCoordinator,
+    ) -> Self {
+        let scraper_pool = Arc::new(ScraperPool::new(config.scraper_pool_size.max(1)));
+        let (job_events, _) = broadcast::channel(JOB_EVENT_CHANNEL_CAPACITY);
+
+        let mut update_config = UpdateConfig::new_github(config.update_owner.clone(), config.update_repo.clone())
+            .with_github_token(config.update_github_token.clone())
+            .with_api_base_url(config.update_api_base_url.clone());
+        if let Some(ref manifest_url) = config.update_manifest_url {
+            update_config = update_config.with_manifest_url(manifest_url.clone());
+        }
+        let update_check = Arc::new(CachedUpdateCheck::new(AutoUpdater::new(update_config)));
+
+        Self {
+            config,
+            job_queue,
+            scraper_pool,
+            job_events,
+            shutdown,
+            update_check,
+        }
+    }
+
+    /// Resolve `session_folder` (falling back to the latest session when
+    /// empty, as `StreamDownload` and `StreamDownloadZip` both do) and list
+    /// the files directly inside it.
+    async fn list_session_files(&self, session_folder: String) -> Result<Vec<PathBuf>, Status> {
+        let client_supplied = !session_folder.is_empty();
+        let session_folder = if session_folder.is_empty() {
+            // まず現在のジョブからセッションフォルダを取得
+            let current_session = {
+                let queue = self.job_queue.read().await;
+                queue.current_job()
+                    .and_then(|job| job.get_session_folder())
+                    .map(|p| p.to_string_lossy().to_string())
+            };
+
+            if let Some(folder) = current_session {
+                folder
+            } else {
+                // ジョブがない場合は、ダウンロードディレクトリ内の最新フォルダを探す
+                let download_path = &self.config.download_path;
+                match find_latest_session_folder(download_path).await {
+                    Some(folder) => folder.to_string_lossy().to_string(),
+                    None => {
+                        // フォルダがない場合はデフォルトのダウンロードディレクトリを使用
+                        download_path.to_string_lossy().to_string()
+                    }
+                }
+            }
+        } else {
+            session_folder
+        };
+
+        tracing::info!("Listing session folder: {}", session_folder);
+
+        let session_path = PathBuf::from(&session_folder);
+        if !session_path.exists() {
+            return Err(Status::not_found(format!("Session folder not found: {}", session_folder)));
+        }
+
+        // 自動選択（最新セッション）は常にダウンロードディレクトリ配下なので対象外。
+        // クライアントが明示的に渡した session_folder のみ、`..` や絶対パスで
+        // ダウンロードディレクトリの外へ出ようとしていないか検証する。
+        if client_supplied {
+            self.ensure_within_download_path(&session_path)?;
+        }
+
+        let mut files: Vec<PathBuf> = vec![];
+        let mut entries = tokio::fs::read_dir(&session_path).await.map_err(|e| {
+            Status::internal(format!("Failed to read session folder: {}", e))
+        })?;
+
+        while let Some(entry) = entries.next_entry().await.map_err(|e| {
+            Status::internal(format!("Failed to read directory entry: {}", e))
+        })? {
+            let path = entry.path();
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+
+        if files.is_empty() {
+            return Err(Status::not_found("No files in session folder"));
+        }
+
+        Ok(files)
+    }
+
+    /// Reject a client-supplied `session_folder` that resolves (after
+    /// symlinks/`..` are canonicalized away) to somewhere outside
+    /// `config.download_path`. Guards `StreamDownload`/`StreamDownloadZip`
+    /// against path traversal via `../` or an absolute path.
+    fn ensure_within_download_path(&self, session_path: &Path) -> Result<(), Status> {
+        let canonical_session = session_path.canonicalize().map_err(|e| {
+            Status::internal(format!("Failed to resolve session folder: {}", e))
+        })?;
+        let canonical_download_root = self.config.download_path.canonicalize().map_err(|e| {
+            Status::internal(format!("Failed to resolve download directory: {}", e))
+        })?;
+
+        if !canonical_session.starts_with(&canonical_download_root) {
+            return Err(Status::permission_denied(
+                "session_folder must be inside the configured download directory",
+            ));
+        }
+
+        Ok(())
     }
 }
 
@@ -71,6 +282,10 @@ impl EtcScraper for EtcScraperService {
                 fail_count: job.fail_count() as i32,
                 current_account,
                 last_error: job.last_error.clone().unwrap_or_default(),
+                last_error_code: job
+                    .last_error_kind
+                    .map(to_proto_error_code)
+                    .unwrap_or(ScrapeErrorCode::None) as i32,
             })
         } else {
             Some(ProtoJobStatus {
@@ -82,6 +297,7 @@ impl EtcScraper for EtcScraperService {
                 fail_count: 0,
                 current_account: String::new(),
                 last_error: String::new(),
+                last_error_code: ScrapeErrorCode::None as i32,
             })
         };
 
@@ -94,11 +310,17 @@ impl EtcScraper for EtcScraperService {
                 .unwrap_or_default()
         };
 
+        // バックグラウンドでキャッシュされた結果を返すだけなので、毎回
+        // GitHub を叩くことはない（CachedUpdateCheck のTTLの間は再利用）
+        let latest_update = self.update_check.check().await;
+
         let response = HealthResponse {
             healthy: true,
             version: self.config.version.clone(),
             current_job,
             last_session_folder,
+            update_available: latest_update.is_some(),
+            latest_version: latest_update.map(|v| v.version).unwrap_or_default(),
         };
 
         Ok(Response::new(response))
@@ -173,30 +395,34 @@ impl EtcScraper for EtcScraperService {
 
         tracing::info!("Scrape requested for user: {}", req.user_id);
 
-        // scraper-service を使用してスクレイピング実行
-        let mut scraper = InternalScraperService::new();
+        // scraper-service を使用してスクレイピング実行（プールから取得）
+        let mut pooled = self.scraper_pool.checkout().await;
         let internal_req = InternalScrapeRequest::new(&req.user_id, &req.password)
             .with_download_path(&self.config.download_path)
             .with_headless(self.config.default_headless);
 
-        match scraper.call(internal_req).await {
+        match pooled.scraper().call(internal_req).await {
             Ok(result) => {
+                pooled.recycle().await;
                 let csv_content = String::from_utf8_lossy(&result.csv_content).to_string();
                 let response = ScrapeResponse {
                     success: true,
                     message: "Scrape completed successfully".to_string(),
                     csv_path: result.csv_path.to_string_lossy().to_string(),
                     csv_content,
+                    error_code: ScrapeErrorCode::None as i32,
                 };
                 Ok(Response::new(response))
             }
             Err(e) => {
+                let message = format!("Scrape failed: {}", e);
                 tracing::error!("Scrape failed for user {}: {}", req.user_id, e);
                 let response = ScrapeResponse {
                     success: false,
-                    message: format!("Scrape failed: {}", e),
+                    message,
                     csv_path: String::new(),
                     csv_content: String::new(),
+                    error_code: to_proto_error_code(ScraperErrorKind::classify(&e.to_string())) as i32,
                 };
                 Ok(Response::new(response))
             }
@@ -214,6 +440,16 @@ impl EtcScraper for EtcScraperService {
             return Err(Status::invalid_argument("At least one account is required"));
         }
 
+        if !req.callback_url.is_empty() {
+            if let Err(e) = crate::job::webhook::validate_callback_url(&req.callback_url) {
+                return Err(Status::invalid_argument(e));
+            }
+        }
+
+        if !self.shutdown.is_accepting_jobs() {
+            return Err(Status::unavailable("Server is shutting down; not accepting new jobs"));
+        }
+
         let account_count = req.accounts.len();
         tracing::info!("ScrapeMultiple requested with {} accounts (async mode)", account_count);
 
@@ -225,44 +461,121 @@ impl EtcScraper for EtcScraperService {
             .map(|a| (a.user_id.clone(), a.password.clone(), a.user_id.clone()))
             .collect();
 
-        // セッションフォルダを作成 (YYYYMMDD_HHMMSS形式)
-        let session_folder_name = Local::now().format("%Y%m%d_%H%M%S").to_string();
-        let session_folder = self.config.download_path.join(&session_folder_name);
+        // resume_session_folder が指定されていればそのフォルダを再利用し、
+        // 以前の実行結果を読み込んで完了済みアカウントをスキップする。
+        // 指定がなければ新しいセッションフォルダを作る
+        // (YYYYMMDD_HHMMSS_ランダムサフィックス形式。同じ秒に開始した
+        // 複数ジョブが同じフォルダに衝突しないようにするため)。
+        let (session_folder, prior_results) = if !req.resume_session_folder.is_empty() {
+            let resume_folder = PathBuf::from(&req.resume_session_folder);
+            let prior_results = crate::job::state::load_account_results(&resume_folder)
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Failed to load prior results from {:?}: {}",
+                        resume_folder, e
+                    );
+                    std::collections::HashMap::new()
+                });
+            (resume_folder, prior_results)
+        } else {
+            let session_folder_name = new_session_folder_name();
+            (self.config.download_path.join(&session_folder_name), std::collections::HashMap::new())
+        };
 
         // ディレクトリを作成
         if let Err(e) = tokio::fs::create_dir_all(&session_folder).await {
             tracing::error!("Failed to create session folder: {}", e);
             return Err(Status::internal(format!("Failed to create session folder: {}", e)));
         }
-        tracing::info!("Created session folder: {:?}", session_folder);
+        tracing::info!("Using session folder: {:?}", session_folder);
 
         // ジョブを作成してキューに追加
-        let job_id = {
+        // idempotency_key が既存ジョブに紐づいていれば、そのジョブIDをそのまま返す
+        // （重複ジョブ作成を防ぐ。再送されたリクエストの場合はここで完了する）
+        let idempotency_key = (!req.idempotency_key.is_empty()).then_some(req.idempotency_key.as_str());
+        let (job_id, is_new_job) = {
             let mut queue = self.job_queue.write().await;
-            let job_id = queue.create_job(
+            let (job_id, outcome) = queue.create_job_idempotent(
+                idempotency_key,
                 accounts,
                 self.config.download_path.clone(),
                 true, // headless mode
             );
+            let is_new_job = outcome == crate::job::CreateJobOutcome::New;
             // セッションフォルダを設定
-            if let Some(job) = queue.get_job_mut(&job_id) {
-                job.set_session_folder(session_folder.clone());
+            if is_new_job {
+                if let Some(job) = queue.get_job_mut(&job_id) {
+                    job.set_session_folder(session_folder.clone());
+                    // 以前の実行で Completed だったアカウントはその結果を
+                    // そのまま引き継ぎ、バックグラウンド処理で読み飛ばす
+                    for (user_id, prior) in &prior_results {
+                        if prior.status == JobStatus::Completed {
+                            if let Some(account) = job.get_account_result_mut(user_id) {
+                                *account = prior.clone();
+                            }
+                        }
+                    }
+                }
+                tracing::info!("Created job {} with {} accounts", job_id, account_count);
+            } else {
+                tracing::info!("Idempotency key matched existing job {}; skipping duplicate scrape", job_id);
             }
-            tracing::info!("Created job {} with {} accounts", job_id, account_count);
-            job_id
+            (job_id, is_new_job)
         };
 
+        if !is_new_job {
+            let response = ScrapeMultipleResponse {
+                results: vec![],
+                success_count: 0,
+                total_count: account_count as i32,
+                job_id,
+            };
+            return Ok(Response::new(response));
+        }
+
         // バックグラウンドでジョブを処理
         let job_queue = Arc::clone(&self.job_queue);
-        tokio::spawn(async move {
-            process_job_in_background(job_queue, job_id, session_folder).await;
-        });
+        let scraper_pool = Arc::clone(&self.scraper_pool);
+        let job_events = self.job_events.clone();
+        let retry_count = self.config.scrape_retry_count;
+        let retry_delay = self.config.scrape_retry_delay();
+        let account_timeout = self.config.scrape_account_timeout();
+        let callback_url = (!req.callback_url.is_empty()).then(|| req.callback_url.clone());
+        let webhook_config = self.config.webhook_config();
+        // Held until the background task below completes, so
+        // `ShutdownCoordinator::wait_for_drain` knows a job is still running.
+        let job_guard = self.shutdown.job_started();
+        // Carries the caller's `p2p_grpc` span (if any) into the background
+        // task, so job-processing logs still correlate to the originating
+        // request even though they run after the RPC has already returned.
+        let request_span = tracing::Span::current();
+        let background_job_id = job_id.clone();
+        tokio::spawn(
+            async move {
+                process_job_in_background(
+                    job_queue,
+                    scraper_pool,
+                    job_events,
+                    background_job_id,
+                    session_folder,
+                    retry_count,
+                    retry_delay,
+                    account_timeout,
+                    callback_url,
+                    webhook_config,
+                )
+                .await;
+                drop(job_guard);
+            }
+            .instrument(request_span),
+        );
 
-        // 即座にレスポンスを返す（results は空、処理は Health API でポーリング）
+        // 即座にレスポンスを返す（results は空、処理は job_id 経由の WatchJob か Health API でポーリング）
         let response = ScrapeMultipleResponse {
             results: vec![],
             success_count: 0,
             total_count: account_count as i32,
+            job_id,
         };
 
         Ok(Response::new(response))
@@ -327,63 +640,15 @@ impl EtcScraper for EtcScraperService {
     ) -> Result<Response<Self::StreamDownloadStream>, Status> {
         let req = request.into_inner();
 
-        // session_folderが空の場合は最新のセッションフォルダを自動選択
-        let session_folder = if req.session_folder.is_empty() {
-            // まず現在のジョブからセッションフォルダを取得
-            let current_session = {
-                let queue = self.job_queue.read().await;
-                queue.current_job()
-                    .and_then(|job| job.get_session_folder())
-                    .map(|p| p.to_string_lossy().to_string())
-            };
+        let files = self.list_session_files(req.session_folder).await?;
+        let total_files = files.len() as i32;
 
-            if let Some(folder) = current_session {
-                folder
-            } else {
-                // ジョブがない場合は、ダウンロードディレクトリ内の最新フォルダを探す
-                let download_path = &self.config.download_path;
-                match find_latest_session_folder(download_path).await {
-                    Some(folder) => folder.to_string_lossy().to_string(),
-                    None => {
-                        // フォルダがない場合はデフォルトのダウンロードディレクトリを使用
-                        download_path.to_string_lossy().to_string()
-                    }
-                }
-            }
+        // クライアント指定のチャンクサイズ（0の場合はデフォルト）を安全な上限でクランプする
+        let chunk_size = if req.chunk_size > 0 {
+            (req.chunk_size as usize).min(STREAM_DOWNLOAD_MAX_CHUNK_SIZE)
         } else {
-            req.session_folder
+            self.config.stream_download_chunk_size.min(STREAM_DOWNLOAD_MAX_CHUNK_SIZE)
         };
-
-        tracing::info!("StreamDownload requested for folder: {}", session_folder);
-
-        let session_path = std::path::PathBuf::from(&session_folder);
-        if !session_path.exists() {
-            return Err(Status::not_found(format!("Session folder not found: {}", session_folder)));
-        }
-
-        // List files in session folder
-        let mut files: Vec<std::path::PathBuf> = vec![];
-        let mut entries = tokio::fs::read_dir(&session_path).await.map_err(|e| {
-            Status::internal(format!("Failed to read session folder: {}", e))
-        })?;
-
-        while let Some(entry) = entries.next_entry().await.map_err(|e| {
-            Status::internal(format!("Failed to read directory entry: {}", e))
-        })? {
-            let path = entry.path();
-            if path.is_file() {
-                files.push(path);
-            }
-        }
-
-        if files.is_empty() {
-            return Err(Status::not_found("No files in session folder"));
-        }
-
-        let total_files = files.len() as i32;
-
-        // Create a stream that sends all files in chunks
-        let chunk_size = 32 * 1024; // 32KB chunks
         let stream = async_stream::try_stream! {
             for (file_index, file_path) in files.into_iter().enumerate() {
                 let filename = file_path
@@ -396,13 +661,8 @@ impl EtcScraper for EtcScraperService {
                 })?;
 
                 let total_size = content.len() as i64;
-                let chunks: Vec<_> = content.chunks(chunk_size).collect();
-                let total_chunks = chunks.len();
-
-                for (i, chunk) in chunks.into_iter().enumerate() {
-                    let offset = (i * chunk_size) as i64;
-                    let is_last_chunk = i + 1 == total_chunks;
 
+                for (offset, chunk, is_last_chunk) in chunk_with_offsets(&content, chunk_size) {
                     yield StreamDownloadChunk {
                         filename: filename.clone(),
                         data: chunk.to_vec(),
@@ -418,14 +678,398 @@ impl EtcScraper for EtcScraperService {
 
         Ok(Response::new(Box::pin(stream)))
     }
+
+    /// Stream type for StreamDownloadZip RPC
+    type StreamDownloadZipStream =
+        Pin<Box<dyn Stream<Item = Result<StreamDownloadZipChunk, Status>> + Send>>;
+
+    /// Stream a ZIP archive of every file in a session folder, so clients
+    /// don't have to stitch many per-file chunks together themselves. The
+    /// `zip` crate needs a `Write + Seek` sink to lay out its central
+    /// directory, so the archive is built into memory first and then
+    /// chunked out over the stream.
+    async fn stream_download_zip(
+        &self,
+        request: Request<StreamDownloadZipRequest>,
+    ) -> Result<Response<Self::StreamDownloadZipStream>, Status> {
+        let req = request.into_inner();
+
+        let files = self.list_session_files(req.session_folder).await?;
+
+        let zip_bytes = tokio::task::spawn_blocking(move || -> zip::result::ZipResult<Vec<u8>> {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+
+            for file_path in &files {
+                let filename = file_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                writer.start_file(filename, options)?;
+                let content = std::fs::read(file_path)?;
+                std::io::Write::write_all(&mut writer, &content)?;
+            }
+
+            Ok(writer.finish()?.into_inner())
+        })
+        .await
+        .map_err(|e| Status::internal(format!("ZIP build task failed: {}", e)))?
+        .map_err(|e| Status::internal(format!("Failed to build ZIP: {}", e)))?;
+
+        let chunk_size = 64 * 1024; // 64KB chunks
+        let stream = async_stream::stream! {
+            let total_size = zip_bytes.len() as i64;
+
+            for (offset, chunk, is_last_chunk) in chunk_with_offsets(&zip_bytes, chunk_size) {
+                yield Ok(StreamDownloadZipChunk {
+                    data: chunk.to_vec(),
+                    offset,
+                    total_size,
+                    is_last_chunk,
+                });
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Merge every CSV in a session folder into one, so clients don't have
+    /// to stitch per-account files together themselves after
+    /// `GetDownloadedFiles`. Column order can differ between files (each
+    /// account's scrape writes its own header); rows are defensively
+    /// remapped onto the union of all headers, in first-seen order.
+    async fn merge_session_csv(
+        &self,
+        request: Request<MergeSessionCsvRequest>,
+    ) -> Result<Response<MergeSessionCsvResponse>, Status> {
+        let req = request.into_inner();
+
+        let files = self.list_session_files(req.session_folder).await?;
+        let csv_files: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|path| {
+                path.extension()
+                    .map(|ext| ext.eq_ignore_ascii_case("csv"))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        if csv_files.is_empty() {
+            return Err(Status::not_found("No CSV files in session folder"));
+        }
+
+        let mut contents: Vec<(String, String)> = Vec::with_capacity(csv_files.len());
+        for path in &csv_files {
+            let bytes = tokio::fs::read(path).await.map_err(|e| {
+                Status::internal(format!("Failed to read file: {}", e))
+            })?;
+            let filename = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            contents.push((filename, String::from_utf8_lossy(&bytes).into_owned()));
+        }
+
+        let merged = merge_csv_content(&contents, req.include_source_column);
+
+        Ok(Response::new(MergeSessionCsvResponse {
+            content: merged.into_bytes(),
+            file_count: contents.len() as i32,
+        }))
+    }
+
+    /// Return structured results (including CSV content, not just a path)
+    /// for every account in a job. P2P clients have no filesystem access,
+    /// so `WatchJob`'s progress events aren't enough to retrieve the data
+    /// itself. Content that was too large to retain in memory (see
+    /// [`crate::job::state::MAX_RETAINED_CSV_BYTES`]) is re-read from
+    /// `csv_path` on demand instead, capped at the same size, and the
+    /// running total across all accounts is capped at
+    /// [`MAX_JOB_RESULTS_RESPONSE_BYTES`] - either cap leaves
+    /// `JobAccountResult::truncated` set so a client knows to fall back to
+    /// `csv_path` for the full content.
+    async fn get_job_results(
+        &self,
+        request: Request<GetJobResultsRequest>,
+    ) -> Result<Response<GetJobResultsResponse>, Status> {
+        let req = request.into_inner();
+        if req.job_id.is_empty() {
+            return Err(Status::invalid_argument("job_id is required"));
+        }
+
+        let (accounts, job_complete) = {
+            let queue = self.job_queue.read().await;
+            let job = queue
+                .get_job(&req.job_id)
+                .ok_or_else(|| Status::not_found(format!("Job not found: {}", req.job_id)))?;
+
+            let accounts: Vec<crate::job::AccountResult> = job.account_results();
+            (accounts, job.is_complete())
+        };
+
+        let mut results = Vec::with_capacity(accounts.len());
+        let mut response_bytes_used = 0usize;
+        for account in accounts {
+            let success = account.status == JobStatus::Completed;
+
+            let (csv_content, truncated) = if response_bytes_used >= MAX_JOB_RESULTS_RESPONSE_BYTES {
+                (Vec::new(), account.csv_content.is_some() || account.csv_path.is_some())
+            } else {
+                let remaining = MAX_JOB_RESULTS_RESPONSE_BYTES - response_bytes_used;
+                match account.csv_content {
+                    Some(content) => {
+                        let truncated = content.len() > remaining;
+                        let content = if truncated { Vec::new() } else { content };
+                        (content, truncated)
+                    }
+                    None => match &account.csv_path {
+                        Some(path) => read_capped_csv(path, remaining.min(MAX_RETAINED_CSV_BYTES)).await,
+                        None => (Vec::new(), false),
+                    },
+                }
+            };
+            response_bytes_used += csv_content.len();
+
+            results.push(JobAccountResult {
+                user_id: account.user_id,
+                success,
+                csv_path: account
+                    .csv_path
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                csv_content,
+                error_message: account.error_message.unwrap_or_default(),
+                error_code: account
+                    .error_kind
+                    .map(to_proto_error_code)
+                    .unwrap_or(ScrapeErrorCode::None) as i32,
+                attempts: account.attempts as i32,
+                truncated,
+            });
+        }
+
+        Ok(Response::new(GetJobResultsResponse {
+            job_id: req.job_id,
+            results,
+            job_complete,
+        }))
+    }
+
+    /// Stream type for WatchJob RPC
+    type WatchJobStream = Pin<Box<dyn Stream<Item = Result<ProtoJobEvent, Status>> + Send>>;
+
+    /// Stream progress events for a job instead of requiring clients to poll `Health`
+    async fn watch_job(
+        &self,
+        request: Request<WatchJobRequest>,
+    ) -> Result<Response<Self::WatchJobStream>, Status> {
+        let req = request.into_inner();
+        if req.job_id.is_empty() {
+            return Err(Status::invalid_argument("job_id is required"));
+        }
+
+        {
+            let queue = self.job_queue.read().await;
+            if queue.get_job(&req.job_id).is_none() {
+                return Err(Status::not_found(format!("Job not found: {}", req.job_id)));
+            }
+        }
+
+        let job_id = req.job_id;
+        let mut rx = self.job_events.subscribe();
+
+        let stream = async_stream::stream! {
+            loop {
+                match rx.recv().await {
+                    Ok(event) if event.job_id == job_id => {
+                        let is_completed = matches!(event.kind, JobEventKind::JobCompleted);
+                        yield Ok(to_proto_job_event(event));
+                        if is_completed {
+                            break;
+                        }
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Split `data` into `chunk_size`-sized pieces, pairing each with its byte
+/// offset and whether it's the last piece. Shared by `StreamDownload` and
+/// `StreamDownloadZip` so the offset/last-chunk bookkeeping only lives in
+/// one place.
+fn chunk_with_offsets(data: &[u8], chunk_size: usize) -> Vec<(i64, &[u8], bool)> {
+    let chunks: Vec<_> = data.chunks(chunk_size.max(1)).collect();
+    let total_chunks = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| ((i * chunk_size) as i64, chunk, i + 1 == total_chunks))
+        .collect()
+}
+
+/// Merge `files` (each a `(filename, csv_content)` pair) into a single CSV
+/// with one header line. Headers are matched by name across files and
+/// deduplicated in first-seen order, so files whose columns are in a
+/// different order (or a subset of) another file's still line up correctly;
+/// a row missing a given column gets an empty cell there. When
+/// `include_source_column` is set, an extra `source_file` column is
+/// appended holding the originating filename. Parsing is a plain
+/// comma-split with no quoting support, matching the CSV files this
+/// service itself generates.
+fn merge_csv_content(files: &[(String, String)], include_source_column: bool) -> String {
+    struct ParsedFile<'a> {
+        filename: &'a str,
+        header: Vec<&'a str>,
+        rows: Vec<Vec<&'a str>>,
+    }
+
+    let mut union_header: Vec<&str> = Vec::new();
+    let mut header_index: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    let mut parsed: Vec<ParsedFile> = Vec::with_capacity(files.len());
+
+    for (filename, content) in files {
+        let mut lines = content.lines();
+        let Some(header_line) = lines.next() else {
+            continue;
+        };
+        let header: Vec<&str> = header_line.split(',').map(|col| col.trim()).collect();
+        for &col in &header {
+            header_index.entry(col).or_insert_with(|| {
+                union_header.push(col);
+                union_header.len() - 1
+            });
+        }
+
+        let rows: Vec<Vec<&str>> = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split(',').collect())
+            .collect();
+
+        parsed.push(ParsedFile { filename, header, rows });
+    }
+
+    let mut out_header: Vec<&str> = union_header.clone();
+    if include_source_column {
+        out_header.push("source_file");
+    }
+    let mut out = out_header.join(",");
+    out.push('\n');
+
+    for file in &parsed {
+        for row in &file.rows {
+            let mut remapped = vec![""; union_header.len()];
+            for (col_index, &col) in file.header.iter().enumerate() {
+                if let (Some(&union_index), Some(&value)) = (header_index.get(col), row.get(col_index)) {
+                    remapped[union_index] = value;
+                }
+            }
+
+            out.push_str(&remapped.join(","));
+            if include_source_column {
+                out.push(',');
+                out.push_str(file.filename);
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Re-read a CSV file for [`EtcScraperService::get_job_results`], never
+/// returning more than `limit` bytes. Returns `(content, truncated)`;
+/// `truncated` is set both on a read error (the file may still exist; the
+/// client should fall back to `csv_path`) and when the file is larger than
+/// `limit`.
+async fn read_capped_csv(path: &std::path::Path, limit: usize) -> (Vec<u8>, bool) {
+    use tokio::io::AsyncReadExt;
+
+    let file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::warn!("get_job_results: failed to open {}: {}", path.display(), e);
+            return (Vec::new(), true);
+        }
+    };
+
+    // Read one byte past `limit` so we can tell a file that's exactly
+    // `limit` bytes apart from one that's larger, without reading the
+    // whole oversized file into memory first.
+    let mut buf = Vec::with_capacity(limit.min(MAX_RETAINED_CSV_BYTES) + 1);
+    if let Err(e) = file.take(limit as u64 + 1).read_to_end(&mut buf).await {
+        tracing::warn!("get_job_results: failed to read {}: {}", path.display(), e);
+        return (Vec::new(), true);
+    }
+
+    if buf.len() > limit {
+        (Vec::new(), true)
+    } else {
+        (buf, false)
+    }
+}
+
+/// Map a [`ScraperErrorKind`] onto its gRPC-facing representation, so
+/// `ScrapeResponse.error_code` / `JobStatus.last_error_code` stay in sync
+/// with the in-process classification instead of drifting out of step.
+fn to_proto_error_code(kind: ScraperErrorKind) -> ScrapeErrorCode {
+    match kind {
+        ScraperErrorKind::LoginFailed => ScrapeErrorCode::LoginFailed,
+        ScraperErrorKind::Timeout => ScrapeErrorCode::Timeout,
+        ScraperErrorKind::SiteUnavailable => ScrapeErrorCode::SiteUnavailable,
+        ScraperErrorKind::ParseFailed => ScrapeErrorCode::ParseFailed,
+        ScraperErrorKind::Io => ScrapeErrorCode::IoError,
+        ScraperErrorKind::Unknown => ScrapeErrorCode::UnknownError,
+    }
+}
+
+/// Map a [`JobEvent`] onto its gRPC-facing representation for `WatchJob`.
+fn to_proto_job_event(event: JobEvent) -> ProtoJobEvent {
+    let (event_type, user_id, message, error_code) = match event.kind {
+        JobEventKind::AccountStarted { user_id } => {
+            (JobEventType::AccountStarted, user_id, String::new(), ScrapeErrorCode::None)
+        }
+        JobEventKind::AccountSucceeded { user_id } => {
+            (JobEventType::AccountSucceeded, user_id, String::new(), ScrapeErrorCode::None)
+        }
+        JobEventKind::AccountFailed { user_id, error, kind } => {
+            (JobEventType::AccountFailed, user_id, error, to_proto_error_code(kind))
+        }
+        JobEventKind::JobCompleted => {
+            (JobEventType::JobCompleted, String::new(), String::new(), ScrapeErrorCode::None)
+        }
+    };
+
+    ProtoJobEvent {
+        job_id: event.job_id,
+        event_type: event_type as i32,
+        user_id,
+        message,
+        error_code: error_code as i32,
+    }
 }
 
 /// バックグラウンドでジョブを処理する関数
 async fn process_job_in_background(
     job_queue: Arc<RwLock<JobQueue>>,
+    scraper_pool: Arc<ScraperPool>,
+    job_events: broadcast::Sender<JobEvent>,
     job_id: String,
     session_folder: PathBuf,
+    retry_count: u32,
+    retry_delay: std::time::Duration,
+    account_timeout: std::time::Duration,
+    callback_url: Option<String>,
+    webhook_config: crate::job::WebhookConfig,
 ) {
+    let started_at = std::time::Instant::now();
     tracing::info!("Starting background job processing for {}", job_id);
 
     // ジョブを開始状態に設定
@@ -437,13 +1081,19 @@ async fn process_job_in_background(
         }
     }
 
-    // ジョブからアカウント情報を取得
+    // ジョブからアカウント情報を取得（resume_session_folder 経由で既に
+    // Completed 済みとしてマークされたアカウントはスキップする）
     let (accounts, headless) = {
         let queue = job_queue.read().await;
         if let Some(job) = queue.get_job(&job_id) {
             let accounts: Vec<(String, String)> = job
                 .account_order
                 .iter()
+                .filter(|user_id| {
+                    job.get_account_result(user_id)
+                        .map(|r| r.status != JobStatus::Completed)
+                        .unwrap_or(true)
+                })
                 .filter_map(|user_id| {
                     job.get_password(user_id).map(|pwd| (user_id.clone(), pwd.clone()))
                 })
@@ -451,6 +1101,7 @@ async fn process_job_in_background(
             (accounts, job.headless)
         } else {
             tracing::error!("Job {} not found", job_id);
+            metrics::histogram!("scrape_job_duration_seconds").record(started_at.elapsed().as_secs_f64());
             return;
         }
     };
@@ -459,25 +1110,80 @@ async fn process_job_in_background(
     for (idx, (user_id, password)) in accounts.iter().enumerate() {
         tracing::info!("Processing account {}/{}: {}", idx + 1, accounts.len(), user_id);
 
+        let _ = job_events.send(JobEvent {
+            job_id: job_id.clone(),
+            kind: JobEventKind::AccountStarted {
+                user_id: user_id.clone(),
+            },
+        });
+
         // 現在のアカウントインデックスを更新
         {
             let mut queue = job_queue.write().await;
             if let Some(job) = queue.get_job_mut(&job_id) {
                 job.current_account_index = idx;
-                // アカウントの状態を Running に設定
-                if let Some(account) = job.get_account_result_mut(user_id) {
-                    account.set_running();
-                }
             }
         }
 
-        // スクレイピング実行（セッションフォルダに保存）
-        let mut scraper = InternalScraperService::new();
-        let internal_req = InternalScrapeRequest::new(user_id, password)
-            .with_download_path(&session_folder)
-            .with_headless(headless);
+        // スクレイピング実行（セッションフォルダに保存、プールから取得したインスタンスを再利用）
+        // リトライ可能なエラー（タイムアウト・ネットワーク）のみ retry_count 回まで再試行する
+        let mut result = None;
+        for attempt in 0..=retry_count {
+            {
+                let mut queue = job_queue.write().await;
+                if let Some(job) = queue.get_job_mut(&job_id) {
+                    if let Some(account) = job.get_account_result_mut(user_id) {
+                        account.set_running();
+                    }
+                }
+            }
+
+            let attempt_result = {
+                let mut pooled = scraper_pool.checkout().await;
+                let internal_req = InternalScrapeRequest::new(user_id, password)
+                    .with_download_path(&session_folder)
+                    .with_headless(headless);
+
+                // A pooled instance that times out is never recycled (same
+                // as any other failed attempt above), so a hung browser
+                // isn't handed to the next account.
+                match tokio::time::timeout(account_timeout, pooled.scraper().call(internal_req)).await {
+                    Ok(r) => {
+                        if r.is_ok() {
+                            pooled.recycle().await;
+                        }
+                        r.map_err(|e| e.to_string())
+                    }
+                    Err(_) => Err(format!(
+                        "Timeout: account scrape exceeded {:?}",
+                        account_timeout
+                    )),
+                }
+            };
 
-        let result = scraper.call(internal_req).await;
+            let should_retry = attempt < retry_count
+                && attempt_result
+                    .as_ref()
+                    .err()
+                    .map(|e| ScraperErrorKind::classify(e).is_retryable())
+                    .unwrap_or(false);
+
+            result = Some(attempt_result);
+
+            if !should_retry {
+                break;
+            }
+
+            tracing::warn!(
+                "Retryable scrape error for {} (attempt {}/{}), retrying after {:?}",
+                user_id,
+                attempt + 1,
+                retry_count + 1,
+                retry_delay
+            );
+            tokio::time::sleep(retry_delay).await;
+        }
+        let result = result.expect("retry loop always runs at least once");
 
         // 結果を更新
         {
@@ -487,25 +1193,54 @@ async fn process_job_in_background(
                     match result {
                         Ok(scrape_result) => {
                             tracing::info!("Scrape succeeded for {}", user_id);
-                            account.set_completed(scrape_result.csv_path);
+                            account.set_completed(scrape_result.csv_path, scrape_result.csv_content);
+                            let _ = job_events.send(JobEvent {
+                                job_id: job_id.clone(),
+                                kind: JobEventKind::AccountSucceeded {
+                                    user_id: user_id.clone(),
+                                },
+                            });
                         }
                         Err(e) => {
                             let error_msg = format!("Scrape failed: {}", e);
+                            let kind = ScraperErrorKind::classify(&error_msg);
                             tracing::error!("{} for user {}", error_msg, user_id);
-                            account.set_failed(error_msg.clone());
-                            job.set_last_error(error_msg);
+                            account.set_failed(error_msg.clone(), kind);
+                            job.set_last_error(error_msg.clone(), kind);
+                            let _ = job_events.send(JobEvent {
+                                job_id: job_id.clone(),
+                                kind: JobEventKind::AccountFailed {
+                                    user_id: user_id.clone(),
+                                    error: error_msg,
+                                    kind,
+                                },
+                            });
                         }
                     }
                 }
                 job.update_overall_status();
             }
         }
+
+        // 進行状況をセッションフォルダに保存する（resume_session_folder で
+        // 再開した際に、完了済みアカウントを読み飛ばせるようにするため）
+        {
+            let queue = job_queue.read().await;
+            if let Some(job) = queue.get_job(&job_id) {
+                if let Err(e) = crate::job::state::write_account_results(
+                    &session_folder,
+                    &job.account_results(),
+                ) {
+                    tracing::warn!("Failed to persist job results to {:?}: {}", session_folder, e);
+                }
+            }
+        }
     }
 
     // ジョブ完了
-    {
+    let counts = {
         let mut queue = job_queue.write().await;
-        if let Some(job) = queue.get_job_mut(&job_id) {
+        let counts = queue.get_job_mut(&job_id).map(|job| {
             job.update_overall_status();
             tracing::info!(
                 "Job {} completed: {}/{} succeeded",
@@ -513,36 +1248,104 @@ async fn process_job_in_background(
                 job.success_count(),
                 job.total_count()
             );
-        }
+            (job.success_count(), job.total_count())
+        });
         queue.clear_current_job();
+        counts
+    };
+
+    metrics::histogram!("scrape_job_duration_seconds").record(started_at.elapsed().as_secs_f64());
+
+    if let (Some(callback_url), Some((success_count, total_count))) = (callback_url, counts) {
+        let payload = crate::job::webhook::JobCompletionPayload {
+            job_id: job_id.clone(),
+            success_count,
+            total_count,
+            session_folder,
+        };
+        crate::job::webhook::send_webhook(&callback_url, &payload, &webhook_config).await;
     }
+
+    let _ = job_events.send(JobEvent {
+        job_id,
+        kind: JobEventKind::JobCompleted,
+    });
 }
 
 /// ダウンロードディレクトリ内の最新のセッションフォルダを探す
-/// セッションフォルダは YYYYMMDD_HHMMSS 形式の名前を持つ
+/// セッションフォルダは YYYYMMDD_HHMMSS[_ランダムサフィックス] 形式の名前を持つ
 async fn find_latest_session_folder(download_path: &std::path::Path) -> Option<PathBuf> {
     if !download_path.exists() {
         return None;
     }
 
     let mut entries = tokio::fs::read_dir(download_path).await.ok()?;
-    let mut folders: Vec<(String, PathBuf)> = Vec::new();
+    let mut folders: Vec<(std::time::SystemTime, PathBuf)> = Vec::new();
 
     while let Ok(Some(entry)) = entries.next_entry().await {
         let path = entry.path();
-        if path.is_dir() {
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                // YYYYMMDD_HHMMSS 形式かどうかチェック (15文字)
-                if name.len() == 15 && name.chars().nth(8) == Some('_') {
-                    folders.push((name.to_string(), path));
-                }
-            }
+        if !path.is_dir() {
+            continue;
         }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !is_session_folder_name(name) {
+            continue;
+        }
+
+        // フォルダ名からタイムスタンプを解析できればそれを使う。
+        // 解析できない（想定外のフォーマット）場合は mtime にフォールバック
+        let timestamp = match session_folder_timestamp(name) {
+            Some(ts) => Some(ts),
+            None => entry.metadata().await.ok().and_then(|m| m.modified().ok()),
+        };
+
+        if let Some(timestamp) = timestamp {
+            folders.push((timestamp, path));
+        }
+    }
+
+    // 解析したタイムスタンプで最新のものを選ぶ。文字列の降順ソートだと、
+    // サフィックス付加やDST境界をまたぐケースで実際の時刻順と
+    // 一致しなくなるため
+    folders.into_iter().max_by_key(|(timestamp, _)| *timestamp).map(|(_, path)| path)
+}
+
+/// Parse the `YYYYMMDD_HHMMSS` prefix of a session folder name (see
+/// [`is_session_folder_name`]) as the local wall-clock time it was created
+/// at. Returns `None` if the prefix isn't a valid date/time or is
+/// ambiguous/nonexistent under the local timezone (e.g. a DST transition),
+/// in which case [`find_latest_session_folder`] falls back to the
+/// directory's mtime.
+fn session_folder_timestamp(name: &str) -> Option<std::time::SystemTime> {
+    let naive = chrono::NaiveDateTime::parse_from_str(&name[..15], "%Y%m%d_%H%M%S").ok()?;
+    Local.from_local_datetime(&naive).single().map(Into::into)
+}
+
+/// Check whether `name` looks like a `scrape_multiple` session folder:
+/// `YYYYMMDD_HHMMSS` (15 chars, the legacy format), optionally followed by
+/// `_` and a random suffix (see [`new_session_folder_name`]) to keep two
+/// jobs started in the same second from colliding into one folder.
+fn is_session_folder_name(name: &str) -> bool {
+    let chars: Vec<char> = name.chars().collect();
+    if chars.len() < 15 {
+        return false;
     }
+    let timestamp_ok = chars[0..8].iter().all(|c| c.is_ascii_digit())
+        && chars[8] == '_'
+        && chars[9..15].iter().all(|c| c.is_ascii_digit());
+    timestamp_ok && (chars.len() == 15 || chars[15] == '_')
+}
 
-    // 名前でソートして最新のものを返す（降順）
-    folders.sort_by(|a, b| b.0.cmp(&a.0));
-    folders.into_iter().next().map(|(_, path)| path)
+/// Build a unique session folder name for a new `scrape_multiple` job:
+/// `YYYYMMDD_HHMMSS_<8 hex chars>`. Two jobs created within the same
+/// second would otherwise both resolve to the plain timestamp and end up
+/// sharing (and interleaving files in) one folder.
+fn new_session_folder_name() -> String {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let suffix = Uuid::new_v4().simple().to_string();
+    format!("{}_{}", timestamp, &suffix[..8])
 }
 
 /// Check if a Windows user session is active
@@ -669,3 +1472,383 @@ fn check_chrome_available() -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_with_offsets_reassembles_regardless_of_chunk_size() {
+        let data: Vec<u8> = (0..10_000u32).map(|n| (n % 256) as u8).collect();
+
+        for chunk_size in [1, 7, 64, 1024, 32 * 1024, 1_000_000] {
+            let chunks = chunk_with_offsets(&data, chunk_size);
+
+            let mut reassembled = Vec::with_capacity(data.len());
+            for (offset, chunk, _) in &chunks {
+                assert_eq!(*offset, reassembled.len() as i64);
+                reassembled.extend_from_slice(chunk);
+            }
+
+            assert_eq!(reassembled, data, "mismatch for chunk_size={}", chunk_size);
+            assert!(chunks.last().unwrap().2, "last chunk not marked as last for chunk_size={}", chunk_size);
+            assert!(
+                chunks[..chunks.len() - 1].iter().all(|(_, _, is_last)| !is_last),
+                "non-final chunk marked as last for chunk_size={}",
+                chunk_size
+            );
+        }
+    }
+
+    #[test]
+    fn test_chunk_with_offsets_empty_data() {
+        let chunks = chunk_with_offsets(&[], 1024);
+        assert!(chunks.is_empty());
+    }
+
+    fn test_service(download_path: PathBuf) -> EtcScraperService {
+        let mut config = GatewayConfig::default();
+        config.download_path = download_path;
+        EtcScraperService::new(config, Arc::new(RwLock::new(JobQueue::new())))
+    }
+
+    #[tokio::test]
+    async fn test_list_session_files_accepts_folder_inside_download_path() {
+        let download_root = tempfile::tempdir().unwrap();
+        let session_dir = download_root.path().join("session-1");
+        tokio::fs::create_dir_all(&session_dir).await.unwrap();
+        tokio::fs::write(session_dir.join("data.csv"), b"a,b,c").await.unwrap();
+
+        let service = test_service(download_root.path().to_path_buf());
+        let files = service
+            .list_session_files(session_dir.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(files.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_session_files_rejects_traversal_outside_download_path() {
+        let download_root = tempfile::tempdir().unwrap();
+        let allowed_session = download_root.path().join("session-1");
+        tokio::fs::create_dir_all(&allowed_session).await.unwrap();
+        tokio::fs::write(allowed_session.join("data.csv"), b"a,b,c").await.unwrap();
+
+        let outside_dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(outside_dir.path().join("secret.txt"), b"nope").await.unwrap();
+
+        let service = test_service(download_root.path().to_path_buf());
+        let err = service
+            .list_session_files(outside_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_list_session_files_rejects_dot_dot_traversal() {
+        let download_root = tempfile::tempdir().unwrap();
+        let session_dir = download_root.path().join("session-1");
+        tokio::fs::create_dir_all(&session_dir).await.unwrap();
+        tokio::fs::write(session_dir.join("data.csv"), b"a,b,c").await.unwrap();
+
+        // Sibling directory outside of download_root, reached via `..`
+        let sibling = download_root.path().join("..").join(
+            download_root.path().file_name().unwrap().to_str().unwrap().to_string() + "-escaped",
+        );
+        tokio::fs::create_dir_all(&sibling).await.unwrap();
+        tokio::fs::write(sibling.join("secret.txt"), b"nope").await.unwrap();
+
+        let traversal_path = session_dir.join("..").join("..").join(
+            sibling.file_name().unwrap().to_str().unwrap(),
+        );
+
+        let service = test_service(download_root.path().to_path_buf());
+        let err = service
+            .list_session_files(traversal_path.to_string_lossy().to_string())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        tokio::fs::remove_dir_all(&sibling).await.ok();
+    }
+
+    #[test]
+    fn test_new_session_folder_name_is_unique_for_near_simultaneous_jobs() {
+        let a = new_session_folder_name();
+        let b = new_session_folder_name();
+
+        assert_ne!(a, b, "two jobs started in the same second must not collide");
+        assert!(is_session_folder_name(&a));
+        assert!(is_session_folder_name(&b));
+    }
+
+    #[test]
+    fn test_is_session_folder_name_accepts_legacy_and_new_formats() {
+        assert!(is_session_folder_name("20240101_120000"));
+        assert!(is_session_folder_name("20240101_120000_a1b2c3d4"));
+        assert!(!is_session_folder_name("not-a-session-folder"));
+        assert!(!is_session_folder_name("20240101120000"));
+    }
+
+    #[tokio::test]
+    async fn test_find_latest_session_folder_picks_newest_by_timestamp_regardless_of_suffix() {
+        let download_root = tempfile::tempdir().unwrap();
+        tokio::fs::create_dir_all(download_root.path().join("20240101_120000_aaaaaaaa")).await.unwrap();
+        tokio::fs::create_dir_all(download_root.path().join("20240102_090000_bbbbbbbb")).await.unwrap();
+
+        let latest = find_latest_session_folder(download_root.path()).await.unwrap();
+
+        assert_eq!(latest.file_name().unwrap(), "20240102_090000_bbbbbbbb");
+    }
+
+    #[tokio::test]
+    async fn test_find_latest_session_folder_ignores_non_session_directories() {
+        let download_root = tempfile::tempdir().unwrap();
+        tokio::fs::create_dir_all(download_root.path().join("not-a-session")).await.unwrap();
+        tokio::fs::write(download_root.path().join("some-file.txt"), b"x").await.unwrap();
+        tokio::fs::create_dir_all(download_root.path().join("20240101_120000")).await.unwrap();
+
+        let latest = find_latest_session_folder(download_root.path()).await.unwrap();
+
+        assert_eq!(latest.file_name().unwrap(), "20240101_120000");
+    }
+
+    #[tokio::test]
+    async fn test_find_latest_session_folder_falls_back_to_mtime_when_unparseable() {
+        let download_root = tempfile::tempdir().unwrap();
+
+        // 数字の並びとしては is_session_folder_name を通るが、実在しない
+        // 日時なので NaiveDateTime::parse_from_str は失敗し、mtime で
+        // フォールバック判定される
+        let older = download_root.path().join("20240101_120000");
+        tokio::fs::create_dir_all(&older).await.unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let invalid_but_newer = download_root.path().join("99999999_999999");
+        tokio::fs::create_dir_all(&invalid_but_newer).await.unwrap();
+
+        assert!(session_folder_timestamp("99999999_999999").is_none());
+
+        let latest = find_latest_session_folder(download_root.path()).await.unwrap();
+
+        assert_eq!(latest.file_name().unwrap(), "99999999_999999");
+    }
+
+    #[test]
+    fn test_merge_csv_content_reorders_differing_columns() {
+        let files = vec![
+            ("a.csv".to_string(), "date,amount\n2024-01-01,100\n".to_string()),
+            ("b.csv".to_string(), "amount,date\n200,2024-01-02\n".to_string()),
+        ];
+
+        let merged = merge_csv_content(&files, false);
+
+        assert_eq!(merged, "date,amount\n2024-01-01,100\n2024-01-02,200\n");
+    }
+
+    #[test]
+    fn test_merge_csv_content_fills_missing_columns() {
+        let files = vec![
+            ("a.csv".to_string(), "date,amount,note\n2024-01-01,100,ok\n".to_string()),
+            ("b.csv".to_string(), "date,amount\n2024-01-02,200\n".to_string()),
+        ];
+
+        let merged = merge_csv_content(&files, false);
+
+        assert_eq!(merged, "date,amount,note\n2024-01-01,100,ok\n2024-01-02,200,\n");
+    }
+
+    #[test]
+    fn test_merge_csv_content_appends_source_column() {
+        let files = vec![
+            ("a.csv".to_string(), "date,amount\n2024-01-01,100\n".to_string()),
+            ("b.csv".to_string(), "date,amount\n2024-01-02,200\n".to_string()),
+        ];
+
+        let merged = merge_csv_content(&files, true);
+
+        assert_eq!(
+            merged,
+            "date,amount,source_file\n2024-01-01,100,a.csv\n2024-01-02,200,b.csv\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_session_csv_ignores_non_csv_files() {
+        let download_root = tempfile::tempdir().unwrap();
+        let session_dir = download_root.path().join("session-1");
+        tokio::fs::create_dir_all(&session_dir).await.unwrap();
+        tokio::fs::write(session_dir.join("a.csv"), "date,amount\n2024-01-01,100\n").await.unwrap();
+        tokio::fs::write(session_dir.join("notes.txt"), "ignore me").await.unwrap();
+
+        let service = test_service(download_root.path().to_path_buf());
+        let response = service
+            .merge_session_csv(Request::new(MergeSessionCsvRequest {
+                session_folder: session_dir.to_string_lossy().to_string(),
+                include_source_column: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.file_count, 1);
+        assert_eq!(
+            String::from_utf8(response.content).unwrap(),
+            "date,amount\n2024-01-01,100\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_session_csv_not_found_without_csv_files() {
+        let download_root = tempfile::tempdir().unwrap();
+        let session_dir = download_root.path().join("session-1");
+        tokio::fs::create_dir_all(&session_dir).await.unwrap();
+        tokio::fs::write(session_dir.join("notes.txt"), "ignore me").await.unwrap();
+
+        let service = test_service(download_root.path().to_path_buf());
+        let err = service
+            .merge_session_csv(Request::new(MergeSessionCsvRequest {
+                session_folder: session_dir.to_string_lossy().to_string(),
+                include_source_column: false,
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_get_job_results_returns_inline_content() {
+        let download_root = tempfile::tempdir().unwrap();
+        let job_queue = Arc::new(RwLock::new(JobQueue::new()));
+        let job_id = {
+            let mut queue = job_queue.write().await;
+            let job_id = queue.create_job(
+                vec![("user1".to_string(), "pass1".to_string(), "User One".to_string())],
+                download_root.path().to_path_buf(),
+                true,
+            );
+            let job = queue.get_job_mut(&job_id).unwrap();
+            job.get_account_result_mut("user1").unwrap().set_completed(
+                download_root.path().join("user1.csv"),
+                b"date,amount\n2024-01-01,100\n".to_vec(),
+            );
+            job_id
+        };
+
+        let mut config = GatewayConfig::default();
+        config.download_path = download_root.path().to_path_buf();
+        let service = EtcScraperService::new(config, job_queue);
+
+        let response = service
+            .get_job_results(Request::new(GetJobResultsRequest { job_id: job_id.clone() }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.job_id, job_id);
+        assert_eq!(response.results.len(), 1);
+        assert!(response.results[0].success);
+        assert_eq!(response.results[0].csv_content, b"date,amount\n2024-01-01,100\n");
+    }
+
+    #[tokio::test]
+    async fn test_get_job_results_caps_oversized_disk_read_and_flags_truncated() {
+        let download_root = tempfile::tempdir().unwrap();
+        let csv_path = download_root.path().join("user1.csv");
+        let oversized = vec![b'x'; crate::job::state::MAX_RETAINED_CSV_BYTES + 1];
+        tokio::fs::write(&csv_path, &oversized).await.unwrap();
+
+        let job_queue = Arc::new(RwLock::new(JobQueue::new()));
+        let job_id = {
+            let mut queue = job_queue.write().await;
+            let job_id = queue.create_job(
+                vec![("user1".to_string(), "pass1".to_string(), "User One".to_string())],
+                download_root.path().to_path_buf(),
+                true,
+            );
+            let job = queue.get_job_mut(&job_id).unwrap();
+            job.get_account_result_mut("user1")
+                .unwrap()
+                .set_completed(csv_path, oversized.clone());
+            job_id
+        };
+
+        let mut config = GatewayConfig::default();
+        config.download_path = download_root.path().to_path_buf();
+        let service = EtcScraperService::new(config, job_queue);
+
+        let response = service
+            .get_job_results(Request::new(GetJobResultsRequest { job_id }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        // Too large even to retain inline, and too large for the capped
+        // disk re-read too - the RPC reports it as truncated instead of
+        // reading an unbounded amount of the file into memory.
+        assert!(response.results[0].csv_content.is_empty());
+        assert!(response.results[0].truncated);
+    }
+
+    #[tokio::test]
+    async fn test_get_job_results_caps_aggregate_response_across_accounts() {
+        // Each account's content is inline-retainable on its own (exactly
+        // MAX_RETAINED_CSV_BYTES), but enough of them together exceed
+        // MAX_JOB_RESULTS_RESPONSE_BYTES, so accounts past the aggregate
+        // budget must have their content dropped from the response even
+        // though nothing is wrong with any single one of them.
+        let account_count = (MAX_JOB_RESULTS_RESPONSE_BYTES / crate::job::state::MAX_RETAINED_CSV_BYTES) + 1;
+        let download_root = tempfile::tempdir().unwrap();
+        let accounts: Vec<(String, String, String)> = (0..account_count)
+            .map(|i| (format!("user{}", i), "pass".to_string(), format!("User {}", i)))
+            .collect();
+
+        let job_queue = Arc::new(RwLock::new(JobQueue::new()));
+        let job_id = {
+            let mut queue = job_queue.write().await;
+            let job_id = queue.create_job(accounts, download_root.path().to_path_buf(), true);
+            let job = queue.get_job_mut(&job_id).unwrap();
+            let content = vec![b'x'; crate::job::state::MAX_RETAINED_CSV_BYTES];
+            for i in 0..account_count {
+                let user_id = format!("user{}", i);
+                let path = download_root.path().join(format!("{}.csv", user_id));
+                job.get_account_result_mut(&user_id).unwrap().set_completed(path, content.clone());
+            }
+            job_id
+        };
+
+        let mut config = GatewayConfig::default();
+        config.download_path = download_root.path().to_path_buf();
+        let service = EtcScraperService::new(config, job_queue);
+
+        let response = service
+            .get_job_results(Request::new(GetJobResultsRequest { job_id }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let last = response.results.last().unwrap();
+        assert!(last.csv_content.is_empty());
+        assert!(last.truncated);
+        assert!(!response.results[0].csv_content.is_empty());
+        assert!(!response.results[0].truncated);
+    }
+
+    #[tokio::test]
+    async fn test_get_job_results_not_found_for_unknown_job() {
+        let service = test_service(tempfile::tempdir().unwrap().path().to_path_buf());
+
+        let err = service
+            .get_job_results(Request::new(GetJobResultsRequest { job_id: "nope".to_string() }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.code(), tonic::Code::NotFound);
+    }
+}
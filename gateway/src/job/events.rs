@@ -0,0 +1,33 @@
+//! Progress events for observing a running job without polling `Health`.
+
+use crate::scraper::ScraperErrorKind;
+
+/// A single state change published while a job runs, broadcast to any
+/// `WatchJob` subscribers so they get pushed updates instead of having to
+/// poll for them.
+#[derive(Debug, Clone)]
+pub struct JobEvent {
+    /// The job this event belongs to
+    pub job_id: String,
+    /// What happened
+    pub kind: JobEventKind,
+}
+
+/// The kinds of progress a [`JobEvent`] can report
+#[derive(Debug, Clone)]
+pub enum JobEventKind {
+    /// An account has started scraping; sent once per account, before its
+    /// first attempt (retries don't send another)
+    AccountStarted { user_id: String },
+    /// An account finished scraping successfully
+    AccountSucceeded { user_id: String },
+    /// An account exhausted its retries and is marked failed
+    AccountFailed {
+        user_id: String,
+        error: String,
+        kind: ScraperErrorKind,
+    },
+    /// All accounts in the job have been processed; no further events for
+    /// this job_id will follow
+    JobCompleted,
+}
@@ -1,5 +1,11 @@
+pub mod events;
+pub mod shutdown;
 pub mod state;
 pub mod queue;
+pub mod webhook;
 
+pub use events::{JobEvent, JobEventKind};
+pub use shutdown::ShutdownCoordinator;
 pub use state::{AccountResult, JobState, JobStatus};
-pub use queue::JobQueue;
+pub use queue::{CreateJobOutcome, JobCheckpoint, JobQueue};
+pub use webhook::WebhookConfig;
@@ -1,11 +1,41 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::state::{JobState, JobStatus};
 
+/// Minimal, serializable snapshot of a [`JobState`] written by
+/// [`JobQueue::checkpoint`] on shutdown. `JobState` itself carries
+/// `Instant` timestamps, which have no stable serialized form, so the
+/// checkpoint captures only what an operator needs to tell what was left
+/// in flight when the process stopped.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobCheckpoint {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub completed_count: usize,
+    pub total_count: usize,
+    pub session_folder: Option<PathBuf>,
+    pub last_error: Option<String>,
+}
+
+/// How long an idempotency key stays valid after the job it created was
+/// recorded, before `create_job_idempotent` treats it as expired and lets a
+/// new job be created for it again.
+const IDEMPOTENCY_KEY_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Whether [`JobQueue::create_job_idempotent`] created a brand new job or
+/// handed back one matched by idempotency key. Returned explicitly instead
+/// of leaving callers to re-derive it from unrelated job state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreateJobOutcome {
+    New,
+    Existing,
+}
+
 /// Job queue for managing multiple scrape jobs
 #[derive(Debug, Default)]
 pub struct JobQueue {
@@ -15,6 +45,10 @@ pub struct JobQueue {
     pending: Vec<String>,
     /// Currently running job ID
     current_job_id: Option<String>,
+    /// Idempotency key -> (job_id, recorded_at). Lets a retried
+    /// `scrape_multiple` call with the same key be handed back the
+    /// original job instead of starting a duplicate scrape.
+    idempotency_keys: HashMap<String, (String, Instant)>,
 }
 
 impl JobQueue {
@@ -40,6 +74,46 @@ impl JobQueue {
         job_id
     }
 
+    /// Create a new job as [`JobQueue::create_job`] does, but if
+    /// `idempotency_key` is `Some` and matches a key recorded by a previous
+    /// (unexpired) call, return that job's id instead of creating a
+    /// duplicate. Used to make `scrape_multiple` safe to retry. The
+    /// returned [`CreateJobOutcome`] tells the caller which case happened,
+    /// so it doesn't have to re-derive "was this job just created" from
+    /// unrelated job state.
+    pub fn create_job_idempotent(
+        &mut self,
+        idempotency_key: Option<&str>,
+        accounts: Vec<(String, String, String)>,
+        download_path: PathBuf,
+        headless: bool,
+    ) -> (String, CreateJobOutcome) {
+        if let Some(key) = idempotency_key {
+            if let Some(existing_job_id) = self.job_for_idempotency_key(key) {
+                return (existing_job_id.to_string(), CreateJobOutcome::Existing);
+            }
+        }
+
+        let job_id = self.create_job(accounts, download_path, headless);
+
+        if let Some(key) = idempotency_key {
+            self.idempotency_keys.insert(key.to_string(), (job_id.clone(), Instant::now()));
+        }
+
+        (job_id, CreateJobOutcome::New)
+    }
+
+    /// Look up the job recorded for `idempotency_key`, if any, treating
+    /// entries older than [`IDEMPOTENCY_KEY_TTL_SECS`] as expired.
+    fn job_for_idempotency_key(&self, idempotency_key: &str) -> Option<&str> {
+        let (job_id, recorded_at) = self.idempotency_keys.get(idempotency_key)?;
+        if recorded_at.elapsed().as_secs() < IDEMPOTENCY_KEY_TTL_SECS {
+            Some(job_id.as_str())
+        } else {
+            None
+        }
+    }
+
     /// Get a job by ID
     pub fn get_job(&self, job_id: &str) -> Option<&JobState> {
         self.jobs.get(job_id)
@@ -70,13 +144,16 @@ impl JobQueue {
         self.pending.len()
     }
 
-    /// Remove completed jobs older than the specified duration
+    /// Remove completed jobs older than the specified duration, and any
+    /// idempotency keys that have passed [`IDEMPOTENCY_KEY_TTL_SECS`].
     pub fn cleanup_old_jobs(&mut self, max_age_secs: u64) {
         let now = Instant::now();
         self.jobs.retain(|_, job| {
             let age = now.duration_since(job.created_at).as_secs();
             age < max_age_secs
         });
+        self.idempotency_keys
+            .retain(|_, (_, recorded_at)| recorded_at.elapsed().as_secs() < IDEMPOTENCY_KEY_TTL_SECS);
     }
 
     /// Get the currently running job ID
@@ -133,6 +210,28 @@ impl JobQueue {
             None
         }
     }
+
+    /// Write a snapshot of every job to `path` as JSON, so an operator can
+    /// see what was in flight after a shutdown. See [`JobCheckpoint`] for
+    /// what's captured.
+    pub fn checkpoint(&self, path: &Path) -> std::io::Result<()> {
+        let snapshot: Vec<JobCheckpoint> = self
+            .jobs
+            .values()
+            .map(|job| JobCheckpoint {
+                job_id: job.job_id.clone(),
+                status: job.status,
+                completed_count: job.completed_count(),
+                total_count: job.total_count(),
+                session_folder: job.session_folder.clone(),
+                last_error: job.last_error.clone(),
+            })
+            .collect();
+
+        let json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
 }
 
 #[cfg(test)]
@@ -166,4 +265,68 @@ mod tests {
         queue.mark_started(&job_id);
         assert_eq!(queue.pending_count(), 0);
     }
+
+    #[test]
+    fn test_create_job_idempotent_returns_existing_job_for_same_key() {
+        let mut queue = JobQueue::new();
+        let accounts = vec![
+            ("user1".to_string(), "pass1".to_string(), "User One".to_string()),
+        ];
+
+        let (first_job_id, first_outcome) = queue.create_job_idempotent(
+            Some("retry-key"),
+            accounts.clone(),
+            PathBuf::from("./downloads"),
+            true,
+        );
+        let (second_job_id, second_outcome) = queue.create_job_idempotent(
+            Some("retry-key"),
+            accounts,
+            PathBuf::from("./downloads"),
+            true,
+        );
+
+        assert_eq!(first_job_id, second_job_id);
+        assert_eq!(first_outcome, CreateJobOutcome::New);
+        assert_eq!(second_outcome, CreateJobOutcome::Existing);
+        assert_eq!(queue.all_job_ids().len(), 1);
+    }
+
+    #[test]
+    fn test_create_job_idempotent_without_key_always_creates_new_job() {
+        let mut queue = JobQueue::new();
+        let accounts = vec![
+            ("user1".to_string(), "pass1".to_string(), "User One".to_string()),
+        ];
+
+        let (first_job_id, first_outcome) =
+            queue.create_job_idempotent(None, accounts.clone(), PathBuf::from("./downloads"), true);
+        let (second_job_id, second_outcome) =
+            queue.create_job_idempotent(None, accounts, PathBuf::from("./downloads"), true);
+
+        assert_ne!(first_job_id, second_job_id);
+        assert_eq!(first_outcome, CreateJobOutcome::New);
+        assert_eq!(second_outcome, CreateJobOutcome::New);
+        assert_eq!(queue.all_job_ids().len(), 2);
+    }
+
+    #[test]
+    fn test_checkpoint_writes_json_snapshot_of_all_jobs() {
+        let mut queue = JobQueue::new();
+        let accounts = vec![
+            ("user1".to_string(), "pass1".to_string(), "User One".to_string()),
+        ];
+        let job_id = queue.create_job(accounts, PathBuf::from("./downloads"), true);
+
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint_path = dir.path().join("job_queue_checkpoint.json");
+        queue.checkpoint(&checkpoint_path).unwrap();
+
+        let contents = std::fs::read_to_string(&checkpoint_path).unwrap();
+        let snapshot: Vec<JobCheckpoint> = serde_json::from_str(&contents).unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].job_id, job_id);
+        assert_eq!(snapshot[0].status, JobStatus::Queued);
+        assert_eq!(snapshot[0].total_count, 1);
+    }
 }
@@ -0,0 +1,178 @@
+//! Coordinates graceful shutdown of background scrape jobs.
+//!
+//! On shutdown signal, `run_server` stops accepting new `scrape_multiple`
+//! jobs, gives any job already running a grace period to reach a safe
+//! point, and checkpoints the [`crate::job::JobQueue`] so an operator can
+//! tell what was in flight when the process stopped. Without this, a job
+//! abandoned mid-scrape can leave a partially-written session folder
+//! behind.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Shared handle for coordinating shutdown across the gRPC service and its
+/// background job tasks. Cheap to clone; every clone refers to the same
+/// underlying state.
+#[derive(Clone, Default)]
+pub struct ShutdownCoordinator {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    accepting_jobs: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+impl ShutdownCoordinator {
+    /// Create a coordinator that is accepting jobs with none in flight.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                accepting_jobs: AtomicBool::new(true),
+                in_flight: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Whether new `scrape_multiple` jobs should be accepted.
+    pub fn is_accepting_jobs(&self) -> bool {
+        self.inner.accepting_jobs.load(Ordering::SeqCst)
+    }
+
+    /// Stop accepting new jobs. Jobs already running are unaffected.
+    pub fn stop_accepting_jobs(&self) {
+        self.inner.accepting_jobs.store(false, Ordering::SeqCst);
+    }
+
+    /// Register that a background job has started processing. Returns a
+    /// guard that marks it finished when dropped, regardless of how the
+    /// background task exits (success, error, or panic).
+    pub fn job_started(&self) -> JobGuard {
+        self.inner.in_flight.fetch_add(1, Ordering::SeqCst);
+        JobGuard {
+            coordinator: self.clone(),
+        }
+    }
+
+    /// Number of background jobs currently running.
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no background jobs are in flight, or `grace` elapses,
+    /// whichever comes first. Returns `true` if everything drained in time.
+    pub async fn wait_for_drain(&self, grace: Duration) -> bool {
+        tokio::time::timeout(grace, async {
+            while self.in_flight() > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+/// RAII guard returned by [`ShutdownCoordinator::job_started`].
+pub struct JobGuard {
+    coordinator: ShutdownCoordinator,
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        self.coordinator.inner.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Run the full shutdown sequence: stop accepting new jobs, wait up to
+/// `grace` for the running one to finish, then write a [`super::JobQueue`]
+/// checkpoint to `checkpoint_path` so an operator can see what was left
+/// in flight.
+pub async fn shutdown(
+    coordinator: &ShutdownCoordinator,
+    job_queue: &tokio::sync::RwLock<super::JobQueue>,
+    grace: Duration,
+    checkpoint_path: &Path,
+) {
+    coordinator.stop_accepting_jobs();
+
+    if coordinator.wait_for_drain(grace).await {
+        tracing::info!("All background jobs drained before shutdown");
+    } else {
+        tracing::warn!(
+            "Shutdown grace period ({:?}) elapsed with {} job(s) still in flight",
+            grace,
+            coordinator.in_flight()
+        );
+    }
+
+    let queue = job_queue.read().await;
+    match queue.checkpoint(checkpoint_path) {
+        Ok(()) => tracing::info!("Wrote job queue checkpoint to {}", checkpoint_path.display()),
+        Err(e) => tracing::error!(
+            "Failed to write job queue checkpoint to {}: {}",
+            checkpoint_path.display(),
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_coordinator_accepts_jobs_with_none_in_flight() {
+        let coordinator = ShutdownCoordinator::new();
+        assert!(coordinator.is_accepting_jobs());
+        assert_eq!(coordinator.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_stop_accepting_jobs() {
+        let coordinator = ShutdownCoordinator::new();
+        coordinator.stop_accepting_jobs();
+        assert!(!coordinator.is_accepting_jobs());
+    }
+
+    #[test]
+    fn test_job_guard_tracks_in_flight_count() {
+        let coordinator = ShutdownCoordinator::new();
+        let guard = coordinator.job_started();
+        assert_eq!(coordinator.in_flight(), 1);
+
+        drop(guard);
+        assert_eq!(coordinator.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_drain_returns_true_immediately_when_idle() {
+        let coordinator = ShutdownCoordinator::new();
+        assert!(coordinator.wait_for_drain(Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_drain_returns_true_once_job_finishes_in_time() {
+        let coordinator = ShutdownCoordinator::new();
+        let guard = coordinator.job_started();
+
+        let coordinator_clone = coordinator.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+            let _ = &coordinator_clone;
+        });
+
+        assert!(coordinator.wait_for_drain(Duration::from_millis(500)).await);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_drain_times_out_when_job_never_finishes() {
+        let coordinator = ShutdownCoordinator::new();
+        let _guard = coordinator.job_started();
+
+        assert!(!coordinator.wait_for_drain(Duration::from_millis(20)).await);
+    }
+}
@@ -1,8 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use crate::scraper::ScraperErrorKind;
+
+/// Largest CSV content [`AccountResult::set_completed`] will retain inline
+/// in memory. Beyond this, only `csv_path` is kept and the content stays on
+/// disk, so a job with many large accounts can't grow `JobState` without
+/// bound while it sits in the queue. [`AccountResult::csv_content`] is
+/// `None` in that case; callers fall back to reading `csv_path`.
+pub const MAX_RETAINED_CSV_BYTES: usize = 1024 * 1024;
+
 /// Job status enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JobStatus {
@@ -33,8 +42,20 @@ pub struct AccountResult {
     pub status: JobStatus,
     /// Path to downloaded CSV file (if successful)
     pub csv_path: Option<PathBuf>,
+    /// CSV content (if successful and no larger than
+    /// [`MAX_RETAINED_CSV_BYTES`]), so P2P clients - which have no
+    /// filesystem access - can get the data straight from
+    /// `GetJobResults` instead of only a path. `None` for oversized
+    /// content even on success; read `csv_path` instead.
+    pub csv_content: Option<Vec<u8>>,
     /// Error message (if failed)
     pub error_message: Option<String>,
+    /// Machine-readable classification of `error_message` (if failed), for
+    /// clients that want to branch on e.g. login failures without parsing
+    /// the message text
+    pub error_kind: Option<ScraperErrorKind>,
+    /// Number of scrape attempts made for this account, including retries
+    pub attempts: u32,
 }
 
 impl AccountResult {
@@ -45,25 +66,35 @@ impl AccountResult {
             name,
             status: JobStatus::Queued,
             csv_path: None,
+            csv_content: None,
             error_message: None,
+            error_kind: None,
+            attempts: 0,
         }
     }
 
-    /// Mark as running
+    /// Mark as running and record the start of another attempt
     pub fn set_running(&mut self) {
         self.status = JobStatus::Running;
+        self.attempts += 1;
     }
 
-    /// Mark as completed with CSV path
-    pub fn set_completed(&mut self, csv_path: PathBuf) {
+    /// Mark as completed with a CSV path and its content. The content is
+    /// only retained inline if it's no larger than
+    /// [`MAX_RETAINED_CSV_BYTES`]; otherwise `csv_content` stays `None` and
+    /// callers read `csv_path` instead.
+    pub fn set_completed(&mut self, csv_path: PathBuf, csv_content: Vec<u8>) {
         self.status = JobStatus::Completed;
         self.csv_path = Some(csv_path);
+        self.csv_content =
+            (csv_content.len() <= MAX_RETAINED_CSV_BYTES).then_some(csv_content);
     }
 
-    /// Mark as failed with error message
-    pub fn set_failed(&mut self, error: String) {
+    /// Mark as failed with an error message and its classification
+    pub fn set_failed(&mut self, error: String, kind: ScraperErrorKind) {
         self.status = JobStatus::Failed;
         self.error_message = Some(error);
+        self.error_kind = Some(kind);
     }
 }
 
@@ -88,12 +119,14 @@ pub struct JobState {
     pub current_account_index: usize,
     /// Download base path
     pub download_path: PathBuf,
-    /// Session folder path (YYYYMMDD_HHMMSS format)
+    /// Session folder path (`YYYYMMDD_HHMMSS_<random suffix>` format)
     pub session_folder: Option<PathBuf>,
     /// Run in headless mode
     pub headless: bool,
     /// Last error message
     pub last_error: Option<String>,
+    /// Machine-readable classification of `last_error`, if any
+    pub last_error_kind: Option<ScraperErrorKind>,
 }
 
 impl JobState {
@@ -127,6 +160,7 @@ impl JobState {
             session_folder: None,
             headless,
             last_error: None,
+            last_error_kind: None,
         }
     }
 
@@ -161,9 +195,10 @@ impl JobState {
         self.current_account_index += 1;
     }
 
-    /// Set the last error message
-    pub fn set_last_error(&mut self, error: String) {
+    /// Set the last error message and its classification
+    pub fn set_last_error(&mut self, error: String, kind: ScraperErrorKind) {
         self.last_error = Some(error);
+        self.last_error_kind = Some(kind);
     }
 
     /// Get success count
@@ -228,6 +263,44 @@ impl JobState {
     pub fn get_account_result_mut(&mut self, user_id: &str) -> Option<&mut AccountResult> {
         self.accounts.get_mut(user_id)
     }
+
+    /// Snapshot of every account's result, in `account_order` - what
+    /// [`write_account_results`] persists to the session folder so a
+    /// resumed job can tell which accounts already succeeded.
+    pub fn account_results(&self) -> Vec<AccountResult> {
+        self.account_order
+            .iter()
+            .filter_map(|user_id| self.accounts.get(user_id).cloned())
+            .collect()
+    }
+}
+
+/// File name [`write_account_results`]/[`load_account_results`] use inside
+/// a job's session folder.
+pub const ACCOUNT_RESULTS_FILENAME: &str = "job_results.json";
+
+/// Persist `results` to `session_folder/ACCOUNT_RESULTS_FILENAME`, so a
+/// later job with `resume_session_folder` set to the same folder can skip
+/// accounts that already completed instead of reprocessing the whole batch.
+pub fn write_account_results(session_folder: &Path, results: &[AccountResult]) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(results)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(session_folder.join(ACCOUNT_RESULTS_FILENAME), json)
+}
+
+/// Load account results previously persisted to `session_folder`, keyed by
+/// user_id. Returns an empty map if the folder has no results file yet
+/// (e.g. the previous job never got as far as processing an account).
+pub fn load_account_results(session_folder: &Path) -> std::io::Result<HashMap<String, AccountResult>> {
+    let path = session_folder.join(ACCOUNT_RESULTS_FILENAME);
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let json = std::fs::read_to_string(&path)?;
+    let results: Vec<AccountResult> = serde_json::from_str(&json)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(results.into_iter().map(|r| (r.user_id.clone(), r)).collect())
 }
 
 #[cfg(test)]
@@ -262,9 +335,75 @@ mod tests {
 
         result.set_running();
         assert_eq!(result.status, JobStatus::Running);
+        assert_eq!(result.attempts, 1);
 
-        result.set_completed(PathBuf::from("./test.csv"));
+        result.set_completed(PathBuf::from("./test.csv"), b"date,amount\n".to_vec());
         assert_eq!(result.status, JobStatus::Completed);
         assert!(result.csv_path.is_some());
+        assert_eq!(result.csv_content, Some(b"date,amount\n".to_vec()));
+    }
+
+    #[test]
+    fn test_account_result_does_not_retain_oversized_csv_content() {
+        let mut result = AccountResult::new("user1".to_string(), "User One".to_string());
+        result.set_running();
+
+        let oversized = vec![0u8; MAX_RETAINED_CSV_BYTES + 1];
+        result.set_completed(PathBuf::from("./test.csv"), oversized);
+
+        assert_eq!(result.status, JobStatus::Completed);
+        assert!(result.csv_path.is_some());
+        assert!(result.csv_content.is_none());
+    }
+
+    #[test]
+    fn test_account_result_tracks_retry_attempts() {
+        let mut result = AccountResult::new("user1".to_string(), "User One".to_string());
+
+        result.set_running();
+        result.set_failed("timeout".to_string(), ScraperErrorKind::Timeout);
+        result.set_running();
+        result.set_failed("timeout".to_string(), ScraperErrorKind::Timeout);
+        result.set_running();
+        result.set_completed(PathBuf::from("./test.csv"), b"data".to_vec());
+
+        assert_eq!(result.attempts, 3);
+        assert_eq!(result.status, JobStatus::Completed);
+    }
+
+    #[test]
+    fn test_account_result_carries_error_kind() {
+        let mut result = AccountResult::new("user1".to_string(), "User One".to_string());
+
+        result.set_failed("invalid password".to_string(), ScraperErrorKind::LoginFailed);
+
+        assert_eq!(result.status, JobStatus::Failed);
+        assert_eq!(result.error_kind, Some(ScraperErrorKind::LoginFailed));
+    }
+
+    #[test]
+    fn test_write_and_load_account_results_round_trips() {
+        let mut completed = AccountResult::new("user1".to_string(), "User One".to_string());
+        completed.set_running();
+        completed.set_completed(PathBuf::from("./user1.csv"), b"data".to_vec());
+
+        let mut failed = AccountResult::new("user2".to_string(), "User Two".to_string());
+        failed.set_running();
+        failed.set_failed("timeout".to_string(), ScraperErrorKind::Timeout);
+
+        let dir = tempfile::tempdir().unwrap();
+        write_account_results(dir.path(), &[completed, failed]).unwrap();
+
+        let loaded = load_account_results(dir.path()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded["user1"].status, JobStatus::Completed);
+        assert_eq!(loaded["user2"].status, JobStatus::Failed);
+    }
+
+    #[test]
+    fn test_load_account_results_returns_empty_map_when_no_file_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = load_account_results(dir.path()).unwrap();
+        assert!(loaded.is_empty());
     }
 }
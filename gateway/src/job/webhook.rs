@@ -0,0 +1,279 @@
+//! Job-completion webhook delivery.
+//!
+//! `scrape_multiple` callers otherwise have to poll `Health` to find out
+//! when a long-running job finishes. When a `callback_url` is supplied,
+//! [`send_webhook`] POSTs a [`JobCompletionPayload`] there instead, so a
+//! client can fire-and-forget and still get notified.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Delay between webhook delivery attempts.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Timeout and retry behavior for webhook delivery, projected out of
+/// [`crate::config::GatewayConfig`] via
+/// [`crate::config::GatewayConfig::webhook_config`].
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookConfig {
+    /// Maximum time to wait for the callback URL to respond to a single
+    /// attempt.
+    pub timeout: Duration,
+    /// Number of extra attempts after the first failure, before giving up.
+    pub retry_count: u32,
+}
+
+/// JSON body POSTed to a job's `callback_url` when it finishes.
+#[derive(Debug, Serialize)]
+pub struct JobCompletionPayload {
+    pub job_id: String,
+    pub success_count: usize,
+    pub total_count: usize,
+    pub session_folder: PathBuf,
+}
+
+/// Reject a peer-supplied `callback_url` that isn't plain `http(s)`, or
+/// that names loopback/private/link-local/unspecified addresses - the
+/// gateway's own internal network, including cloud metadata endpoints like
+/// `169.254.169.254`. Without this, any P2P peer could direct the
+/// gateway's outbound webhook client at itself (SSRF). Called once, at the
+/// RPC boundary in [`crate::grpc::scraper_service`], before a job is ever
+/// created; `send_webhook` itself trusts its caller and does not re-check.
+///
+/// This only catches IP literals and the `localhost` hostname - it does
+/// not resolve DNS names, so a hostname that resolves to an internal
+/// address at request time is not caught. That would need a resolve-then-
+/// dial check (and DNS-rebinding protection on top of that), which is more
+/// than this fix needs right now.
+pub fn validate_callback_url(callback_url: &str) -> Result<(), String> {
+    let authority = callback_url
+        .strip_prefix("https://")
+        .or_else(|| callback_url.strip_prefix("http://"))
+        .ok_or_else(|| format!("callback_url must use http or https: {}", callback_url))?
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or("");
+
+    let host = host_from_authority(authority);
+
+    if host.is_empty() {
+        return Err(format!("callback_url has no host: {}", callback_url));
+    }
+
+    if host.eq_ignore_ascii_case("localhost") {
+        return Err(format!("callback_url host is not allowed: {}", host));
+    }
+
+    if let Ok(ip) = host.parse::<std::net::IpAddr>() {
+        if is_disallowed_ip(&ip) {
+            return Err(format!("callback_url host is not allowed: {}", ip));
+        }
+    }
+
+    Ok(())
+}
+
+/// Pull the host out of a URL authority (`user:pass@host:port`), stripping
+/// userinfo and port, and unwrapping `[...]` around an IPv6 literal.
+fn host_from_authority(authority: &str) -> &str {
+    let authority = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+
+    if let Some(rest) = authority.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+
+    authority.split(':').next().unwrap_or(authority)
+}
+
+/// Loopback, private, link-local, or unspecified - the ranges a webhook
+/// callback has no legitimate reason to target.
+fn is_disallowed_ip(ip: &std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(v4) => {
+            v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_unspecified()
+        }
+        std::net::IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_disallowed_ip(&std::net::IpAddr::V4(v4));
+            }
+            let segments = v6.segments();
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (segments[0] & 0xfe00) == 0xfc00 // fc00::/7 unique local
+                || (segments[0] & 0xffc0) == 0xfe80 // fe80::/10 link-local
+        }
+    }
+}
+
+/// POST `payload` to `callback_url`, retrying up to `config.retry_count`
+/// additional times (on a connection error or non-2xx status) before
+/// giving up. A client that never receives its callback can still poll
+/// `Health` for the job id, so failures are logged rather than surfaced
+/// anywhere - there's no RPC in flight left to fail.
+pub async fn send_webhook(callback_url: &str, payload: &JobCompletionPayload, config: &WebhookConfig) {
+    // reqwest's default policy follows up to 10 redirects without
+    // re-validating the final host, which would let a callback_url that
+    // passes validate_callback_url 302 its way to a private/loopback
+    // address anyway - disable redirects entirely instead.
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to create HTTP client");
+
+    for attempt in 0..=config.retry_count {
+        let result = client.post(callback_url).timeout(config.timeout).json(payload).send().await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                tracing::info!("Webhook delivered for job {} to {}", payload.job_id, callback_url);
+                return;
+            }
+            Ok(response) => {
+                tracing::warn!(
+                    "Webhook for job {} to {} returned status {} (attempt {}/{})",
+                    payload.job_id,
+                    callback_url,
+                    response.status(),
+                    attempt + 1,
+                    config.retry_count + 1
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Webhook for job {} to {} failed: {} (attempt {}/{})",
+                    payload.job_id,
+                    callback_url,
+                    e,
+                    attempt + 1,
+                    config.retry_count + 1
+                );
+            }
+        }
+
+        if attempt < config.retry_count {
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+
+    tracing::error!(
+        "Giving up on webhook for job {} to {} after {} attempts",
+        payload.job_id,
+        callback_url,
+        config.retry_count + 1
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Starts an in-process server that fails the first `fail_count`
+    /// requests with a 500, then succeeds, mirroring the mock pattern used
+    /// for the JWKS provider tests.
+    async fn start_mock_webhook(fail_count: usize) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&request_count);
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+
+                let attempt = counter.fetch_add(1, Ordering::SeqCst);
+                let response = if attempt < fail_count {
+                    "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                } else {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{}/", addr), request_count)
+    }
+
+    fn test_payload() -> JobCompletionPayload {
+        JobCompletionPayload {
+            job_id: "job-1".to_string(),
+            success_count: 1,
+            total_count: 1,
+            session_folder: PathBuf::from("./downloads/20260101_000000"),
+        }
+    }
+
+    #[test]
+    fn validate_callback_url_accepts_public_http_and_https() {
+        assert!(validate_callback_url("https://example.com/callback").is_ok());
+        assert!(validate_callback_url("http://example.com:8080/callback").is_ok());
+        assert!(validate_callback_url("https://203.0.113.10/callback").is_ok());
+    }
+
+    #[test]
+    fn validate_callback_url_rejects_non_http_schemes() {
+        assert!(validate_callback_url("file:///etc/passwd").is_err());
+        assert!(validate_callback_url("ftp://example.com/callback").is_err());
+    }
+
+    #[test]
+    fn validate_callback_url_rejects_loopback_and_localhost() {
+        assert!(validate_callback_url("http://127.0.0.1/callback").is_err());
+        assert!(validate_callback_url("http://127.0.0.1:8080/callback").is_err());
+        assert!(validate_callback_url("http://localhost/callback").is_err());
+        assert!(validate_callback_url("http://[::1]/callback").is_err());
+    }
+
+    #[test]
+    fn validate_callback_url_rejects_link_local_and_metadata_endpoint() {
+        assert!(validate_callback_url("http://169.254.169.254/latest/meta-data").is_err());
+        assert!(validate_callback_url("http://[fe80::1]/callback").is_err());
+    }
+
+    #[test]
+    fn validate_callback_url_rejects_private_ranges() {
+        assert!(validate_callback_url("http://10.0.0.1/callback").is_err());
+        assert!(validate_callback_url("http://192.168.1.1/callback").is_err());
+        assert!(validate_callback_url("http://172.16.0.1/callback").is_err());
+    }
+
+    #[tokio::test]
+    async fn send_webhook_succeeds_on_first_attempt() {
+        let (url, request_count) = start_mock_webhook(0).await;
+        let config = WebhookConfig { timeout: Duration::from_secs(5), retry_count: 2 };
+
+        send_webhook(&url, &test_payload(), &config).await;
+
+        assert_eq!(request_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn send_webhook_retries_until_success() {
+        let (url, request_count) = start_mock_webhook(2).await;
+        let config = WebhookConfig { timeout: Duration::from_secs(5), retry_count: 2 };
+
+        send_webhook(&url, &test_payload(), &config).await;
+
+        assert_eq!(request_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn send_webhook_gives_up_after_retry_count_exhausted() {
+        let (url, request_count) = start_mock_webhook(usize::MAX).await;
+        let config = WebhookConfig { timeout: Duration::from_secs(5), retry_count: 2 };
+
+        send_webhook(&url, &test_payload(), &config).await;
+
+        assert_eq!(request_count.load(Ordering::SeqCst), 3);
+    }
+}
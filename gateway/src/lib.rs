@@ -6,17 +6,24 @@
 pub mod config;
 pub mod grpc;
 pub mod job;
+pub mod metrics;
+#[cfg(feature = "p2p")]
 pub mod p2p;
 pub mod router;
 pub mod scraper;
 pub mod services;
+pub mod shutdown;
 pub mod updater;
 
-pub use config::GatewayConfig;
+pub use config::{GatewayConfig, LogFormat};
 pub use grpc::EtcScraperService;
 pub use grpc::PdfGeneratorService;
-pub use job::{AccountResult, JobQueue, JobState, JobStatus};
+pub use job::{AccountResult, JobQueue, JobState, JobStatus, ShutdownCoordinator};
+pub use shutdown::{Shutdown, ShutdownTrigger};
+#[cfg(feature = "p2p")]
 pub use p2p::{P2PConfig, P2PError, P2PManager};
 pub use router::ServiceRouter;
-pub use scraper::{MockScraperService, ScrapeConfig, ScrapeResult, ScraperError, ScraperService};
-pub use updater::{AutoUpdater, UpdateConfig, UpdateError};
+pub use scraper::{
+    MockScraperService, ScrapeConfig, ScrapeResult, ScraperError, ScraperErrorKind, ScraperService,
+};
+pub use updater::{AutoUpdater, CachedUpdateCheck, UpdateConfig, UpdateError};
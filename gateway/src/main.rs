@@ -13,11 +13,56 @@ use gateway_lib::{
     grpc::gateway_server::gateway_service_server::GatewayServiceServer,
     grpc::scraper_server::etc_scraper_server::EtcScraperServer,
     grpc::pdf_server::pdf_generator_server::PdfGeneratorServer,
+    grpc::timecard_server::timecard_service_server::TimecardServiceServer,
     grpc::gateway_service::GatewayServiceImpl,
-    p2p::{self, grpc_handler::TonicServiceBridge, P2PCredentials, SetupConfig},
     updater::{AutoUpdater, UpdateConfig, UpdateChannel, format_update_info},
-    EtcScraperService, PdfGeneratorService, GatewayConfig, JobQueue,
+    EtcScraperService, PdfGeneratorService, GatewayConfig, JobQueue, LogFormat, ShutdownCoordinator,
 };
+#[cfg(feature = "p2p")]
+use gateway_lib::p2p::{self, P2PCredentials, SetupConfig};
+use timecard_service::TimecardGrpcService;
+
+/// Initialize the global `tracing` subscriber, shared by `run_server`,
+/// `run_p2p_client`, and `run_p2p_service` so all three modes honor
+/// `GatewayConfig::log_format`/`GATEWAY_LOG_FORMAT` the same way. `is_service`
+/// additionally layers in the Windows Event Log when running as a service.
+fn init_tracing(default_filter: &str, format: LogFormat, is_service: bool) {
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| default_filter.into());
+
+    #[cfg(windows)]
+    if is_service {
+        // Windows Service mode: output to both Event Log and console
+        let eventlog = tracing_layer_win_eventlog::EventLogLayer::new("GatewayService".to_string());
+        match format {
+            LogFormat::Json => tracing_subscriber::registry()
+                .with(env_filter)
+                .with(tracing_subscriber::fmt::layer().json())
+                .with(eventlog)
+                .init(),
+            LogFormat::Text => tracing_subscriber::registry()
+                .with(env_filter)
+                .with(tracing_subscriber::fmt::layer())
+                .with(eventlog)
+                .init(),
+        }
+        return;
+    }
+
+    #[cfg(not(windows))]
+    let _ = is_service; // suppress unused warning
+
+    match format {
+        LogFormat::Json => tracing_subscriber::registry()
+            .with(env_filter)
+            .with(tracing_subscriber::fmt::layer().json())
+            .init(),
+        LogFormat::Text => tracing_subscriber::registry()
+            .with(env_filter)
+            .with(tracing_subscriber::fmt::layer())
+            .init(),
+    }
+}
 
 #[cfg(windows)]
 mod windows_service_impl {
@@ -48,16 +93,12 @@ mod windows_service_impl {
     }
 
     fn run_service() -> Result<(), Box<dyn std::error::Error>> {
-        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
-        let shutdown_tx = std::sync::Arc::new(std::sync::Mutex::new(Some(shutdown_tx)));
+        let (shutdown, shutdown_trigger) = gateway_lib::Shutdown::new();
 
-        let shutdown_tx_clone = shutdown_tx.clone();
         let event_handler = move |control_event| -> ServiceControlHandlerResult {
             match control_event {
                 ServiceControl::Stop | ServiceControl::Shutdown => {
-                    if let Some(tx) = shutdown_tx_clone.lock().unwrap().take() {
-                        let _ = tx.send(());
-                    }
+                    shutdown_trigger.trigger();
                     ServiceControlHandlerResult::NoError
                 }
                 ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
@@ -86,12 +127,22 @@ mod windows_service_impl {
             match mode {
                 super::ServiceMode::P2P => {
                     // Run in P2P mode
-                    let signaling_url = super::get_signaling_url();
-                    super::run_p2p_service(Some(shutdown_rx), signaling_url).await
+                    #[cfg(feature = "p2p")]
+                    {
+                        let signaling_url = super::get_signaling_url();
+                        super::run_p2p_service(shutdown, true, signaling_url).await
+                    }
+                    #[cfg(not(feature = "p2p"))]
+                    {
+                        tracing::error!(
+                            "Service mode is set to p2p, but this build was not compiled with p2p support. Falling back to gRPC mode."
+                        );
+                        super::run_server(shutdown, true).await
+                    }
                 }
                 super::ServiceMode::Grpc => {
                     // Run in gRPC mode
-                    super::run_server(Some(shutdown_rx)).await
+                    super::run_server(shutdown, true).await
                 }
             }
         })?;
@@ -111,81 +162,95 @@ mod windows_service_impl {
 }
 
 async fn run_server(
-    shutdown_rx: Option<tokio::sync::oneshot::Receiver<()>>,
+    shutdown: gateway_lib::Shutdown,
+    is_service: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| "gateway=info".into());
-
-    let is_service = shutdown_rx.is_some();
-
-    #[cfg(windows)]
-    if is_service {
-        // Windows Service mode: output to both Event Log and console
-        let eventlog = tracing_layer_win_eventlog::EventLogLayer::new("GatewayService".to_string());
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
-            .with(eventlog)
-            .init();
-    } else {
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
-            .init();
-    }
-
-    #[cfg(not(windows))]
-    {
-        let _ = is_service; // suppress unused warning
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
-            .init();
-    }
+    // Initialize tracing. The log format must be known before the rest of
+    // the config is loaded, since loading it may itself want to log (e.g. a
+    // warning about an unreadable config file).
+    init_tracing("gateway=info", GatewayConfig::log_format_from_env(), is_service);
 
     // Load configuration
     let config = GatewayConfig::from_env();
+    if let Err(e) = config.validate(false) {
+        tracing::error!("Invalid configuration: {}", e);
+        return Err(e.into());
+    }
     tracing::info!("Starting Gateway v{}", config.version);
     tracing::info!("gRPC server listening on {}", config.grpc_addr);
 
+    // Install the Prometheus recorder before anything records a metric, and
+    // serve it on its own port alongside the gRPC server.
+    let metrics_handle = gateway_lib::metrics::install_recorder();
+    if config.enable_metrics {
+        let metrics_addr = config.metrics_addr.parse()?;
+        tokio::spawn(async move {
+            if let Err(e) = gateway_lib::metrics::serve(metrics_addr, metrics_handle).await {
+                tracing::error!("Metrics server on {} failed: {}", metrics_addr, e);
+            }
+        });
+    }
+
     // Create shared job queue
     let job_queue = Arc::new(RwLock::new(JobQueue::new()));
 
+    // Coordinates graceful shutdown: stops `scrape_multiple` accepting new
+    // jobs and tracks in-flight ones so shutdown can wait for them to drain.
+    let shutdown_coordinator = ShutdownCoordinator::new();
+
     // Create gRPC services
     let gateway_service = GatewayServiceImpl::new();
-    let scraper_service = EtcScraperService::new(config.clone(), job_queue.clone());
+    let scraper_service = EtcScraperService::with_shutdown_coordinator(
+        config.clone(),
+        job_queue.clone(),
+        shutdown_coordinator.clone(),
+    );
     let pdf_service = PdfGeneratorService::new();
+    let timecard_service = TimecardGrpcService::new();
 
     // Parse address
     let addr = config.grpc_addr.parse()?;
 
-    // Create reflection service
-    let reflection_service = tonic_reflection::server::Builder::configure()
-        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
-        .build_v1()
-        .expect("Failed to create reflection service");
+    // Create the reflection service. A bad/missing FILE_DESCRIPTOR_SET (e.g.
+    // a stale build) shouldn't take down gRPC entirely: log it and serve
+    // without reflection rather than panicking at startup.
+    let mut server = Server::builder();
+    if !config.enable_reflection {
+        tracing::info!("gRPC reflection: disabled (by configuration)");
+    } else {
+        match tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+            .build_v1()
+        {
+            Ok(reflection_service) => {
+                server = server.add_service(reflection_service);
+                tracing::info!("gRPC reflection: enabled");
+            }
+            Err(e) => {
+                tracing::warn!("gRPC reflection: disabled (failed to build reflection service: {})", e);
+            }
+        }
+    }
 
     // Start gRPC server with optional shutdown signal
-    let server = Server::builder()
-        .add_service(reflection_service)
+    let server = server
         .add_service(GatewayServiceServer::new(gateway_service))
         .add_service(EtcScraperServer::new(scraper_service))
-        .add_service(PdfGeneratorServer::new(pdf_service));
-
-    match shutdown_rx {
-        Some(rx) => {
-            server
-                .serve_with_shutdown(addr, async {
-                    let _ = rx.await;
-                    tracing::info!("Shutdown signal received");
-                })
-                .await?;
-        }
-        None => {
-            server.serve(addr).await?;
-        }
-    }
+        .add_service(PdfGeneratorServer::new(pdf_service))
+        .add_service(TimecardServiceServer::new(timecard_service));
+
+    let shutdown_grace = config.shutdown_grace();
+    let checkpoint_path = config.download_path.join("job_queue_checkpoint.json");
+
+    server.serve_with_shutdown(addr, shutdown.recv()).await?;
+
+    gateway_lib::job::shutdown::shutdown(
+        &shutdown_coordinator,
+        &job_queue,
+        shutdown_grace,
+        &checkpoint_path,
+    )
+    .await;
 
     Ok(())
 }
@@ -193,6 +258,14 @@ async fn run_server(
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
 
+    #[cfg(not(feature = "p2p"))]
+    reject_p2p_args_if_unsupported(&args);
+
+    #[cfg(feature = "p2p")]
+    if args.iter().any(|a| a == "--p2p-creds-plaintext") {
+        p2p::set_credentials_plaintext_only(true);
+    }
+
     // Check for command line arguments
     if args.len() > 1 {
         match args[1].as_str() {
@@ -203,9 +276,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("Service installed successfully");
                     return Ok(());
                 }
-                #[cfg(not(windows))]
+                #[cfg(target_os = "linux")]
+                {
+                    install_systemd_service()?;
+                    println!("Service installed successfully");
+                    return Ok(());
+                }
+                #[cfg(not(any(windows, target_os = "linux")))]
                 {
-                    eprintln!("Service installation is only supported on Windows");
+                    eprintln!("Service installation is only supported on Windows and Linux");
                     return Ok(());
                 }
             }
@@ -216,18 +295,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("Service uninstalled successfully");
                     return Ok(());
                 }
-                #[cfg(not(windows))]
+                #[cfg(target_os = "linux")]
+                {
+                    uninstall_systemd_service()?;
+                    println!("Service uninstalled successfully");
+                    return Ok(());
+                }
+                #[cfg(not(any(windows, target_os = "linux")))]
                 {
-                    eprintln!("Service uninstallation is only supported on Windows");
+                    eprintln!("Service uninstallation is only supported on Windows and Linux");
                     return Ok(());
                 }
             }
             "run" => {
                 // Run as console application
                 let runtime = tokio::runtime::Runtime::new()?;
-                runtime.block_on(run_server(None))?;
+                runtime.block_on(run_server(gateway_lib::Shutdown::new().0, false))?;
                 return Ok(());
             }
+            #[cfg(feature = "p2p")]
             "--p2p-setup" | "--p2p-reauth" => {
                 // P2P OAuth setup - fall through to parse_p2p_args to collect all options
                 if let Some(result) = parse_p2p_args(&args) {
@@ -270,11 +356,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 runtime.block_on(perform_update(channel, true))?;
                 return Ok(());
             }
-            "--update-from" => {
-                // Install a specific version by tag
+            "--list-releases" => {
+                // List available releases on the configured channel
+                let runtime = tokio::runtime::Runtime::new()?;
+                let channel = find_update_channel(&args);
+                runtime.block_on(list_releases_cli(channel))?;
+                return Ok(());
+            }
+            "--update-from" | "--update-to" => {
+                // Install a specific version by tag (forward or rollback)
                 let tag = find_update_from_tag(&args).ok_or_else(|| {
-                    eprintln!("Usage: gateway --update-from <tag>");
-                    eprintln!("Example: gateway --update-from v0.2.30");
+                    eprintln!("Usage: gateway --update-to <tag>");
+                    eprintln!("Example: gateway --update-to v0.2.30");
                     std::io::Error::new(std::io::ErrorKind::InvalidInput, "Missing tag argument")
                 })?;
                 let runtime = tokio::runtime::Runtime::new()?;
@@ -319,12 +412,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("Signaling URL: {}", get_signaling_url());
                 return Ok(());
             }
+            "--show-config" => {
+                print_effective_config(&args);
+                return Ok(());
+            }
             "--help" | "-h" => {
                 print_help();
                 return Ok(());
             }
             _ => {
                 // Check for --p2p-* options
+                #[cfg(feature = "p2p")]
                 if let Some(result) = parse_p2p_args(&args) {
                     let runtime = tokio::runtime::Runtime::new()?;
                     runtime.block_on(result)?;
@@ -346,7 +444,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 eprintln!("Running as console application instead...");
                 eprintln!("Use 'gateway run' to run as console app, or 'gateway install' to install as service");
                 let runtime = tokio::runtime::Runtime::new()?;
-                runtime.block_on(run_server(None))
+                runtime.block_on(run_server(gateway_lib::Shutdown::new().0, false))
             }
         }
     }
@@ -354,7 +452,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(not(windows))]
     {
         let runtime = tokio::runtime::Runtime::new()?;
-        runtime.block_on(run_server(None))
+        runtime.block_on(run_server(gateway_lib::Shutdown::new().0, false))
+    }
+}
+
+/// Exit with a clear error if any `--p2p-*` flag was passed to a build
+/// compiled with `--no-default-features` (i.e. without the `p2p` feature).
+#[cfg(not(feature = "p2p"))]
+fn reject_p2p_args_if_unsupported(args: &[String]) {
+    if args.iter().any(|a| a.starts_with("--p2p-")) {
+        eprintln!("This gateway build was not compiled with p2p support.");
+        eprintln!("Rebuild with the default features (or `--features p2p`) to use --p2p-* options.");
+        std::process::exit(1);
     }
 }
 
@@ -413,6 +522,62 @@ fn uninstall_service() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Path of the systemd unit file written by [`install_systemd_service`]
+#[cfg(target_os = "linux")]
+const SYSTEMD_UNIT_PATH: &str = "/etc/systemd/system/gateway.service";
+
+#[cfg(target_os = "linux")]
+fn install_systemd_service() -> Result<(), Box<dyn std::error::Error>> {
+    let exe_path = std::env::current_exe()?;
+
+    let unit = format!(
+        "[Unit]\n\
+         Description=API Gateway Service\n\
+         After=network.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={} run\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy=multi-user.target\n",
+        exe_path.display()
+    );
+
+    std::fs::write(SYSTEMD_UNIT_PATH, unit)?;
+
+    run_systemctl(&["daemon-reload"])?;
+    run_systemctl(&["enable", "gateway.service"])?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn uninstall_systemd_service() -> Result<(), Box<dyn std::error::Error>> {
+    // Best-effort stop/disable - a service that was never started or
+    // enabled shouldn't block removing the unit file.
+    let _ = run_systemctl(&["disable", "--now", "gateway.service"]);
+
+    if std::path::Path::new(SYSTEMD_UNIT_PATH).exists() {
+        std::fs::remove_file(SYSTEMD_UNIT_PATH)?;
+    }
+
+    run_systemctl(&["daemon-reload"])?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn run_systemctl(args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let status = std::process::Command::new("systemctl").args(args).status()?;
+    if !status.success() {
+        return Err(format!("systemctl {} failed: {}", args.join(" "), status).into());
+    }
+    Ok(())
+}
+
 fn print_help() {
     println!("Gateway Service - API Gateway for gRPC requests");
     println!("Version: {}", env!("CARGO_PKG_VERSION"));
@@ -420,40 +585,71 @@ fn print_help() {
     println!("Usage:");
     println!("  gateway                  Run as Windows service");
     println!("  gateway run              Run as console application (gRPC mode)");
-    println!("  gateway install          Install as Windows service");
-    println!("  gateway uninstall        Uninstall Windows service");
+    println!("  gateway install          Install as Windows service (or systemd unit on Linux)");
+    println!("  gateway uninstall        Uninstall Windows service (or systemd unit on Linux)");
     println!();
     println!("Service Mode:");
     println!("  --set-mode <p2p|grpc>    Set service mode (restarts service if running)");
     println!("  --get-mode               Show current service mode");
+    println!("  --show-config            Print effective configuration (env/file/registry resolved)");
     println!();
     println!("Update Options:");
     println!("  --check-service          Check if service is ready for installation");
     println!("  --check-update           Check for available updates");
+    println!("  --list-releases          List available releases on the update channel");
     println!("  --update                 Download and install the latest update (exe)");
     println!("  --update-msi             Download and install the latest update (MSI installer)");
-    println!("  --update-from <tag>      Install a specific version by tag (e.g., v0.2.30)");
-    println!("  --update-from <tag> --msi  Install specific version using MSI");
+    println!("  --update-to <tag>        Install a specific version by tag (e.g., v0.2.30);");
+    println!("                           rolling back to an older tag asks for confirmation");
+    println!("  --update-from <tag>      Alias for --update-to");
+    println!("  --update-to <tag> --msi  Install specific version using MSI");
     println!("  --update-channel <ch>    Update channel: stable (default) or beta");
     println!();
-    println!("P2P Options:");
-    println!("  --p2p-setup              Run OAuth setup for P2P authentication");
-    println!("  --p2p-reauth             Force re-authentication (Google OAuth)");
-    println!("  --p2p-run                Connect to P2P signaling server (console mode)");
-    println!("  --p2p-creds <path>       Specify credentials file path");
-    println!("  --p2p-apikey <key>       Use specified API key directly");
-    println!("  --p2p-auth-url <url>     Auth server URL for OAuth setup");
-    println!("  --p2p-signaling-url <url> Signaling server WebSocket URL");
-    println!();
+    #[cfg(feature = "p2p")]
+    {
+        println!("P2P Options:");
+        println!("  --p2p-setup              Run OAuth setup for P2P authentication");
+        println!("  --p2p-reauth             Force re-authentication (Google OAuth)");
+        println!("  --p2p-run                Connect to P2P signaling server (console mode)");
+        println!("  --p2p-verify             Verify saved credentials authenticate, then exit");
+        println!("  --p2p-creds <path>       Specify credentials file path");
+        println!("  --p2p-creds-plaintext    Store the refresh token in plaintext (skip DPAPI/keyring)");
+        println!("  --p2p-apikey <key>       Use specified API key directly");
+        println!("  --p2p-auth-url <url>     Auth server URL for OAuth setup");
+        println!("  --p2p-signaling-url <url> Signaling server WebSocket URL");
+        println!();
+    }
+    #[cfg(not(feature = "p2p"))]
+    {
+        println!("P2P Options:");
+        println!("  (not available - this build was compiled without the `p2p` feature)");
+        println!();
+    }
     println!("Environment Variables:");
     println!("  GATEWAY_GRPC_ADDR        gRPC listen address (default: [::1]:50051)");
     println!("  P2P_AUTH_URL             Auth server URL for P2P OAuth");
     println!("  P2P_SIGNALING_URL        WebSocket signaling server URL");
+    println!("  P2P_STUN_SERVERS         Comma-separated STUN server URLs");
     println!("  GITHUB_OWNER             GitHub repository owner for updates");
     println!("  GITHUB_REPO              GitHub repository name for updates");
+    println!("  GITHUB_TOKEN             GitHub API token for update checks (raises the");
+    println!("                           rate limit from 60 to 5000 requests/hour; also");
+    println!("                           required for private repositories)");
+    println!("  GITHUB_API_BASE_URL      GitHub REST API base URL (default:");
+    println!("                           https://api.github.com); point at a GitHub");
+    println!("                           Enterprise instance's API base for internal repos");
+    println!("  UPDATE_MANIFEST_URL      Static version manifest URL to check for updates");
+    println!("                           against instead of GitHub, for air-gapped sites;");
+    println!("                           takes priority over GITHUB_OWNER/GITHUB_REPO");
+    println!();
+    println!("A gateway.toml (or gateway.json) file next to the executable can also set");
+    println!("grpc_addr, download_path, concurrency, signaling_url, stun_servers, and");
+    println!("update_owner/update_repo/update_github_token/update_api_base_url/");
+    println!("update_manifest_url; precedence is env > file > compiled default.");
 }
 
 /// Parse P2P-related command line arguments
+#[cfg(feature = "p2p")]
 fn parse_p2p_args(
     args: &[String],
 ) -> Option<std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), Box<dyn std::error::Error>>> + Send>>> {
@@ -464,6 +660,7 @@ fn parse_p2p_args(
     let mut has_setup = false;
     let mut has_reauth = false;
     let mut has_run = false;
+    let mut has_verify = false;
 
     // First pass: collect all arguments
     let mut i = 1;
@@ -497,6 +694,10 @@ fn parse_p2p_args(
                 has_run = true;
                 i += 1;
             }
+            "--p2p-verify" => {
+                has_verify = true;
+                i += 1;
+            }
             _ => {
                 i += 1;
             }
@@ -516,6 +717,12 @@ fn parse_p2p_args(
         }));
     }
 
+    if has_verify {
+        return Some(Box::pin(async move {
+            run_p2p_verify(signaling_url, creds_path).await
+        }));
+    }
+
     if has_run {
         return Some(Box::pin(async move {
             run_p2p_client(signaling_url, creds_path).await
@@ -536,6 +743,7 @@ fn parse_p2p_args(
 /// Run P2P OAuth setup
 ///
 /// If `force_reauth` is true, always perform OAuth setup even if credentials exist.
+#[cfg(feature = "p2p")]
 async fn run_p2p_setup(
     auth_url: Option<&str>,
     creds_path: Option<&str>,
@@ -719,35 +927,223 @@ impl std::str::FromStr for ServiceMode {
 }
 
 const REGISTRY_KEY: &str = r"SOFTWARE\Gateway";
-const DEFAULT_SIGNALING_URL: &str = "wss://cf-wbrtc-auth.m-tama-ramu.workers.dev/ws/app";
 
-/// Get current service mode from registry
+/// Typed accessor for `HKLM\SOFTWARE\Gateway`, replacing the old `reg.exe`
+/// shell-outs below - their stdout parsing broke on values containing
+/// spaces (e.g. some `SignalingUrl` overrides), since `reg query`'s column
+/// layout isn't a stable, machine-parseable format.
 #[cfg(windows)]
-fn get_service_mode() -> ServiceMode {
-    use std::process::Command;
+mod registry {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegQueryValueExW, RegSetValueExW, HKEY, HKEY_LOCAL_MACHINE,
+        KEY_READ, KEY_WRITE, REG_OPTION_NON_VOLATILE, REG_SAM_FLAGS, REG_SZ,
+    };
+    #[cfg(test)]
+    use windows::Win32::System::Registry::RegDeleteTreeW;
+
+    #[derive(Debug)]
+    pub enum RegistryError {
+        Open(u32),
+        Read(u32),
+        Write(u32),
+        NotUtf16,
+    }
 
-    // Use reg query to read the registry value
-    let output = Command::new("reg")
-        .args(["query", &format!("HKLM\\{}", REGISTRY_KEY), "/v", "ServiceMode"])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            // Parse output: "    ServiceMode    REG_SZ    p2p"
-            if stdout.to_lowercase().contains("grpc") {
-                ServiceMode::Grpc
-            } else {
-                ServiceMode::P2P // Default to P2P
+    impl std::fmt::Display for RegistryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                RegistryError::Open(code) => write!(f, "failed to open HKLM\\{} (code {})", super::REGISTRY_KEY, code),
+                RegistryError::Read(code) => write!(f, "failed to read registry value (code {})", code),
+                RegistryError::Write(code) => write!(f, "failed to write registry value (code {})", code),
+                RegistryError::NotUtf16 => write!(f, "registry value is not valid UTF-16"),
             }
         }
-        _ => ServiceMode::P2P, // Default to P2P if registry key doesn't exist
     }
+
+    impl std::error::Error for RegistryError {}
+
+    fn to_wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Opens (creating if needed) `root\subkey` with the given access
+    /// rights. Caller must [`RegCloseKey`] the returned handle.
+    fn open_key_at(root: HKEY, subkey: &str, access: REG_SAM_FLAGS) -> Result<HKEY, RegistryError> {
+        let subkey_wide = to_wide(subkey);
+        let mut hkey = HKEY::default();
+
+        let result = unsafe {
+            RegCreateKeyExW(
+                root,
+                PCWSTR(subkey_wide.as_ptr()),
+                0,
+                PCWSTR::null(),
+                REG_OPTION_NON_VOLATILE,
+                access,
+                None,
+                &mut hkey,
+                None,
+            )
+        };
+
+        if result != ERROR_SUCCESS {
+            return Err(RegistryError::Open(result.0));
+        }
+
+        Ok(hkey)
+    }
+
+    fn open_key(access: REG_SAM_FLAGS) -> Result<HKEY, RegistryError> {
+        open_key_at(HKEY_LOCAL_MACHINE, REGISTRY_KEY, access)
+    }
+
+    /// Read a `REG_SZ` value, returning `Ok(None)` if it isn't set.
+    pub fn get_string(value_name: &str) -> Result<Option<String>, RegistryError> {
+        let hkey = open_key(KEY_READ)?;
+        get_string_from(hkey, value_name)
+    }
+
+    fn get_string_from(hkey: HKEY, value_name: &str) -> Result<Option<String>, RegistryError> {
+        let name_wide = to_wide(value_name);
+
+        let mut data_size: u32 = 0;
+        let query_size_result = unsafe {
+            RegQueryValueExW(hkey, PCWSTR(name_wide.as_ptr()), None, None, None, Some(&mut data_size))
+        };
+
+        if query_size_result != ERROR_SUCCESS {
+            unsafe { let _ = RegCloseKey(hkey); }
+            return Ok(None);
+        }
+
+        let mut buffer = vec![0u8; data_size as usize];
+        let result = unsafe {
+            RegQueryValueExW(
+                hkey,
+                PCWSTR(name_wide.as_ptr()),
+                None,
+                None,
+                Some(buffer.as_mut_ptr()),
+                Some(&mut data_size),
+            )
+        };
+
+        unsafe { let _ = RegCloseKey(hkey); }
+
+        if result != ERROR_SUCCESS {
+            return Err(RegistryError::Read(result.0));
+        }
+
+        // REG_SZ data is UTF-16 with a trailing NUL; drop it before decoding.
+        let (_, aligned, _) = unsafe { buffer.align_to::<u16>() };
+        let trimmed = aligned.split(|&c| c == 0).next().unwrap_or(&[]);
+        String::from_utf16(trimmed).map(Some).map_err(|_| RegistryError::NotUtf16)
+    }
+
+    /// Write a `REG_SZ` value.
+    pub fn set_string(value_name: &str, data: &str) -> Result<(), RegistryError> {
+        let hkey = open_key(KEY_WRITE)?;
+        set_string_from(hkey, value_name, data)
+    }
+
+    fn set_string_from(hkey: HKEY, value_name: &str, data: &str) -> Result<(), RegistryError> {
+        let name_wide = to_wide(value_name);
+        let data_wide = to_wide(data);
+        let data_bytes = unsafe {
+            std::slice::from_raw_parts(data_wide.as_ptr() as *const u8, data_wide.len() * 2)
+        };
+
+        let result = unsafe {
+            RegSetValueExW(hkey, PCWSTR(name_wide.as_ptr()), 0, REG_SZ, Some(data_bytes))
+        };
+
+        unsafe { let _ = RegCloseKey(hkey); }
+
+        if result != ERROR_SUCCESS {
+            return Err(RegistryError::Write(result.0));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+
+        // HKLM\SOFTWARE\Gateway needs admin rights to write, so these tests
+        // exercise the same get_string_from/set_string_from codepath against
+        // a disposable subkey under HKCU instead, which any user can write.
+        const TEST_SUBKEY: &str = r"Software\GatewayRegistryAccessorTest";
+
+        fn open_test_key(access: REG_SAM_FLAGS) -> HKEY {
+            open_key_at(HKEY_CURRENT_USER, TEST_SUBKEY, access).unwrap()
+        }
+
+        fn cleanup_test_key() {
+            let subkey = to_wide(TEST_SUBKEY);
+            unsafe {
+                let _ = RegDeleteTreeW(HKEY_CURRENT_USER, PCWSTR(subkey.as_ptr()));
+            }
+        }
+
+        #[test]
+        fn test_set_then_get_round_trips() {
+            set_string_from(open_test_key(KEY_WRITE), "TestValue", "round-trip-value").unwrap();
+            let value = get_string_from(open_test_key(KEY_READ), "TestValue").unwrap();
+            assert_eq!(value, Some("round-trip-value".to_string()));
+            cleanup_test_key();
+        }
+
+        #[test]
+        fn test_get_missing_value_returns_none() {
+            let value = get_string_from(open_test_key(KEY_READ), "ValueThatDoesNotExist").unwrap();
+            assert_eq!(value, None);
+            cleanup_test_key();
+        }
+
+        #[test]
+        fn test_set_value_with_spaces_round_trips() {
+            // The old `reg.exe` + stdout-parsing approach broke on this.
+            let url = "wss://example.com/ws/app some value";
+            set_string_from(open_test_key(KEY_WRITE), "TestUrl", url).unwrap();
+            let value = get_string_from(open_test_key(KEY_READ), "TestUrl").unwrap();
+            assert_eq!(value, Some(url.to_string()));
+            cleanup_test_key();
+        }
+    }
+}
+
+/// Get current service mode from registry
+#[cfg(windows)]
+fn get_service_mode() -> ServiceMode {
+    match registry::get_string("ServiceMode") {
+        Ok(Some(value)) if value.eq_ignore_ascii_case("grpc") => ServiceMode::Grpc,
+        Ok(_) => ServiceMode::P2P, // Default to P2P if unset or any other value
+        Err(e) => {
+            tracing::warn!("Failed to read ServiceMode from registry, defaulting to p2p: {}", e);
+            ServiceMode::P2P
+        }
+    }
+}
+
+/// State file backing [`ServiceMode`] on non-Windows platforms, mirroring
+/// [`gateway_lib::p2p::P2PCredentials::service_path`]'s `/etc/gateway`
+/// convention. There's no registry there, so `--set-mode`/`--get-mode`
+/// need their own backing store to actually persist anything.
+#[cfg(not(windows))]
+fn mode_state_path() -> std::path::PathBuf {
+    std::path::PathBuf::from("/etc/gateway").join("mode")
 }
 
 #[cfg(not(windows))]
 fn get_service_mode() -> ServiceMode {
-    ServiceMode::Grpc // Non-Windows defaults to gRPC
+    match std::fs::read_to_string(mode_state_path()) {
+        Ok(contents) => contents.trim().parse().unwrap_or(ServiceMode::Grpc),
+        Err(_) => ServiceMode::Grpc, // No state file yet: default to gRPC
+    }
 }
 
 /// Get signaling URL from registry or environment variable
@@ -758,70 +1154,108 @@ fn get_signaling_url() -> String {
         return url;
     }
 
-    use std::process::Command;
-
-    // Try to read from registry
-    let output = Command::new("reg")
-        .args(["query", &format!("HKLM\\{}", REGISTRY_KEY), "/v", "SignalingUrl"])
-        .output();
-
-    match output {
-        Ok(out) if out.status.success() => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            // Parse output: "    SignalingUrl    REG_SZ    wss://..."
-            for line in stdout.lines() {
-                if line.contains("SignalingUrl") && line.contains("REG_SZ") {
-                    if let Some(url) = line.split("REG_SZ").nth(1) {
-                        let url = url.trim();
-                        if !url.is_empty() {
-                            return url.to_string();
-                        }
-                    }
-                }
-            }
-            DEFAULT_SIGNALING_URL.to_string()
+    match registry::get_string("SignalingUrl") {
+        Ok(Some(url)) if !url.is_empty() => url,
+        Ok(_) => GatewayConfig::from_env().signaling_url,
+        Err(e) => {
+            tracing::warn!("Failed to read SignalingUrl from registry, using default: {}", e);
+            GatewayConfig::from_env().signaling_url
         }
-        _ => DEFAULT_SIGNALING_URL.to_string(),
     }
 }
 
 #[cfg(not(windows))]
 fn get_signaling_url() -> String {
-    std::env::var("P2P_SIGNALING_URL").unwrap_or_else(|_| DEFAULT_SIGNALING_URL.to_string())
+    GatewayConfig::from_env().signaling_url
 }
 
-/// Set service mode in registry
-#[cfg(windows)]
-fn set_service_mode(mode: ServiceMode) -> Result<(), Box<dyn std::error::Error>> {
-    use std::process::Command;
+/// Print the effective configuration this gateway would start with,
+/// resolved the same way `run_server`/`windows_service_impl::run_service`
+/// resolve it at startup: [`GatewayConfig::from_env`] for most settings,
+/// [`get_service_mode`]/[`get_signaling_url`] for the registry-backed P2P
+/// mode settings, and [`find_update_channel`] for any `--update-channel`
+/// passed alongside `--show-config`. Secrets (the P2P API key) are masked.
+fn print_effective_config(args: &[String]) {
+    let config = GatewayConfig::from_env();
+    let channel = find_update_channel(args);
 
-    let mode_str = mode.to_string();
-
-    let output = Command::new("reg")
-        .args([
-            "add",
-            &format!("HKLM\\{}", REGISTRY_KEY),
-            "/v", "ServiceMode",
-            "/t", "REG_SZ",
-            "/d", &mode_str,
-            "/f",
-        ])
-        .output()?;
+    println!("Gateway effective configuration (version {})", config.version);
+    println!();
+    println!("Service mode: {}", get_service_mode());
+    println!("Signaling URL: {}", get_signaling_url());
+    println!();
+    println!("gRPC address: {}", config.grpc_addr);
+    println!("Log format: {:?}", config.log_format);
+    println!(
+        "Metrics: {} ({})",
+        if config.enable_metrics { "enabled" } else { "disabled" },
+        config.metrics_addr
+    );
+    println!("Reflection: {}", if config.enable_reflection { "enabled" } else { "disabled" });
+    println!();
+    println!("Download path: {}", config.download_path.display());
+    println!("Max concurrent jobs: {}", config.max_concurrent_jobs);
+    println!("Job timeout: {}s", config.job_timeout_secs);
+    println!("Scraper pool size: {}", config.scraper_pool_size);
+    println!();
+    println!("Update channel: {}", channel);
+    println!("Update repo: {}/{}", config.update_owner, config.update_repo);
+    println!();
+    println!("P2P STUN servers: {}", config.stun_servers.join(", "));
+    println!(
+        "P2P rate limit: {} req/s (burst {})",
+        config.p2p_rate_limit_rps, config.p2p_rate_limit_burst
+    );
+
+    #[cfg(feature = "p2p")]
+    {
+        let creds_path = P2PCredentials::default_path();
+        println!("Credentials path: {}", creds_path.display());
+        match P2PCredentials::load(&creds_path) {
+            Ok(creds) => {
+                let app_id = if creds.app_id.is_empty() { "(none)" } else { &creds.app_id };
+                println!("Credentials: api_key={}, app_id={}", mask_secret(&creds.api_key), app_id);
+            }
+            Err(e) => println!("Credentials: unavailable ({})", e),
+        }
+    }
+    #[cfg(not(feature = "p2p"))]
+    {
+        println!("Credentials: (not available - this build was compiled without the `p2p` feature)");
+    }
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to set service mode: {}", stderr).into());
+/// Mask a secret for display, keeping only enough of it to recognize which
+/// credential is in use without exposing the rest (e.g. in a support
+/// ticket's `--show-config` output).
+#[cfg(feature = "p2p")]
+fn mask_secret(secret: &str) -> String {
+    if secret.is_empty() {
+        return "(none)".to_string();
     }
+    let visible = secret.chars().take(4).collect::<String>();
+    format!("{}...", visible)
+}
 
+/// Set service mode in registry
+#[cfg(windows)]
+fn set_service_mode(mode: ServiceMode) -> Result<(), Box<dyn std::error::Error>> {
+    registry::set_string("ServiceMode", &mode.to_string())?;
     Ok(())
 }
 
 #[cfg(not(windows))]
-fn set_service_mode(_mode: ServiceMode) -> Result<(), Box<dyn std::error::Error>> {
-    Err("Service mode setting is only supported on Windows".into())
+fn set_service_mode(mode: ServiceMode) -> Result<(), Box<dyn std::error::Error>> {
+    let path = mode_state_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, mode.to_string())?;
+    Ok(())
 }
 
 /// Save API key directly to credentials file
+#[cfg(feature = "p2p")]
 async fn save_api_key(
     api_key: &str,
     creds_path: Option<&str>,
@@ -837,23 +1271,20 @@ async fn save_api_key(
     Ok(())
 }
 
-/// Run P2P client and connect to signaling server
-async fn run_p2p_client(
+/// Verify that saved P2P credentials actually authenticate, without
+/// registering the app or waiting for peers. Exits non-zero on failure so
+/// provisioning scripts can check `$?`.
+#[cfg(feature = "p2p")]
+async fn run_p2p_verify(
     signaling_url: Option<String>,
     creds_path: Option<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use std::collections::HashMap;
-    use std::sync::Arc;
-    use tokio::sync::RwLock;
-
-    // Initialize tracing
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| "gateway=debug,webrtc=info".into()))
+            .unwrap_or_else(|_| "gateway=info".into()))
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load credentials
     let path = creds_path
         .as_ref()
         .map(std::path::PathBuf::from)
@@ -865,336 +1296,87 @@ async fn run_p2p_client(
     println!("Loaded credentials from: {}", path.display());
     println!("API Key: {}...", &creds.api_key[..creds.api_key.len().min(20)]);
 
-    // Determine signaling URL
     let signaling_url = signaling_url
         .or_else(|| std::env::var("P2P_SIGNALING_URL").ok())
-        .unwrap_or_else(|| "wss://cf-wbrtc-auth.m-tama-ramu.workers.dev/ws/app".to_string());
+        .unwrap_or_else(|| GatewayConfig::from_env().signaling_url);
 
-    println!("Connecting to signaling server: {}", signaling_url);
-
-    // Shared state for P2P peer management with multi-peer support
-    struct P2PState {
-        signaling_client: Option<Arc<RwLock<p2p::AuthenticatedSignalingClient>>>,
-        /// Map of peer_id -> peer connection
-        peers: HashMap<String, Arc<p2p::P2PPeer>>,
-        /// Counter for generating unique peer IDs
-        peer_counter: u64,
-    }
-
-    impl P2PState {
-        fn new() -> Self {
-            Self {
-                signaling_client: None,
-                peers: HashMap::new(),
-                peer_counter: 0,
-            }
-        }
+    println!("Verifying against signaling server: {}", signaling_url);
 
-        /// Generate a unique peer ID
-        fn next_peer_id(&mut self) -> String {
-            self.peer_counter += 1;
-            format!("peer-{}", self.peer_counter)
-        }
-
-        /// Remove a peer from the map and return it for cleanup
-        fn remove_peer(&mut self, peer_id: &str) -> Option<Arc<p2p::P2PPeer>> {
-            self.peers.remove(peer_id)
-        }
-
-        /// Get current peer count
-        fn peer_count(&self) -> usize {
-            self.peers.len()
-        }
-    }
-
-    let state = Arc::new(RwLock::new(P2PState::new()));
-
-    // Create gRPC services and combine them with Routes for P2P requests
-    let config = GatewayConfig::from_env();
-    let job_queue = Arc::new(RwLock::new(JobQueue::new()));
-    let scraper_service = EtcScraperService::new(config, job_queue);
-    let pdf_service = PdfGeneratorService::new();
-
-    // Create reflection service for P2P
-    let reflection_service = tonic_reflection::server::Builder::configure()
-        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
-        .build_v1()
-        .expect("Failed to create reflection service");
-
-    // Combine multiple gRPC services into a single Routes service
-    let routes = tonic::service::Routes::new(EtcScraperServer::new(scraper_service))
-        .add_service(PdfGeneratorServer::new(pdf_service))
-        .add_service(reflection_service);
-    let grpc_bridge = Arc::new(TonicServiceBridge::new(routes));
-
-    // Type alias for the gRPC bridge with Routes
-    type RoutesBridge = TonicServiceBridge<tonic::service::Routes>;
+    let config = p2p::SignalingConfig {
+        server_url: signaling_url,
+        api_key: creds.api_key,
+        reconnect: p2p::ReconnectConfig::disabled(),
+        ..Default::default()
+    };
 
-    // Create event handler with state access
-    struct P2PEventHandler {
-        state: Arc<RwLock<P2PState>>,
-        grpc_bridge: Arc<RoutesBridge>,
-    }
+    let mut client = p2p::AuthenticatedSignalingClient::new(config);
 
-    #[async_trait::async_trait]
-    impl p2p::SignalingEventHandler for P2PEventHandler {
-        async fn on_authenticated(&self, payload: p2p::AuthOKPayload) {
+    match client.verify(std::time::Duration::from_secs(15)).await {
+        Ok(payload) => {
             println!("Authenticated! User ID: {}, Type: {}", payload.user_id, payload.user_type);
+            Ok(())
         }
-
-        async fn on_auth_error(&self, payload: p2p::AuthErrorPayload) {
-            eprintln!("Auth error: {}", payload.error);
-        }
-
-        async fn on_app_registered(&self, payload: p2p::AppRegisteredPayload) {
-            println!("App registered! App ID: {}", payload.app_id);
-            println!("Waiting for WebRTC offers from browsers...");
+        Err(e) => {
+            eprintln!("Verification failed: {}", e);
+            std::process::exit(1);
         }
+    }
+}
 
-        async fn on_offer(&self, sdp: String, request_id: Option<String>) {
-            // Generate a unique peer ID for this connection
-            let peer_id = {
-                let mut state = self.state.write().await;
-                state.next_peer_id()
-            };
-
-            println!("Received WebRTC offer (peer_id: {}, request_id: {:?})", peer_id, request_id);
-            tracing::debug!("Offer SDP:\n{}", sdp);
-
-            // Create WebRTC peer and generate answer
-            let peer_config = p2p::PeerConfig {
-                stun_servers: vec![
-                    "stun:stun.l.google.com:19302".to_string(),
-                    "stun:stun1.l.google.com:19302".to_string(),
-                ],
-                turn_servers: vec![],
-            };
-
-            match p2p::P2PPeer::new(peer_id.clone(), peer_config).await {
-                Ok(peer) => {
-                    // Set up handlers
-                    if let Err(e) = peer.setup_handlers().await {
-                        eprintln!("Failed to setup peer handlers: {:?}", e);
-                        return;
-                    }
+/// How often both P2P entry points send an `app_status` heartbeat over the
+/// signaling connection (see [`p2p::P2PRuntime::spawn_status_heartbeat`]).
+#[cfg(feature = "p2p")]
+const STATUS_HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 
-                    if let Err(e) = peer.setup_data_channel_handler().await {
-                        eprintln!("Failed to setup data channel handler: {:?}", e);
-                        return;
-                    }
+/// Run P2P client and connect to signaling server
+///
+/// A thin wrapper around [`p2p::P2PRuntime`]: this function owns loading
+/// credentials, the interactive reconnect policy (via
+/// `connect_with_reconnect`), and waiting on a [`gateway_lib::Shutdown`]
+/// (Ctrl+C, since this path never runs as a service). Everything else - peer
+/// state, the gRPC bridge, and offer/answer/ICE handling - lives in the
+/// shared runtime so it can't drift from `run_p2p_service` again.
+#[cfg(feature = "p2p")]
+async fn run_p2p_client(
+    signaling_url: Option<String>,
+    creds_path: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
 
-                    // Subscribe to peer events
-                    let mut event_rx = peer.subscribe().await;
-                    let peer = Arc::new(peer);
-
-                    // Spawn event handler task with cleanup on disconnect
-                    let peer_clone = peer.clone();
-                    let grpc_bridge = self.grpc_bridge.clone();
-                    let state_clone = self.state.clone();
-                    let peer_id_clone = peer_id.clone();
-                    tokio::spawn(async move {
-                        while let Some(event) = event_rx.recv().await {
-                            match event {
-                                p2p::PeerEvent::Connected => {
-                                    tracing::info!("WebRTC peer {} connected!", peer_id_clone);
-                                    let state = state_clone.read().await;
-                                    tracing::info!("Active peers: {}", state.peer_count());
-                                }
-                                p2p::PeerEvent::Disconnected => {
-                                    tracing::info!("WebRTC peer {} disconnected", peer_id_clone);
-
-                                    // Remove peer from state and cleanup
-                                    let removed_peer = {
-                                        let mut state = state_clone.write().await;
-                                        let peer = state.remove_peer(&peer_id_clone);
-                                        tracing::info!("Removed peer {} from state. Remaining peers: {}", peer_id_clone, state.peer_count());
-                                        peer
-                                    };
-
-                                    // Cleanup peer resources
-                                    if let Some(peer) = removed_peer {
-                                        if let Err(e) = peer.cleanup().await {
-                                            tracing::warn!("Failed to cleanup peer {}: {:?}", peer_id_clone, e);
-                                        } else {
-                                            tracing::debug!("Peer {} cleanup complete", peer_id_clone);
-                                        }
-                                    }
-
-                                    break;
-                                }
-                                p2p::PeerEvent::DataReceived(data) => {
-                                    tracing::debug!("Received data ({} bytes) from peer {}", data.len(), peer_id_clone);
-
-                                    // Process gRPC request using TonicServiceBridge with reflection support
-                                    let result = p2p::grpc_handler::process_request_with_reflection(
-                                        &data,
-                                        &grpc_bridge,
-                                        Some(proto::FILE_DESCRIPTOR_SET),
-                                    ).await;
-
-                                    match result {
-                                        p2p::grpc_handler::GrpcProcessResult::Unary(response) => {
-                                            // Send single unary response
-                                            if let Err(e) = peer_clone.send(&response).await {
-                                                eprintln!("Failed to send gRPC response to {}: {:?}", peer_id_clone, e);
-                                            } else {
-                                                tracing::debug!("Sent unary gRPC response ({} bytes) to {}", response.len(), peer_id_clone);
-                                            }
-                                        }
-                                        p2p::grpc_handler::GrpcProcessResult::Streaming(messages) => {
-                                            // Send each stream message individually
-                                            tracing::info!("Sending {} stream messages to {}", messages.len(), peer_id_clone);
-                                            for (i, msg) in messages.iter().enumerate() {
-                                                if let Err(e) = peer_clone.send(msg).await {
-                                                    eprintln!("Failed to send stream message {}/{} to {}: {:?}", i + 1, messages.len(), peer_id_clone, e);
-                                                    break;
-                                                } else {
-                                                    tracing::debug!("Sent stream message {}/{} ({} bytes) to {}", i + 1, messages.len(), msg.len(), peer_id_clone);
-                                                }
-                                            }
-                                            tracing::info!("Finished sending stream messages to {}", peer_id_clone);
-                                        }
-                                    }
-                                }
-                                p2p::PeerEvent::IceCandidate { candidate, sdp_mid, sdp_mline_index } => {
-                                    tracing::debug!("Local ICE candidate for {}: {} (mid: {:?}, index: {:?})",
-                                        peer_id_clone, candidate, sdp_mid, sdp_mline_index);
-                                }
-                                p2p::PeerEvent::Error(e) => {
-                                    eprintln!("Peer {} error: {}", peer_id_clone, e);
-                                }
-                            }
-                        }
-                        tracing::debug!("Event handler task for peer {} exiting", peer_id_clone);
-                    });
-
-                    // Create answer SDP
-                    match peer.create_answer(&sdp).await {
-                        Ok(answer_sdp) => {
-                            println!("Created WebRTC answer for peer {}", peer_id);
-                            tracing::debug!("Answer SDP:\n{}", answer_sdp);
-
-                            // Send answer via signaling
-                            let state = self.state.read().await;
-                            if let Some(ref client) = state.signaling_client {
-                                let client = client.read().await;
-                                if let Err(e) = client.send_answer(&answer_sdp, request_id.as_deref()).await {
-                                    eprintln!("Failed to send answer: {:?}", e);
-                                } else {
-                                    println!("Answer sent successfully for peer {}!", peer_id);
-
-                                    // Wait a moment for ICE gathering
-                                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-
-                                    // Send local ICE candidates
-                                    let candidates = peer.get_ice_candidates().await;
-                                    for c in candidates {
-                                        let candidate_json = serde_json::json!({
-                                            "candidate": c.candidate,
-                                            "sdpMid": c.sdp_mid,
-                                            "sdpMLineIndex": c.sdp_mline_index,
-                                        });
-                                        if let Err(e) = client.send_ice(candidate_json).await {
-                                            tracing::warn!("Failed to send ICE candidate: {:?}", e);
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Store peer in state map
-                            drop(state);
-                            let mut state = self.state.write().await;
-                            state.peers.insert(peer_id.clone(), peer);
-                            tracing::info!("Peer {} added to state. Total peers: {}", peer_id, state.peer_count());
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to create answer: {:?}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to create peer connection: {:?}", e);
-                }
-            }
-        }
+    // Initialize tracing
+    init_tracing("gateway=debug,webrtc=info", GatewayConfig::log_format_from_env(), false);
 
-        async fn on_answer(&self, sdp: String, app_id: Option<String>) {
-            println!("Received answer (app_id: {:?})", app_id);
-            tracing::debug!("Answer SDP: {}", &sdp[..sdp.len().min(200)]);
-
-            // Apply answer to existing peer connection (if we were the offerer)
-            // For multi-peer, we would need to identify which peer this is for
-            // Currently this is mainly for when we are the offerer (not typical in this setup)
-            let state = self.state.read().await;
-            // Try to find the most recent peer that might be waiting for an answer
-            if let Some((_id, peer)) = state.peers.iter().next() {
-                if let Err(e) = peer.set_remote_answer(&sdp).await {
-                    eprintln!("Failed to set remote answer: {:?}", e);
-                } else {
-                    println!("Remote answer set successfully");
-                }
-            }
-        }
+    // Load credentials
+    let path = creds_path
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(P2PCredentials::default_path);
 
-        async fn on_ice(&self, candidate: serde_json::Value) {
-            tracing::debug!("Received remote ICE candidate: {:?}", candidate);
-
-            // Add ICE candidate to all peer connections
-            // In a more complete implementation, we'd identify which peer this is for
-            let state = self.state.read().await;
-            let candidate_str = candidate.get("candidate")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let sdp_mid = candidate.get("sdpMid")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-            let sdp_mline_index = candidate.get("sdpMLineIndex")
-                .and_then(|v| v.as_u64())
-                .map(|v| v as u16);
-
-            if !candidate_str.is_empty() {
-                // Add to all peers (in practice, should be targeted to specific peer)
-                for (peer_id, peer) in state.peers.iter() {
-                    if let Err(e) = peer.add_ice_candidate(candidate_str, sdp_mid.clone(), sdp_mline_index).await {
-                        tracing::warn!("Failed to add ICE candidate to peer {}: {:?}", peer_id, e);
-                    } else {
-                        tracing::debug!("Added remote ICE candidate to peer {}", peer_id);
-                    }
-                }
-            }
-        }
+    let creds = P2PCredentials::load(&path)
+        .map_err(|e| format!("Failed to load credentials from {}: {}", path.display(), e))?;
 
-        async fn on_error(&self, message: String) {
-            eprintln!("Signaling error: {}", message);
-        }
+    println!("Loaded credentials from: {}", path.display());
+    println!("API Key: {}...", &creds.api_key[..creds.api_key.len().min(20)]);
 
-        async fn on_connected(&self) {
-            tracing::info!("Connected to signaling server!");
-            println!("Connected to signaling server!");
+    // Determine signaling URL
+    let signaling_url = signaling_url
+        .or_else(|| std::env::var("P2P_SIGNALING_URL").ok())
+        .unwrap_or_else(|| GatewayConfig::from_env().signaling_url);
 
-            // Re-register app on reconnection
-            let state = self.state.read().await;
-            if let Some(ref client) = state.signaling_client {
-                let client = client.read().await;
-                if let Err(e) = client.register_app().await {
-                    tracing::error!("Failed to register app on reconnect: {:?}", e);
-                } else {
-                    tracing::info!("App re-registered after reconnection");
-                    println!("App re-registered after reconnection");
-                }
-            }
-        }
+    println!("Connecting to signaling server: {}", signaling_url);
 
-        async fn on_disconnected(&self) {
-            tracing::warn!("Disconnected from signaling server");
-            println!("Disconnected from signaling server (will reconnect automatically)");
-            // Don't cleanup peers - they may still be connected via WebRTC
-            // The signaling server is only needed for establishing new connections
-            let state = self.state.read().await;
-            tracing::info!("Signaling disconnected, keeping {} active peers", state.peer_count());
-        }
+    let config = GatewayConfig::from_env();
+    if let Err(e) = config.validate(true) {
+        tracing::error!("Invalid configuration: {}", e);
+        return Err(e.into());
     }
 
+    let idle_timeout = config.p2p_peer_idle_timeout();
+    let runtime = Arc::new(p2p::P2PRuntime::new(config, true));
+    let reaper_handle = runtime.spawn_peer_reaper(idle_timeout);
+    let heartbeat_handle = runtime.spawn_status_heartbeat(STATUS_HEARTBEAT_INTERVAL);
+
     // Create signaling client
     let signaling_config = p2p::SignalingConfig {
         server_url: signaling_url,
@@ -1205,21 +1387,15 @@ async fn run_p2p_client(
     };
 
     let client = Arc::new(RwLock::new(p2p::AuthenticatedSignalingClient::new(signaling_config)));
-    let handler = Arc::new(P2PEventHandler {
-        state: state.clone(),
-        grpc_bridge: grpc_bridge.clone(),
-    });
 
-    // Store client in state before connecting (needed for on_connected handler)
-    {
-        let mut s = state.write().await;
-        s.signaling_client = Some(client.clone());
-    }
+    // Store client in the runtime before connecting (needed for the
+    // on_connected/on_offer handlers)
+    runtime.set_signaling_client(client.clone()).await;
 
     // Set event handler
     {
         let mut c = client.write().await;
-        c.set_event_handler(handler);
+        c.set_event_handler(runtime.clone());
     }
 
     println!("Connecting to signaling server...");
@@ -1253,11 +1429,10 @@ async fn run_p2p_client(
     println!("Press Ctrl+C to exit.");
     println!();
 
-    // Wait for Ctrl+C
-    tokio::signal::ctrl_c().await?;
+    // Wait for shutdown
+    gateway_lib::Shutdown::new().0.recv().await;
 
     println!("Shutting down...");
-    tracing::info!("Shutdown signal received");
 
     // Stop reconnection by closing the client
     {
@@ -1268,22 +1443,9 @@ async fn run_p2p_client(
     // Wait for reconnect task to finish
     let _ = reconnect_handle.await;
 
-    // Close all peer connections
-    {
-        let peers_to_close: Vec<(String, Arc<p2p::P2PPeer>)> = {
-            let mut state = state.write().await;
-            let peers: Vec<_> = state.peers.drain().collect();
-            tracing::info!("Closing {} peer connections", peers.len());
-            peers
-        };
-
-        for (peer_id, peer) in peers_to_close {
-            tracing::info!("Closing peer {}", peer_id);
-            if let Err(e) = peer.cleanup().await {
-                tracing::warn!("Failed to cleanup peer {}: {:?}", peer_id, e);
-            }
-        }
-    }
+    reaper_handle.abort();
+    heartbeat_handle.abort();
+    runtime.close_all_peers().await;
 
     tracing::info!("Shutdown complete");
     Ok(())
@@ -1291,45 +1453,25 @@ async fn run_p2p_client(
 
 /// Run P2P client as a Windows service with shutdown signal support
 ///
-/// This is a simplified version that initializes tracing for service mode
-/// and uses the signaling client's run_with_reconnect method.
+/// A thin wrapper around [`p2p::P2PRuntime`], like `run_p2p_client`. What's
+/// different here - and the only thing that should be different - is the
+/// reconnect policy (a polling loop instead of `connect_with_reconnect`) and
+/// a shutdown timeout so a hung cleanup can't block the service manager. The
+/// shutdown source itself is the same [`gateway_lib::Shutdown`] every entry
+/// point uses; the service control handler fires it via the paired
+/// [`gateway_lib::ShutdownTrigger`], and it still also races Ctrl+C for when
+/// this is run outside a Windows service.
+#[cfg(feature = "p2p")]
 async fn run_p2p_service(
-    shutdown_rx: Option<tokio::sync::oneshot::Receiver<()>>,
+    shutdown: gateway_lib::Shutdown,
+    is_service: bool,
     signaling_url: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use std::collections::HashMap;
     use std::sync::Arc;
     use tokio::sync::RwLock;
 
     // Initialize tracing for service mode
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| "gateway=info,webrtc=warn".into());
-
-    let is_service = shutdown_rx.is_some();
-
-    #[cfg(windows)]
-    if is_service {
-        let eventlog = tracing_layer_win_eventlog::EventLogLayer::new("GatewayService".to_string());
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
-            .with(eventlog)
-            .init();
-    } else {
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
-            .init();
-    }
-
-    #[cfg(not(windows))]
-    {
-        let _ = is_service;
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
-            .init();
-    }
+    init_tracing("gateway=info,webrtc=warn", GatewayConfig::log_format_from_env(), is_service);
 
     tracing::info!("Starting Gateway P2P Service v{}", env!("CARGO_PKG_VERSION"));
     tracing::info!("Signaling URL: {}", signaling_url);
@@ -1341,247 +1483,16 @@ async fn run_p2p_service(
 
     tracing::info!("Loaded credentials from: {}", path.display());
 
-    // Shared state for P2P peer management (same structure as run_p2p_client)
-    struct P2PState {
-        signaling_client: Option<Arc<RwLock<p2p::AuthenticatedSignalingClient>>>,
-        peers: HashMap<String, Arc<p2p::P2PPeer>>,
-        peer_counter: u64,
-    }
-
-    impl P2PState {
-        fn new() -> Self {
-            Self {
-                signaling_client: None,
-                peers: HashMap::new(),
-                peer_counter: 0,
-            }
-        }
-
-        fn next_peer_id(&mut self) -> String {
-            self.peer_counter += 1;
-            format!("peer-{}", self.peer_counter)
-        }
-
-        #[allow(dead_code)]
-        fn remove_peer(&mut self, peer_id: &str) -> Option<Arc<p2p::P2PPeer>> {
-            self.peers.remove(peer_id)
-        }
-
-        fn peer_count(&self) -> usize {
-            self.peers.len()
-        }
-    }
-
-    let state = Arc::new(RwLock::new(P2PState::new()));
-
-    // Create gRPC services and combine them with Routes for P2P requests
     let config = GatewayConfig::from_env();
-    let job_queue = Arc::new(RwLock::new(JobQueue::new()));
-    let scraper_service = EtcScraperService::new(config, job_queue);
-    let pdf_service = PdfGeneratorService::new();
-
-    // Create reflection service for P2P
-    let reflection_service = tonic_reflection::server::Builder::configure()
-        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
-        .build_v1()
-        .expect("Failed to create reflection service");
-
-    // Combine multiple gRPC services into a single Routes service
-    let routes = tonic::service::Routes::new(EtcScraperServer::new(scraper_service))
-        .add_service(PdfGeneratorServer::new(pdf_service))
-        .add_service(reflection_service);
-    let grpc_bridge = Arc::new(TonicServiceBridge::new(routes));
-
-    type RoutesBridge = TonicServiceBridge<tonic::service::Routes>;
-
-    // Event handler
-    struct P2PEventHandler {
-        state: Arc<RwLock<P2PState>>,
-        grpc_bridge: Arc<RoutesBridge>,
+    if let Err(e) = config.validate(true) {
+        tracing::error!("Invalid configuration: {}", e);
+        return Err(e.into());
     }
 
-    #[async_trait::async_trait]
-    impl p2p::SignalingEventHandler for P2PEventHandler {
-        async fn on_authenticated(&self, payload: p2p::AuthOKPayload) {
-            tracing::info!("Authenticated! User ID: {}, Type: {}", payload.user_id, payload.user_type);
-            // App registration is now handled in run_p2p_service after initial connection
-        }
-
-        async fn on_auth_error(&self, payload: p2p::AuthErrorPayload) {
-            tracing::error!("Auth error: {}", payload.error);
-        }
-
-        async fn on_app_registered(&self, payload: p2p::AppRegisteredPayload) {
-            tracing::info!("App registered! App ID: {}", payload.app_id);
-        }
-
-        async fn on_offer(&self, sdp: String, request_id: Option<String>) {
-            let peer_id = {
-                let mut state = self.state.write().await;
-                state.next_peer_id()
-            };
-
-            tracing::info!("Received WebRTC offer (peer_id: {}, request_id: {:?})", peer_id, request_id);
-
-            let peer_config = p2p::PeerConfig {
-                stun_servers: vec![
-                    "stun:stun.l.google.com:19302".to_string(),
-                    "stun:stun1.l.google.com:19302".to_string(),
-                ],
-                turn_servers: vec![],
-            };
-
-            match p2p::P2PPeer::new(peer_id.clone(), peer_config).await {
-                Ok(peer) => {
-                    if let Err(e) = peer.setup_handlers().await {
-                        tracing::error!("Failed to setup peer handlers: {:?}", e);
-                        return;
-                    }
-
-                    if let Err(e) = peer.setup_data_channel_handler().await {
-                        tracing::error!("Failed to setup data channel handler: {:?}", e);
-                        return;
-                    }
-
-                    let mut event_rx = peer.subscribe().await;
-                    let peer = Arc::new(peer);
-
-                    // Spawn event handler task
-                    let peer_clone = peer.clone();
-                    let grpc_bridge = self.grpc_bridge.clone();
-                    let state_clone = self.state.clone();
-                    let peer_id_clone = peer_id.clone();
-                    tokio::spawn(async move {
-                        while let Some(event) = event_rx.recv().await {
-                            match event {
-                                p2p::PeerEvent::Connected => {
-                                    tracing::info!("WebRTC peer {} connected!", peer_id_clone);
-                                }
-                                p2p::PeerEvent::Disconnected => {
-                                    tracing::info!("WebRTC peer {} disconnected", peer_id_clone);
-                                    let mut state = state_clone.write().await;
-                                    if let Some(peer) = state.peers.remove(&peer_id_clone) {
-                                        if let Err(e) = peer.cleanup().await {
-                                            tracing::warn!("Failed to cleanup peer {}: {:?}", peer_id_clone, e);
-                                        }
-                                    }
-                                    break;
-                                }
-                                p2p::PeerEvent::DataReceived(data) => {
-                                    let result = p2p::grpc_handler::process_request_with_reflection(
-                                        &data,
-                                        &grpc_bridge,
-                                        Some(proto::FILE_DESCRIPTOR_SET),
-                                    ).await;
-                                    match result {
-                                        p2p::grpc_handler::GrpcProcessResult::Unary(response) => {
-                                            if let Err(e) = peer_clone.send(&response).await {
-                                                tracing::error!("Failed to send response to {}: {:?}", peer_id_clone, e);
-                                            }
-                                        }
-                                        p2p::grpc_handler::GrpcProcessResult::Streaming(messages) => {
-                                            for msg in messages {
-                                                if let Err(e) = peer_clone.send(&msg).await {
-                                                    tracing::error!("Failed to send stream message to {}: {:?}", peer_id_clone, e);
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                p2p::PeerEvent::IceCandidate { .. } => {}
-                                p2p::PeerEvent::Error(e) => {
-                                    tracing::error!("Peer {} error: {}", peer_id_clone, e);
-                                }
-                            }
-                        }
-                    });
-
-                    // Create answer
-                    match peer.create_answer(&sdp).await {
-                        Ok(answer_sdp) => {
-                            let state = self.state.read().await;
-                            if let Some(ref client) = state.signaling_client {
-                                let client = client.read().await;
-                                if let Err(e) = client.send_answer(&answer_sdp, request_id.as_deref()).await {
-                                    tracing::error!("Failed to send answer: {:?}", e);
-                                } else {
-                                    tracing::info!("Answer sent for peer {}", peer_id);
-
-                                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-
-                                    let candidates = peer.get_ice_candidates().await;
-                                    for c in candidates {
-                                        let candidate_json = serde_json::json!({
-                                            "candidate": c.candidate,
-                                            "sdpMid": c.sdp_mid,
-                                            "sdpMLineIndex": c.sdp_mline_index,
-                                        });
-                                        if let Err(e) = client.send_ice(candidate_json).await {
-                                            tracing::warn!("Failed to send ICE candidate: {:?}", e);
-                                        }
-                                    }
-                                }
-                            }
-
-                            drop(state);
-                            let mut state = self.state.write().await;
-                            state.peers.insert(peer_id.clone(), peer);
-                            tracing::info!("Peer {} added. Total: {}", peer_id, state.peer_count());
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to create answer: {:?}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create peer: {:?}", e);
-                }
-            }
-        }
-
-        async fn on_answer(&self, _sdp: String, _app_id: Option<String>) {
-            tracing::debug!("Received answer (unexpected in server mode)");
-        }
-
-        async fn on_ice(&self, candidate: serde_json::Value) {
-            let candidate_str = candidate.get("candidate")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let sdp_mid = candidate.get("sdpMid")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-            let sdp_mline_index = candidate.get("sdpMLineIndex")
-                .and_then(|v| v.as_u64())
-                .map(|v| v as u16);
-
-            if !candidate_str.is_empty() {
-                let state = self.state.read().await;
-                for (peer_id, peer) in state.peers.iter() {
-                    if let Err(e) = peer.add_ice_candidate(candidate_str, sdp_mid.clone(), sdp_mline_index).await {
-                        tracing::warn!("Failed to add ICE candidate to peer {}: {:?}", peer_id, e);
-                    }
-                }
-            }
-        }
-
-        async fn on_error(&self, message: String) {
-            tracing::error!("Signaling error: {}", message);
-        }
-
-        async fn on_connected(&self) {
-            tracing::info!("Connected to signaling server");
-            // App registration happens in on_authenticated after auth succeeds
-        }
-
-        async fn on_disconnected(&self) {
-            tracing::warn!("Disconnected from signaling server");
-            // Don't cleanup peers - they may still be connected via WebRTC
-            // The signaling server is only needed for establishing new connections
-            let state = self.state.read().await;
-            tracing::info!("Signaling disconnected, keeping {} active peers", state.peer_count());
-        }
-    }
+    let idle_timeout = config.p2p_peer_idle_timeout();
+    let runtime = Arc::new(p2p::P2PRuntime::new(config, false));
+    let reaper_handle = runtime.spawn_peer_reaper(idle_timeout);
+    let heartbeat_handle = runtime.spawn_status_heartbeat(STATUS_HEARTBEAT_INTERVAL);
 
     // Create signaling client
     let signaling_config = p2p::SignalingConfig {
@@ -1593,21 +1504,15 @@ async fn run_p2p_service(
     };
 
     let client = Arc::new(RwLock::new(p2p::AuthenticatedSignalingClient::new(signaling_config)));
-    let handler = Arc::new(P2PEventHandler {
-        state: state.clone(),
-        grpc_bridge: grpc_bridge.clone(),
-    });
 
-    // Store client in state before connecting (needed for on_connected handler)
-    {
-        let mut s = state.write().await;
-        s.signaling_client = Some(client.clone());
-    }
+    // Store client in the runtime before connecting (needed for the
+    // on_connected/on_offer handlers)
+    runtime.set_signaling_client(client.clone()).await;
 
     // Set event handler
     {
         let mut c = client.write().await;
-        c.set_event_handler(handler);
+        c.set_event_handler(runtime.clone());
     }
 
     tracing::info!("P2P service starting, connecting to signaling server...");
@@ -1676,16 +1581,7 @@ async fn run_p2p_service(
     });
 
     // Wait for shutdown signal
-    match shutdown_rx {
-        Some(rx) => {
-            let _ = rx.await;
-            tracing::info!("Shutdown signal received");
-        }
-        None => {
-            tokio::signal::ctrl_c().await?;
-            tracing::info!("Ctrl+C received");
-        }
-    }
+    shutdown.recv().await;
 
     tracing::info!("Shutting down P2P service...");
 
@@ -1702,15 +1598,10 @@ async fn run_p2p_service(
         reconnect_handle.abort();
         let _ = reconnect_handle.await;
 
-        // Clean up peers
-        {
-            let mut state = state.write().await;
-            let peers: Vec<_> = state.peers.drain().collect();
-            for (peer_id, peer) in peers {
-                tracing::info!("Closing peer {}", peer_id);
-                let _ = peer.cleanup().await;
-            }
-        }
+        // Stop the idle-peer reaper and status heartbeat, then clean up peers
+        reaper_handle.abort();
+        heartbeat_handle.abort();
+        runtime.close_all_peers().await;
 
         // Now we can get the write lock and close properly
         {
@@ -1739,21 +1630,26 @@ fn find_update_channel(args: &[String]) -> UpdateChannel {
 /// Find --update-from argument value (tag name)
 fn find_update_from_tag(args: &[String]) -> Option<String> {
     for i in 0..args.len() {
-        if args[i] == "--update-from" && i + 1 < args.len() {
+        if (args[i] == "--update-from" || args[i] == "--update-to") && i + 1 < args.len() {
             return Some(args[i + 1].clone());
         }
     }
     None
 }
 
-/// Get update configuration from environment or defaults
+/// Get update configuration from the config file/environment or defaults
 fn get_update_config(channel: UpdateChannel) -> UpdateConfig {
-    let owner = std::env::var("GITHUB_OWNER")
-        .unwrap_or_else(|_| "yhonda-ohishi-pub-dev".to_string());
-    let repo = std::env::var("GITHUB_REPO")
-        .unwrap_or_else(|_| "rust-router".to_string());
+    let config = GatewayConfig::from_env();
+    let mut update_config = UpdateConfig::new_github(config.update_owner, config.update_repo)
+        .with_channel(channel)
+        .with_github_token(config.update_github_token)
+        .with_api_base_url(config.update_api_base_url);
+
+    if let Some(manifest_url) = config.update_manifest_url {
+        update_config = update_config.with_manifest_url(manifest_url);
+    }
 
-    UpdateConfig::new_github(owner, repo).with_channel(channel)
+    update_config
 }
 
 /// Check for available updates
@@ -1786,6 +1682,44 @@ async fn check_for_update(channel: UpdateChannel) -> Result<(), Box<dyn std::err
     Ok(())
 }
 
+/// List available releases on the given update channel, noting whether a
+/// matching asset exists for the current platform so operators can pick a
+/// version to pin to with `--update-from <tag>`.
+async fn list_releases_cli(channel: UpdateChannel) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Listing releases (channel: {})...", channel);
+    println!();
+
+    let include_prerelease = channel == UpdateChannel::Beta;
+    let config = get_update_config(channel);
+    let updater = AutoUpdater::new(config);
+
+    match updater.list_releases(include_prerelease).await {
+        Ok(releases) => {
+            if releases.is_empty() {
+                println!("No releases found.");
+                return Ok(());
+            }
+
+            for release in &releases {
+                let published = release.published_at.as_deref().unwrap_or("unknown");
+                let kind = if release.prerelease { "prerelease" } else { "stable" };
+                let asset_status = if updater.has_platform_asset(release) {
+                    "asset available"
+                } else {
+                    "no matching asset"
+                };
+                println!("{:<20} {:<24} {:<10} {}", release.tag_name, published, kind, asset_status);
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to list releases: {}", e);
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
 /// Wait for user to press Enter
 fn wait_for_keypress() {
     println!();
@@ -1793,6 +1727,27 @@ fn wait_for_keypress() {
     let _ = std::io::stdin().read_line(&mut String::new());
 }
 
+/// Ask the operator to confirm rolling back to an older version, to guard
+/// against an accidental `--update-to` with a stale tag. Returns true if
+/// the operator answered "y" or "yes".
+fn confirm_downgrade(target_version: &str) -> bool {
+    println!();
+    println!(
+        "WARNING: {} is older than the currently running version {}.",
+        target_version,
+        env!("CARGO_PKG_VERSION")
+    );
+    print!("Continue with this downgrade? [y/N] ");
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
 /// Perform the update
 async fn perform_update(channel: UpdateChannel, prefer_msi: bool) -> Result<(), Box<dyn std::error::Error>> {
     let update_type = if prefer_msi { "MSI" } else { "exe" };
@@ -1866,6 +1821,12 @@ async fn perform_update_from_tag(tag: &str, prefer_msi: bool) -> Result<(), Box<
                     println!("  {}", line);
                 }
             }
+
+            if updater.is_downgrade(&version.version) && !confirm_downgrade(&version.version) {
+                println!("Downgrade cancelled.");
+                return Ok(());
+            }
+
             println!();
             println!("Downloading from: {}", version.download_url);
             println!();
@@ -0,0 +1,53 @@
+//! Prometheus metrics for the gateway.
+//!
+//! This module only owns installing the global recorder and serving the
+//! scrape endpoint; the actual counters/histograms are recorded at the call
+//! sites that know about them (`p2p::grpc_handler::TonicServiceBridge::call`,
+//! the P2P peer event loop in `p2p::runtime`, `grpc::scraper_service`'s background
+//! job processor, and `updater::AutoUpdater`).
+//!
+//! Metric names:
+//! - `grpc_requests_total{method,status}` - counter, one per bridged gRPC call
+//! - `grpc_request_duration_seconds{method}` - histogram
+//! - `grpc_request_bytes{method}` / `grpc_response_bytes{method}` - histograms of
+//!   message bytes summed across a call, also logged as a warning above the
+//!   configurable `p2p_large_message_threshold_bytes`/`p2p_slow_request_threshold_secs`
+//!   thresholds (see `TonicServiceBridge::call`)
+//! - `p2p_active_peers` - gauge, set to the current peer count on connect/disconnect
+//! - `p2p_offers_rejected_total` - counter, one per offer rejected for being over
+//!   the configurable `p2p_max_peers` limit (see `P2PRuntime::on_offer`)
+//! - `p2p_peer_recreations_total{outcome}` - counter, one per attempt to transparently
+//!   recreate a failed/disconnected peer (see `P2PRuntime::spawn_peer_event_loop`),
+//!   `outcome` is `success`, `failed`, or `exhausted` (past `p2p_peer_recreate_max_retries`)
+//! - `scrape_job_duration_seconds` - histogram, one sample per finished `scrape_multiple` job
+//! - `update_checks_total` - counter, one per `AutoUpdater::check_for_update` call
+
+use std::net::SocketAddr;
+
+use axum::{routing::get, Router};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+
+/// Install the global Prometheus recorder. Must be called once, before any
+/// `metrics::counter!`/`histogram!`/`gauge!` call site is reached, or those
+/// macro calls silently no-op against the default no-op recorder.
+pub fn install_recorder() -> PrometheusHandle {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("failed to install Prometheus metrics recorder")
+}
+
+/// Serve the installed recorder's `/metrics` endpoint on `addr` until the
+/// process exits or the server errors. Spawned alongside the gRPC server so
+/// both run on the same tokio runtime.
+pub async fn serve(addr: SocketAddr, handle: PrometheusHandle) -> std::io::Result<()> {
+    let app = Router::new().route(
+        "/metrics",
+        get(move || {
+            let handle = handle.clone();
+            async move { handle.render() }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Metrics server listening on {}", addr);
+    axum::serve(listener, app).await
+}
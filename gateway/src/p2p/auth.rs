@@ -3,7 +3,7 @@
 //! Implements OAuth setup flow for P2P authentication using polling method.
 //! Compatible with cf-wbrtc-auth server.
 
-use crate::p2p::credentials::{CredentialsError, P2PCredentials};
+use crate::p2p::credentials::{CredentialsError, P2PCredentials, CREDENTIALS_VERSION};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -51,6 +51,24 @@ pub struct SetupConfig {
 
     /// Whether to automatically open browser
     pub auto_open_browser: bool,
+
+    /// `User-Agent` header sent with every request, so an auth server that
+    /// rejects or rate-limits unidentified clients (or just wants to
+    /// attribute traffic) has something to key on. Default:
+    /// `gateway/{CARGO_PKG_VERSION}`, matching `updater`'s GitHub API client.
+    pub user_agent: String,
+
+    /// Per-request HTTP timeout in seconds (default: 30). Bounds a single
+    /// `initiate_setup`/poll/`refresh_api_key` call; unrelated to
+    /// `timeout_secs`, which bounds the whole polling loop.
+    pub request_timeout_secs: u64,
+
+    /// Skip the browser launch entirely and instead print the setup URL
+    /// (and user code, if the server sends one) for the operator to read
+    /// and enter manually - the only option on a headless box, where
+    /// `open::that` would fail or open nothing useful anyway. Defaults to
+    /// [`is_headless`]'s autodetection; set explicitly to override it.
+    pub headless: bool,
 }
 
 impl Default for SetupConfig {
@@ -61,10 +79,22 @@ impl Default for SetupConfig {
             poll_interval_secs: 2,
             timeout_secs: 300,
             auto_open_browser: true,
+            user_agent: format!("gateway/{}", env!("CARGO_PKG_VERSION")),
+            request_timeout_secs: 30,
+            headless: is_headless(),
         }
     }
 }
 
+/// Best-effort detection of a headless Linux host: no `DISPLAY` set, so
+/// there's no X server for `open::that` to hand the URL to. Other
+/// platforms (Windows, macOS) are assumed to have a usable GUI shell even
+/// when run over a remote session, matching how `open::that` behaves
+/// there.
+fn is_headless() -> bool {
+    cfg!(target_os = "linux") && std::env::var_os("DISPLAY").is_none()
+}
+
 /// Response from setup initiation
 #[derive(Debug, Deserialize)]
 struct SetupInitResponse {
@@ -73,6 +103,12 @@ struct SetupInitResponse {
 
     /// URL for user to visit
     url: String,
+
+    /// Short code the user types in at `url` to confirm they're setting up
+    /// this device, if the server uses a device-code style flow. Not every
+    /// server sends one.
+    #[serde(default)]
+    user_code: Option<String>,
 }
 
 /// Response from setup polling
@@ -91,6 +127,10 @@ struct SetupPollResponse {
     /// Refresh token (only present when status is "complete")
     refresh_token: Option<String>,
 
+    /// Seconds until the API key expires, if the server reports one
+    #[serde(default)]
+    expires_in: Option<i64>,
+
     /// Error message (only present when status is "error")
     #[serde(default)]
     error: Option<String>,
@@ -103,6 +143,36 @@ struct RefreshResponse {
     api_key: String,
     app_id: String,
     refresh_token: String,
+
+    /// Seconds until the new API key expires, if the server reports one
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// Cap on the backoff delay computed by [`poll_backoff_delay`], so a user
+/// who's slow to authenticate still gets polled at a reasonable rate
+/// instead of tailing off to minutes between checks.
+const MAX_POLL_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Delay before poll attempt number `attempt` (0-indexed), doubling from
+/// `base` each attempt and capped at [`MAX_POLL_BACKOFF`]. Keeps the first
+/// few polls responsive while backing off for a user who takes a while to
+/// finish authenticating, so the auth server isn't hit every
+/// `poll_interval_secs` for the full `timeout_secs` window.
+fn poll_backoff_delay(attempt: u32, base: Duration) -> Duration {
+    let delay_ms = base.as_millis().saturating_mul(1u128 << attempt.min(16));
+    Duration::from_millis(delay_ms.min(MAX_POLL_BACKOFF.as_millis()) as u64)
+}
+
+/// Convert a server-reported `expires_in` (seconds from now) into an absolute
+/// unix timestamp, as stored on [`P2PCredentials::expires_at`].
+fn expires_at_from_now(expires_in: Option<i64>) -> Option<i64> {
+    let expires_in = expires_in?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    Some(now + expires_in)
 }
 
 /// Request for token refresh
@@ -122,7 +192,8 @@ impl OAuthSetup {
     /// Create a new OAuth setup handler
     pub fn new(config: SetupConfig) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .timeout(Duration::from_secs(config.request_timeout_secs))
+            .user_agent(config.user_agent.clone())
             .build()
             .expect("Failed to create HTTP client");
 
@@ -139,13 +210,24 @@ impl OAuthSetup {
         // Step 1: Initiate setup
         let init_response = self.initiate_setup().await?;
 
-        tracing::info!(
-            "OAuth setup initiated. Please authenticate at: {}",
-            init_response.url
-        );
-
-        // Step 2: Open browser if configured
-        if self.config.auto_open_browser {
+        // Step 2: Show the user how to authenticate. On a headless box (or
+        // with auto_open_browser disabled) there's no point trying to
+        // launch a browser, so print the URL - and user code, if the
+        // server sent one - clearly enough to read over SSH.
+        if self.config.headless || !self.config.auto_open_browser {
+            println!("To finish setup, open this URL in any browser:");
+            println!();
+            println!("    {}", init_response.url);
+            if let Some(code) = &init_response.user_code {
+                println!();
+                println!("Then enter this code when prompted: {}", code);
+            }
+            println!();
+        } else {
+            tracing::info!(
+                "OAuth setup initiated. Please authenticate at: {}",
+                init_response.url
+            );
             if let Err(e) = open::that(&init_response.url) {
                 tracing::warn!("Failed to open browser: {}. Please open the URL manually.", e);
             }
@@ -187,23 +269,61 @@ impl OAuthSetup {
             .map_err(|e| AuthError::InvalidResponse(e.to_string()))
     }
 
-    /// Poll for setup completion
+    /// Poll for setup completion. The wait between polls backs off
+    /// exponentially (see [`poll_backoff_delay`]) so a slow-to-authenticate
+    /// user doesn't generate load on the auth server for the full
+    /// `timeout_secs` window, and races each wait against Ctrl+C so a user
+    /// who closes the browser without finishing auth can abort immediately
+    /// instead of waiting it out.
     async fn poll_for_completion(&self, setup_id: &str) -> Result<P2PCredentials, AuthError> {
         let url = format!("{}/setup/poll?token={}", self.config.auth_server_url, setup_id);
         let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
         let timeout = Duration::from_secs(self.config.timeout_secs);
         let start = std::time::Instant::now();
+        let mut attempt: u32 = 0;
 
         loop {
             if start.elapsed() > timeout {
                 return Err(AuthError::SetupExpired);
             }
 
-            tokio::time::sleep(poll_interval).await;
+            tokio::select! {
+                _ = tokio::time::sleep(poll_backoff_delay(attempt, poll_interval)) => {
+                    attempt += 1;
+                }
+                result = tokio::signal::ctrl_c() => {
+                    if let Err(e) = result {
+                        tracing::warn!("Failed to listen for Ctrl+C: {}", e);
+                    }
+                    println!("Cancelled by user, aborting OAuth setup.");
+                    return Err(AuthError::SetupExpired);
+                }
+            }
 
             let response = self.client.get(&url).send().await?;
+            let status = response.status();
 
-            if !response.status().is_success() {
+            if !status.is_success() {
+                let is_transient = status.is_server_error()
+                    || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || status == reqwest::StatusCode::REQUEST_TIMEOUT;
+
+                if !is_transient && status.is_client_error() {
+                    // A 4xx (bad/expired token, unauthorized, ...) won't
+                    // resolve itself by polling again - surface it instead
+                    // of silently retrying until the overall timeout. 429
+                    // and 408 are the exception: they're the server asking
+                    // us to slow down or retry, not a rejection of the setup.
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(AuthError::SetupFailed(format!(
+                        "Server returned {}: {}",
+                        status, body
+                    )));
+                }
+                // 5xx, 429, 408 and other transient failures: worth a log
+                // line so a persistent outage doesn't just look like
+                // "pending" until timeout, but still worth retrying.
+                tracing::warn!("Setup poll request failed with {}, retrying", status);
                 continue;
             }
 
@@ -223,11 +343,14 @@ impl OAuthSetup {
                         .ok_or_else(|| AuthError::InvalidResponse("Missing api_key".to_string()))?;
                     let app_id = poll_response.app_id.unwrap_or_default();
                     let refresh_token = poll_response.refresh_token;
+                    let expires_at = expires_at_from_now(poll_response.expires_in);
 
                     return Ok(P2PCredentials {
+                        version: CREDENTIALS_VERSION,
                         api_key,
                         app_id,
                         refresh_token,
+                        expires_at,
                     });
                 }
                 "expired" => {
@@ -280,9 +403,11 @@ impl OAuthSetup {
             .map_err(|e| AuthError::InvalidResponse(e.to_string()))?;
 
         Ok(P2PCredentials {
+            version: CREDENTIALS_VERSION,
             api_key: refresh_response.api_key,
             app_id: refresh_response.app_id,
             refresh_token: Some(refresh_response.refresh_token),
+            expires_at: expires_at_from_now(refresh_response.expires_in),
         })
     }
 }
@@ -341,6 +466,23 @@ pub async fn refresh_if_needed(
     }
 }
 
+/// Refresh credentials only if they're within `threshold` of expiry (or
+/// already expired). Returns the input credentials unchanged, without a
+/// network call, when no refresh is needed. Intended to be called before
+/// [`crate::p2p::AuthenticatedSignalingClient::connect`] so a stale API key
+/// doesn't have to fail auth first.
+pub async fn refresh_if_expiring(
+    credentials: &P2PCredentials,
+    auth_server_url: &str,
+    threshold: Duration,
+) -> Result<P2PCredentials, AuthError> {
+    if !credentials.needs_refresh(threshold) {
+        return Ok(credentials.clone());
+    }
+
+    refresh_if_needed(credentials, auth_server_url).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -354,6 +496,9 @@ mod tests {
         assert_eq!(config.poll_interval_secs, 2);
         assert_eq!(config.timeout_secs, 300);
         assert!(config.auto_open_browser);
+        assert_eq!(config.user_agent, format!("gateway/{}", env!("CARGO_PKG_VERSION")));
+        assert_eq!(config.request_timeout_secs, 30);
+        assert_eq!(config.headless, is_headless());
     }
 
     #[test]
@@ -364,6 +509,9 @@ mod tests {
             poll_interval_secs: 5,
             timeout_secs: 600,
             auto_open_browser: false,
+            user_agent: "test-agent/1.0".to_string(),
+            request_timeout_secs: 10,
+            headless: true,
         };
 
         assert_eq!(config.auth_server_url, "https://auth.example.com");
@@ -371,6 +519,26 @@ mod tests {
         assert_eq!(config.poll_interval_secs, 5);
         assert_eq!(config.timeout_secs, 600);
         assert!(!config.auto_open_browser);
+        assert_eq!(config.user_agent, "test-agent/1.0");
+        assert_eq!(config.request_timeout_secs, 10);
+        assert!(config.headless);
+    }
+
+    #[test]
+    fn test_is_headless_respects_display_on_linux() {
+        if cfg!(target_os = "linux") {
+            let saved = std::env::var_os("DISPLAY");
+            std::env::remove_var("DISPLAY");
+            assert!(is_headless());
+            std::env::set_var("DISPLAY", ":0");
+            assert!(!is_headless());
+            match saved {
+                Some(v) => std::env::set_var("DISPLAY", v),
+                None => std::env::remove_var("DISPLAY"),
+            }
+        } else {
+            assert!(!is_headless());
+        }
     }
 
     #[test]
@@ -385,6 +553,16 @@ mod tests {
         assert_eq!(setup.config.app_name, "Gateway");
     }
 
+    #[test]
+    fn test_poll_backoff_delay_doubles_then_caps() {
+        let base = Duration::from_secs(2);
+        assert_eq!(poll_backoff_delay(0, base), Duration::from_secs(2));
+        assert_eq!(poll_backoff_delay(1, base), Duration::from_secs(4));
+        assert_eq!(poll_backoff_delay(2, base), Duration::from_secs(8));
+        assert_eq!(poll_backoff_delay(3, base), MAX_POLL_BACKOFF);
+        assert_eq!(poll_backoff_delay(20, base), MAX_POLL_BACKOFF);
+    }
+
     #[test]
     fn test_auth_error_display() {
         let err = AuthError::SetupExpired;
@@ -406,9 +584,11 @@ mod tests {
     #[tokio::test]
     async fn test_refresh_if_needed_no_token() {
         let creds = P2PCredentials {
+            version: CREDENTIALS_VERSION,
             api_key: "test-key".to_string(),
             app_id: "test-app".to_string(),
             refresh_token: None,
+            expires_at: None,
         };
 
         let result = refresh_if_needed(&creds, "https://auth.example.com").await;
@@ -422,6 +602,45 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_refresh_if_expiring_skips_when_not_expiring() {
+        let creds = P2PCredentials {
+            version: CREDENTIALS_VERSION,
+            api_key: "test-key".to_string(),
+            app_id: "test-app".to_string(),
+            refresh_token: None,
+            expires_at: Some(i64::MAX),
+        };
+
+        let result = refresh_if_expiring(&creds, "https://auth.example.com", Duration::from_secs(300))
+            .await
+            .unwrap();
+
+        assert_eq!(result.api_key, creds.api_key);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_if_expiring_falls_through_when_expiring() {
+        let creds = P2PCredentials {
+            version: CREDENTIALS_VERSION,
+            api_key: "test-key".to_string(),
+            app_id: "test-app".to_string(),
+            refresh_token: None,
+            expires_at: Some(0),
+        };
+
+        let result = refresh_if_expiring(&creds, "https://auth.example.com", Duration::from_secs(300))
+            .await;
+
+        assert!(result.is_err());
+        match result {
+            Err(AuthError::RefreshFailed(msg)) => {
+                assert!(msg.contains("No refresh token"));
+            }
+            _ => panic!("Expected RefreshFailed error"),
+        }
+    }
+
     /// Integration test with real server
     /// Run with: cargo test test_real_refresh --lib -- --ignored
     #[tokio::test]
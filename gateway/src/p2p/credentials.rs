@@ -5,8 +5,31 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
+/// Set from `--p2p-creds-plaintext`. When `true`, [`P2PCredentials::save`]
+/// and [`P2PCredentials::load`] never touch OS-backed secure storage and
+/// always read/write the refresh token in plaintext, matching the pre-
+/// encryption behavior exactly.
+static PLAINTEXT_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Opt out of OS-backed secure storage for the P2P refresh token
+/// (`--p2p-creds-plaintext`). Call once at startup, before any credentials
+/// are loaded or saved.
+pub fn set_plaintext_only(value: bool) {
+    PLAINTEXT_ONLY.store(value, Ordering::Relaxed);
+}
+
+fn plaintext_only() -> bool {
+    PLAINTEXT_ONLY.load(Ordering::Relaxed)
+}
+
+/// Current version of the persisted credentials format. Bump this whenever
+/// a field is added that older [`P2PCredentials::load`] callers must
+/// migrate/default, and handle the old value in [`P2PCredentials::migrate`].
+pub const CREDENTIALS_VERSION: u32 = 1;
+
 /// Errors that can occur during credential operations
 #[derive(Error, Debug)]
 pub enum CredentialsError {
@@ -21,11 +44,20 @@ pub enum CredentialsError {
 
     #[error("Invalid credentials format")]
     InvalidFormat,
+
+    #[error("Credentials file is version {0}, but this gateway only understands up to version {CREDENTIALS_VERSION}. Update the gateway before using these credentials.")]
+    UnsupportedVersion(u32),
 }
 
 /// P2P Credentials containing API key and optional refresh token
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct P2PCredentials {
+    /// Format version. Files written before this field existed parse as
+    /// `0` (the [`serde(default)`] for `u32`) and get migrated forward by
+    /// [`P2PCredentials::migrate`] on load.
+    #[serde(default)]
+    pub version: u32,
+
     /// API key for authentication
     pub api_key: String,
 
@@ -36,24 +68,65 @@ pub struct P2PCredentials {
     /// Refresh token for obtaining new API keys
     #[serde(default)]
     pub refresh_token: Option<String>,
+
+    /// Unix timestamp (seconds) the API key expires at, when known. `None`
+    /// means the server didn't report an expiry, so we can't tell and treat
+    /// the key as not expiring until auth actually fails.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
 }
 
 impl P2PCredentials {
     /// Create new credentials with API key only
     pub fn new(api_key: String) -> Self {
         Self {
+            version: CREDENTIALS_VERSION,
             api_key,
             app_id: String::new(),
             refresh_token: None,
+            expires_at: None,
         }
     }
 
     /// Create credentials with all fields
     pub fn with_refresh_token(api_key: String, app_id: String, refresh_token: String) -> Self {
         Self {
+            version: CREDENTIALS_VERSION,
             api_key,
             app_id,
             refresh_token: Some(refresh_token),
+            expires_at: None,
+        }
+    }
+
+    /// Upgrade an older persisted format in place, defaulting any field it
+    /// didn't have. `version` fields newer than this build understands are
+    /// rejected instead of silently ignored, since we can't know what a
+    /// future field means or whether skipping it is safe.
+    fn migrate(mut self) -> Result<Self, CredentialsError> {
+        if self.version > CREDENTIALS_VERSION {
+            return Err(CredentialsError::UnsupportedVersion(self.version));
+        }
+
+        // Every version <= CREDENTIALS_VERSION already defaults its new
+        // fields via #[serde(default)] (JSON) or an explicit default in
+        // parse_env_format (ENV), so upgrading is just bumping the marker.
+        self.version = CREDENTIALS_VERSION;
+        Ok(self)
+    }
+
+    /// Whether the API key is expired or will expire within `threshold`.
+    /// Returns `false` when there's no known expiry.
+    pub fn needs_refresh(&self, threshold: std::time::Duration) -> bool {
+        match self.expires_at {
+            Some(expires_at) => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0);
+                expires_at - now <= threshold.as_secs() as i64
+            }
+            None => false,
         }
     }
 
@@ -74,20 +147,51 @@ impl P2PCredentials {
         let content = std::fs::read_to_string(path)?;
 
         // Try JSON format first
-        if content.trim().starts_with('{') {
-            return serde_json::from_str(&content)
-                .map_err(|e| CredentialsError::Parse(e.to_string()));
+        let mut creds = if content.trim().starts_with('{') {
+            serde_json::from_str(&content).map_err(|e| CredentialsError::Parse(e.to_string()))?
+        } else {
+            Self::parse_env_format(&content)?
+        };
+
+        creds.fill_refresh_token_from_secure_storage(&content);
+
+        creds.migrate()
+    }
+
+    /// If the refresh token wasn't in the file (because it was protected by
+    /// secure storage on save, or simply never written), try to recover it
+    /// from DPAPI/the OS keyring. Leaves `refresh_token` as-is on any
+    /// failure - a missing refresh token isn't fatal, callers already treat
+    /// `None` as "can't auto-refresh".
+    fn fill_refresh_token_from_secure_storage(&mut self, raw_content: &str) {
+        if self.refresh_token.is_some() || plaintext_only() {
+            return;
         }
 
-        // Parse ENV format
-        Self::parse_env_format(&content)
+        if let Some(blob) = raw_content.lines().find_map(|line| {
+            line.trim()
+                .split_once('=')
+                .and_then(|(key, value)| (key.trim() == "P2P_REFRESH_TOKEN_ENC").then(|| value.trim().to_string()))
+        }) {
+            match secure_store::unprotect_dpapi(&blob) {
+                Ok(token) => self.refresh_token = Some(token),
+                Err(e) => tracing::warn!("Failed to decrypt stored P2P refresh token: {}", e),
+            }
+            return;
+        }
+
+        if let Ok(token) = secure_store::retrieve_keyring(&self.app_id) {
+            self.refresh_token = Some(token);
+        }
     }
 
     /// Parse ENV format credentials
     fn parse_env_format(content: &str) -> Result<Self, CredentialsError> {
+        let mut version = 0;
         let mut api_key = None;
         let mut app_id = String::new();
         let mut refresh_token = None;
+        let mut expires_at = None;
 
         for line in content.lines() {
             let line = line.trim();
@@ -100,6 +204,9 @@ impl P2PCredentials {
                 let value = value.trim().trim_matches('"').trim_matches('\'');
 
                 match key {
+                    "P2P_VERSION" | "VERSION" => {
+                        version = value.parse::<u32>().unwrap_or(0);
+                    }
                     "P2P_API_KEY" | "API_KEY" => api_key = Some(value.to_string()),
                     "P2P_APP_ID" | "APP_ID" => app_id = value.to_string(),
                     "P2P_REFRESH_TOKEN" | "REFRESH_TOKEN" => {
@@ -107,6 +214,9 @@ impl P2PCredentials {
                             refresh_token = Some(value.to_string());
                         }
                     }
+                    "P2P_EXPIRES_AT" | "EXPIRES_AT" => {
+                        expires_at = value.parse::<i64>().ok();
+                    }
                     _ => {}
                 }
             }
@@ -115,13 +225,41 @@ impl P2PCredentials {
         let api_key = api_key.ok_or(CredentialsError::InvalidFormat)?;
 
         Ok(Self {
+            version,
             api_key,
             app_id,
             refresh_token,
+            expires_at,
         })
     }
 
+    /// Parse and load credentials from an `.env`-style file only - the
+    /// format [`save`]/[`to_env_file`] actually write. Unlike [`load`],
+    /// this never attempts to sniff/parse JSON, so it's the right entry
+    /// point when the caller already knows the file is `.env`-style (e.g.
+    /// round-tripping a freshly written [`default_path`]).
+    pub fn from_env_file<P: AsRef<Path>>(path: P) -> Result<Self, CredentialsError> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Err(CredentialsError::NotFound(
+                path.display().to_string(),
+            ));
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let mut creds = Self::parse_env_format(&content)?;
+        creds.fill_refresh_token_from_secure_storage(&content);
+        creds.migrate()
+    }
+
     /// Save credentials to a file in ENV format
+    ///
+    /// The refresh token is encrypted at rest via OS-backed secure storage
+    /// when available (DPAPI on Windows, the OS keyring elsewhere) unless
+    /// [`set_plaintext_only`] has opted out. If secure storage isn't
+    /// available, falls back to writing the refresh token in plaintext and
+    /// logs a warning.
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CredentialsError> {
         let path = path.as_ref();
 
@@ -130,14 +268,19 @@ impl P2PCredentials {
             std::fs::create_dir_all(parent)?;
         }
 
-        let mut content = format!("P2P_API_KEY={}\n", self.api_key);
+        let mut content = format!("P2P_VERSION={}\n", CREDENTIALS_VERSION);
+        content.push_str(&format!("P2P_API_KEY={}\n", self.api_key));
 
         if !self.app_id.is_empty() {
             content.push_str(&format!("P2P_APP_ID={}\n", self.app_id));
         }
 
         if let Some(ref token) = self.refresh_token {
-            content.push_str(&format!("P2P_REFRESH_TOKEN={}\n", token));
+            content.push_str(&self.encode_refresh_token_line(token));
+        }
+
+        if let Some(expires_at) = self.expires_at {
+            content.push_str(&format!("P2P_EXPIRES_AT={}\n", expires_at));
         }
 
         std::fs::write(path, content)?;
@@ -145,6 +288,36 @@ impl P2PCredentials {
         Ok(())
     }
 
+    /// Alias for [`save`] under the name that pairs with [`from_env_file`],
+    /// for callers who want to be explicit that the on-disk format is
+    /// `.env`-style rather than relying on [`save`]'s default.
+    pub fn to_env_file<P: AsRef<Path>>(&self, path: P) -> Result<(), CredentialsError> {
+        self.save(path)
+    }
+
+    /// Encode the refresh token line to write to the credentials file,
+    /// protecting it via secure storage when possible.
+    fn encode_refresh_token_line(&self, token: &str) -> String {
+        if !plaintext_only() {
+            match secure_store::protect(&self.app_id, token) {
+                Ok(secure_store::Protected::Encrypted(blob)) => {
+                    return format!("P2P_REFRESH_TOKEN_ENC={}\n", blob);
+                }
+                Ok(secure_store::Protected::StoredInKeyring) => {
+                    return String::new();
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Secure storage unavailable for P2P refresh token ({}), saving in plaintext",
+                        e
+                    );
+                }
+            }
+        }
+
+        format!("P2P_REFRESH_TOKEN={}\n", token)
+    }
+
     /// Save credentials as JSON
     pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<(), CredentialsError> {
         let path = path.as_ref();
@@ -162,7 +335,10 @@ impl P2PCredentials {
     }
 
     /// Get default credentials file path
-    /// Uses C:\ProgramData\Gateway on Windows for service compatibility
+    ///
+    /// Always an `.env`-style path (see [`save`]/[`from_env_file`]), never
+    /// JSON - uses C:\ProgramData\Gateway on Windows for service
+    /// compatibility.
     pub fn default_path() -> std::path::PathBuf {
         Self::service_path()
     }
@@ -195,12 +371,128 @@ impl P2PCredentials {
     }
 }
 
+/// OS-backed protection for the P2P refresh token.
+///
+/// Windows has no ambient keyring API reachable without extra services, so
+/// we use DPAPI to encrypt the token and embed the ciphertext directly in
+/// the credentials file. Everywhere else we use the OS keyring (Keychain /
+/// Secret Service / etc. via the `keyring` crate), which keeps the token
+/// out of the file entirely.
+mod secure_store {
+    /// Outcome of [`protect`]: either a ciphertext to embed in the
+    /// credentials file, or confirmation that the token now lives in the
+    /// OS keyring and doesn't need to be written to the file at all.
+    pub enum Protected {
+        Encrypted(String),
+        StoredInKeyring,
+    }
+
+    pub fn protect(app_id: &str, token: &str) -> Result<Protected, String> {
+        #[cfg(windows)]
+        {
+            let _ = app_id;
+            dpapi::encrypt(token).map(Protected::Encrypted)
+        }
+        #[cfg(not(windows))]
+        {
+            keyring_entry(app_id)?
+                .set_password(token)
+                .map_err(|e| e.to_string())?;
+            Ok(Protected::StoredInKeyring)
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn unprotect_dpapi(blob_base64: &str) -> Result<String, String> {
+        dpapi::decrypt(blob_base64)
+    }
+
+    #[cfg(not(windows))]
+    pub fn unprotect_dpapi(_blob_base64: &str) -> Result<String, String> {
+        Err("DPAPI-encrypted credentials require Windows".to_string())
+    }
+
+    #[cfg(not(windows))]
+    pub fn retrieve_keyring(app_id: &str) -> Result<String, String> {
+        keyring_entry(app_id)?.get_password().map_err(|e| e.to_string())
+    }
+
+    #[cfg(windows)]
+    pub fn retrieve_keyring(_app_id: &str) -> Result<String, String> {
+        Err("No OS keyring backend on Windows; refresh token is DPAPI-encrypted".to_string())
+    }
+
+    #[cfg(not(windows))]
+    fn keyring_entry(app_id: &str) -> Result<keyring::Entry, String> {
+        let account = if app_id.is_empty() { "default" } else { app_id };
+        keyring::Entry::new("gateway-p2p-refresh-token", account).map_err(|e| e.to_string())
+    }
+
+    #[cfg(windows)]
+    mod dpapi {
+        use base64::Engine;
+        use windows::Win32::Foundation::HLOCAL;
+        use windows::Win32::Security::Cryptography::{
+            CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+        };
+        use windows::Win32::System::Memory::LocalFree;
+
+        pub fn encrypt(token: &str) -> Result<String, String> {
+            unsafe {
+                let input = CRYPT_INTEGER_BLOB {
+                    cbData: token.len() as u32,
+                    pbData: token.as_ptr() as *mut u8,
+                };
+                let mut output = CRYPT_INTEGER_BLOB::default();
+
+                CryptProtectData(&input, None, None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output)
+                    .map_err(|e| format!("CryptProtectData failed: {}", e))?;
+
+                let bytes = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+                LocalFree(HLOCAL(output.pbData as *mut _));
+
+                Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+            }
+        }
+
+        pub fn decrypt(blob_base64: &str) -> Result<String, String> {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(blob_base64)
+                .map_err(|e| format!("Invalid base64: {}", e))?;
+
+            unsafe {
+                let mut input = CRYPT_INTEGER_BLOB {
+                    cbData: bytes.len() as u32,
+                    pbData: bytes.as_ptr() as *mut u8,
+                };
+                let mut output = CRYPT_INTEGER_BLOB::default();
+
+                CryptUnprotectData(&mut input, None, None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output)
+                    .map_err(|e| format!("CryptUnprotectData failed: {}", e))?;
+
+                let decrypted = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+                LocalFree(HLOCAL(output.pbData as *mut _));
+
+                String::from_utf8(decrypted).map_err(|e| format!("Decrypted token is not UTF-8: {}", e))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
+    use std::sync::Mutex;
     use tempfile::NamedTempFile;
 
+    /// `PLAINTEXT_ONLY` is a single process-wide `AtomicBool`, so tests that
+    /// flip it would otherwise race with each other under `cargo test`'s
+    /// default parallel execution - one test's `set_plaintext_only(false)`
+    /// could land between another's `set_plaintext_only(true)` and its
+    /// assertions. Hold this for the duration of any such test instead.
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
     #[test]
     fn test_parse_env_format() {
         let content = r#"
@@ -242,6 +534,12 @@ P2P_REFRESH_TOKEN=refresh-token-456
 
     #[test]
     fn test_save_and_load() {
+        let _guard = TEST_LOCK.lock().unwrap();
+
+        // Secure storage is unavailable/irrelevant in test environments, so
+        // pin plaintext mode to make the round-trip deterministic.
+        set_plaintext_only(true);
+
         let file = NamedTempFile::new().unwrap();
         let path = file.path().to_path_buf();
         drop(file);
@@ -260,5 +558,97 @@ P2P_REFRESH_TOKEN=refresh-token-456
         assert_eq!(loaded.refresh_token, creds.refresh_token);
 
         std::fs::remove_file(&path).ok();
+        set_plaintext_only(false);
+    }
+
+    #[test]
+    fn test_to_env_file_from_env_file_round_trip() {
+        let _guard = TEST_LOCK.lock().unwrap();
+
+        // Secure storage is unavailable/irrelevant in test environments, so
+        // pin plaintext mode to make the round-trip deterministic.
+        set_plaintext_only(true);
+
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_path_buf();
+        drop(file);
+
+        let creds = P2PCredentials::with_refresh_token(
+            "roundtrip-key".to_string(),
+            "roundtrip-app".to_string(),
+            "roundtrip-token".to_string(),
+        );
+
+        creds.to_env_file(&path).unwrap();
+
+        let loaded = P2PCredentials::from_env_file(&path).unwrap();
+        assert_eq!(loaded.version, creds.version);
+        assert_eq!(loaded.api_key, creds.api_key);
+        assert_eq!(loaded.app_id, creds.app_id);
+        assert_eq!(loaded.refresh_token, creds.refresh_token);
+        assert_eq!(loaded.expires_at, creds.expires_at);
+
+        std::fs::remove_file(&path).ok();
+        set_plaintext_only(false);
+    }
+
+    #[test]
+    fn test_load_env_without_version_migrates_to_current() {
+        let content = "P2P_API_KEY=legacy-key\nP2P_APP_ID=legacy-app\n";
+        let creds = P2PCredentials::parse_env_format(content).unwrap();
+        assert_eq!(creds.version, 0);
+
+        let migrated = creds.migrate().unwrap();
+        assert_eq!(migrated.version, CREDENTIALS_VERSION);
+        assert_eq!(migrated.api_key, "legacy-key");
+        assert_eq!(migrated.expires_at, None);
+    }
+
+    #[test]
+    fn test_load_json_without_version_migrates_to_current() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, r#"{{"api_key": "json-key"}}"#).unwrap();
+
+        let creds = P2PCredentials::load(file.path()).unwrap();
+        assert_eq!(creds.version, CREDENTIALS_VERSION);
+        assert_eq!(creds.api_key, "json-key");
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_future_version() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"{{"version": {}, "api_key": "future-key"}}"#,
+            CREDENTIALS_VERSION + 1
+        )
+        .unwrap();
+
+        let err = P2PCredentials::load(file.path()).unwrap_err();
+        assert!(matches!(err, CredentialsError::UnsupportedVersion(v) if v == CREDENTIALS_VERSION + 1));
+    }
+
+    #[test]
+    fn test_plaintext_only_writes_refresh_token_in_clear() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        set_plaintext_only(true);
+
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_path_buf();
+        drop(file);
+
+        let creds = P2PCredentials::with_refresh_token(
+            "plain-key".to_string(),
+            "plain-app".to_string(),
+            "plain-token".to_string(),
+        );
+        creds.save(&path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("P2P_REFRESH_TOKEN=plain-token"));
+        assert!(!content.contains("P2P_REFRESH_TOKEN_ENC"));
+
+        std::fs::remove_file(&path).ok();
+        set_plaintext_only(false);
     }
 }
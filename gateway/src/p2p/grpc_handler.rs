@@ -27,6 +27,7 @@ use tokio::sync::Mutex;
 use tonic::body::BoxBody;
 use tonic::Status;
 use tower::Service;
+use tracing::Instrument;
 
 /// gRPC status codes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -52,11 +53,22 @@ pub enum StatusCode {
 }
 
 /// Parsed gRPC request from DataChannel
+///
+/// `messages` holds every client data frame in the order they were sent.
+/// Unary and server-streaming calls send exactly one; client-streaming and
+/// bidi-streaming calls send one per client message.
 #[derive(Debug)]
 pub struct GrpcRequest {
     pub path: String,
     pub headers: HashMap<String, String>,
-    pub message: Vec<u8>,
+    pub messages: Vec<Vec<u8>>,
+}
+
+impl GrpcRequest {
+    /// The first client message, or an empty slice if none were sent
+    pub fn message(&self) -> &[u8] {
+        self.messages.first().map(|m| m.as_slice()).unwrap_or(&[])
+    }
 }
 
 /// gRPC response to send back via DataChannel
@@ -66,6 +78,16 @@ pub struct GrpcResponse {
     pub messages: Vec<Vec<u8>>,
     pub status: StatusCode,
     pub status_message: Option<String>,
+    /// Trailing metadata beyond `grpc-status`/`grpc-message` (which have
+    /// their own fields above), e.g. `grpc-status-details-bin` carrying rich
+    /// error details. Captured from the real HTTP trailers by
+    /// `TonicServiceBridge::parse_http_response` and re-emitted by
+    /// `encode_response`/`encode_trailer_frame`.
+    pub trailers: HashMap<String, String>,
+    /// Whether `messages` are gzip-compressed (set when the caller sent
+    /// `grpc-accept-encoding: gzip`), so `encode_response` can mark the
+    /// outgoing data frames with the compressed flag.
+    pub compressed: bool,
 }
 
 impl GrpcResponse {
@@ -76,6 +98,8 @@ impl GrpcResponse {
             messages: vec![message],
             status: StatusCode::Ok,
             status_message: None,
+            trailers: HashMap::new(),
+            compressed: false,
         }
     }
 
@@ -86,6 +110,8 @@ impl GrpcResponse {
             messages: vec![],
             status,
             status_message: Some(message.into()),
+            trailers: HashMap::new(),
+            compressed: false,
         }
     }
 
@@ -93,6 +119,124 @@ impl GrpcResponse {
     pub fn unimplemented(method: &str) -> Self {
         Self::error(StatusCode::Unimplemented, format!("Method not implemented: {}", method))
     }
+
+    /// Successful response carrying multiple messages, e.g. one per
+    /// server-streaming item (see `encode_streaming_response`).
+    pub fn ok_stream(messages: Vec<Vec<u8>>) -> Self {
+        Self {
+            headers: HashMap::new(),
+            messages,
+            status: StatusCode::Ok,
+            status_message: None,
+            trailers: HashMap::new(),
+            compressed: false,
+        }
+    }
+
+    /// Start a [`GrpcResponseBuilder`] for cases that need more than one
+    /// header or a status set alongside the message, e.g. `handle_*`
+    /// functions that want to attach `x-request-id` without a separate
+    /// `headers.insert` call.
+    pub fn builder() -> GrpcResponseBuilder {
+        GrpcResponseBuilder::new()
+    }
+
+    /// Set a header, consuming and returning `self` for chaining onto a
+    /// response built elsewhere (e.g. `handle_list_services(fds).with_header(...)`).
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Builder for [`GrpcResponse`], for assembling a response with headers,
+/// one or more messages, and a status in a single chained expression instead
+/// of constructing the struct literal or mutating fields after the fact.
+#[derive(Debug)]
+pub struct GrpcResponseBuilder {
+    headers: HashMap<String, String>,
+    messages: Vec<Vec<u8>>,
+    status: StatusCode,
+    status_message: Option<String>,
+    trailers: HashMap<String, String>,
+    compressed: bool,
+}
+
+impl GrpcResponseBuilder {
+    fn new() -> Self {
+        Self {
+            headers: HashMap::new(),
+            messages: Vec::new(),
+            status: StatusCode::Ok,
+            status_message: None,
+            trailers: HashMap::new(),
+            compressed: false,
+        }
+    }
+
+    /// Append one message. Call repeatedly to build up a streaming response.
+    pub fn message(mut self, message: Vec<u8>) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Replace the message list wholesale.
+    pub fn messages(mut self, messages: Vec<Vec<u8>>) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set a custom trailer, e.g. `grpc-status-details-bin`. `grpc-status`/
+    /// `grpc-message` are set via `status`/`status_message` instead.
+    pub fn trailer(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.trailers.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn status_message(mut self, message: impl Into<String>) -> Self {
+        self.status_message = Some(message.into());
+        self
+    }
+
+    pub fn compressed(mut self, compressed: bool) -> Self {
+        self.compressed = compressed;
+        self
+    }
+
+    pub fn build(self) -> GrpcResponse {
+        GrpcResponse {
+            headers: self.headers,
+            messages: self.messages,
+            status: self.status,
+            status_message: self.status_message,
+            trailers: self.trailers,
+            compressed: self.compressed,
+        }
+    }
+}
+
+/// Result of parsing as many complete gRPC-Web frames as `data` contains.
+struct ParsedFrames {
+    /// Decoded message payloads, in order (trailer frames are never included).
+    messages: Vec<Vec<u8>>,
+    /// True if `data` ended mid-frame (a short header, or a header whose
+    /// declared length runs past the end of `data`). The caller should
+    /// buffer `data[consumed..]` together with more incoming bytes and
+    /// re-parse, rather than treat a truncated payload as a complete message.
+    incomplete: bool,
+    /// Number of bytes consumed by complete frames. `data[consumed..]` is
+    /// the unparsed remainder, which is empty unless `incomplete` is true.
+    consumed: usize,
 }
 
 /// Parse multiple gRPC frames from response body
@@ -103,7 +247,8 @@ impl GrpcResponse {
 /// - data (N bytes): message payload
 ///
 /// Returns a vector of message payloads (data frames only, excludes trailers)
-fn parse_grpc_frames(data: &[u8]) -> Vec<Vec<u8>> {
+/// as [`ParsedFrames::messages`], alongside how much of `data` was consumed.
+fn parse_grpc_frames(data: &[u8]) -> ParsedFrames {
     let mut messages = Vec::new();
     let mut offset = 0;
 
@@ -116,25 +261,44 @@ fn parse_grpc_frames(data: &[u8]) -> Vec<Vec<u8>> {
             data[offset + 4],
         ]) as usize;
 
-        offset += 5;
-
-        if offset + msg_len > data.len() {
-            // Incomplete frame, take what we have
-            if flags == 0x00 && offset < data.len() {
-                messages.push(data[offset..].to_vec());
-            }
-            break;
+        if offset + 5 + msg_len > data.len() {
+            // Trailing partial frame: leave it (header included) unconsumed
+            // rather than decode a truncated payload.
+            return ParsedFrames { messages, incomplete: true, consumed: offset };
         }
 
         // Only include data frames (0x00), skip trailer frames (0x01)
         if flags == 0x00 {
-            messages.push(data[offset..offset + msg_len].to_vec());
+            messages.push(data[offset + 5..offset + 5 + msg_len].to_vec());
         }
 
-        offset += msg_len;
+        offset += 5 + msg_len;
     }
 
-    messages
+    // Fewer than 5 bytes left over is also an incomplete trailing frame (a
+    // partial length-prefix header).
+    let incomplete = offset < data.len();
+    ParsedFrames { messages, incomplete, consumed: offset }
+}
+
+/// True if `headers` declares gRPC-Web's base64-text content type
+/// (`application/grpc-web-text`, optionally with a `+proto`/`+json` codec
+/// suffix). Some browser gRPC-Web clients send this instead of raw binary
+/// `application/grpc-web` frames.
+fn is_grpc_web_text(headers: &HashMap<String, String>) -> bool {
+    headers
+        .get("content-type")
+        .map(|ct| ct.starts_with("application/grpc-web-text"))
+        .unwrap_or(false)
+}
+
+/// Copy the request's `content-type` onto the response, so `encode_response`
+/// encodes the response frames the same way `parse_request` decoded the
+/// request's (e.g. both base64 for `application/grpc-web-text`).
+fn propagate_content_type(response: &mut GrpcResponse, request: &GrpcRequest) {
+    if let Some(content_type) = request.headers.get("content-type") {
+        response.headers.insert("content-type".to_string(), content_type.clone());
+    }
 }
 
 /// Parse a gRPC-Web request from raw DataChannel data
@@ -180,32 +344,66 @@ pub fn parse_request(data: &[u8]) -> Result<GrpcRequest, String> {
     let headers: HashMap<String, String> = serde_json::from_str(&headers_json)
         .map_err(|e| format!("Invalid headers JSON: {}", e))?;
 
-    // Rest is gRPC-Web frames
+    // Rest is gRPC-Web frames. Client-streaming and bidi-streaming calls send
+    // one data frame per client message, so collect all of them rather than
+    // just the first. Client requests never carry trailer frames, so unlike
+    // `parse_grpc_frames` (used for service responses), flag 0x01 here means
+    // "gzip-compressed data frame" and must be decompressed, not dropped.
+    //
+    // `application/grpc-web-text` clients base64-encode the frames, so
+    // decode before framing.
     let frames_data = &data[offset..];
-
-    // Parse gRPC-Web data frame to extract message
-    let message = if frames_data.len() >= 5 {
-        let flags = frames_data[0];
-        let msg_len = u32::from_be_bytes([
-            frames_data[1], frames_data[2], frames_data[3], frames_data[4]
-        ]) as usize;
-
-        if flags == 0x00 && frames_data.len() >= 5 + msg_len {
-            frames_data[5..5 + msg_len].to_vec()
-        } else {
-            vec![]
-        }
+    let messages = if is_grpc_web_text(&headers) {
+        use base64::Engine;
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(frames_data)
+            .map_err(|e| format!("Invalid base64 grpc-web-text body: {}", e))?;
+        parse_client_frames(&decoded)?
     } else {
-        vec![]
+        parse_client_frames(frames_data)?
     };
 
     Ok(GrpcRequest {
         path,
         headers,
-        message,
+        messages,
     })
 }
 
+/// Parse client-sent gRPC-Web data frames, honoring the compressed-data flag
+/// (0x01) by gzip-decompressing the payload. Unlike response frames, client
+/// requests never carry a trailer frame.
+fn parse_client_frames(data: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+
+    while offset + 5 <= data.len() {
+        let flags = data[offset];
+        let msg_len = u32::from_be_bytes([
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+            data[offset + 4],
+        ]) as usize;
+        offset += 5;
+
+        if offset + msg_len > data.len() {
+            break;
+        }
+
+        let payload = &data[offset..offset + msg_len];
+        match flags {
+            0x00 => messages.push(payload.to_vec()),
+            0x01 => messages.push(gzip_decompress(payload)?),
+            other => return Err(format!("unsupported gRPC-Web frame flag: {:#x}", other)),
+        }
+
+        offset += msg_len;
+    }
+
+    Ok(messages)
+}
+
 /// Stream message flags for streaming RPC over DataChannel
 pub const STREAM_FLAG_DATA: u8 = 0x00;
 pub const STREAM_FLAG_END: u8 = 0x01;
@@ -243,13 +441,17 @@ fn encode_grpc_data_frame(message: &[u8]) -> Vec<u8> {
     result
 }
 
-/// Encode a trailer frame with status
-fn encode_trailer_frame(status: StatusCode, status_message: Option<&str>) -> Vec<u8> {
+/// Encode a trailer frame: `grpc-status`, optional `grpc-message`, and any
+/// custom trailers the response carries (e.g. `grpc-status-details-bin`).
+fn encode_trailer_frame(response: &GrpcResponse) -> Vec<u8> {
     let mut trailers = Vec::new();
-    trailers.push(format!("grpc-status: {}", status as u32));
-    if let Some(msg) = status_message {
+    trailers.push(format!("grpc-status: {}", response.status as u32));
+    if let Some(ref msg) = response.status_message {
         trailers.push(format!("grpc-message: {}", msg));
     }
+    for (key, value) in &response.trailers {
+        trailers.push(format!("{}: {}", key, value));
+    }
     let trailer_text = trailers.join("\r\n") + "\r\n";
     let trailer_bytes = trailer_text.as_bytes();
 
@@ -278,33 +480,33 @@ pub fn encode_response(response: &GrpcResponse) -> Vec<u8> {
     // Write headers
     result.extend_from_slice(headers_bytes);
 
-    // Write data frames
+    // Build the data + trailer frames in their own buffer so that, for
+    // `application/grpc-web-text` responses, the whole thing can be
+    // base64-encoded as one unit (mirroring how `parse_request` decodes a
+    // grpc-web-text client's frames before parsing them).
+    let mut frames = Vec::new();
+
+    // Write data frames. When the response is gzip-compressed, each data
+    // frame's flag carries the compressed bit (0x01) instead of 0x00.
+    let data_flag = if response.compressed { 0x01 } else { 0x00 };
     for message in &response.messages {
-        // flags = 0x00 (data frame)
-        result.push(0x00);
+        frames.push(data_flag);
         // length (big-endian u32)
         let msg_len = message.len() as u32;
-        result.extend_from_slice(&msg_len.to_be_bytes());
+        frames.extend_from_slice(&msg_len.to_be_bytes());
         // message data
-        result.extend_from_slice(message);
+        frames.extend_from_slice(message);
     }
 
     // Write trailer frame
-    let mut trailers = Vec::new();
-    trailers.push(format!("grpc-status: {}", response.status as u32));
-    if let Some(ref msg) = response.status_message {
-        trailers.push(format!("grpc-message: {}", msg));
-    }
-    let trailer_text = trailers.join("\r\n") + "\r\n";
-    let trailer_bytes = trailer_text.as_bytes();
+    frames.extend_from_slice(&encode_trailer_frame(response));
 
-    // flags = 0x01 (trailer frame)
-    result.push(0x01);
-    // length (big-endian u32)
-    let trailer_len = trailer_bytes.len() as u32;
-    result.extend_from_slice(&trailer_len.to_be_bytes());
-    // trailer data
-    result.extend_from_slice(trailer_bytes);
+    if is_grpc_web_text(&response.headers) {
+        use base64::Engine;
+        result.extend_from_slice(base64::engine::general_purpose::STANDARD.encode(&frames).as_bytes());
+    } else {
+        result.extend_from_slice(&frames);
+    }
 
     result
 }
@@ -318,13 +520,48 @@ pub trait GrpcHandler: Send + Sync {
 /// Default handler that routes to registered methods
 pub struct GrpcRouter {
     handlers: HashMap<String, Box<dyn Fn(&GrpcRequest) -> GrpcResponse + Send + Sync>>,
+    /// Method paths known from a descriptor set, grouped by owning service
+    /// (full name, e.g. `etc_scraper.EtcScraper`), so
+    /// [`register_service`](Self::register_service) can wire every method of
+    /// a service at once instead of the caller re-typing each path. Empty
+    /// for routers built with [`new`](Self::new).
+    known_service_paths: HashMap<String, Vec<String>>,
 }
 
 impl GrpcRouter {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            known_service_paths: HashMap::new(),
+        }
+    }
+
+    /// Build a router pre-populated with every method path found in
+    /// `file_descriptor_set`, each returning [`GrpcResponse::unimplemented`]
+    /// until a handler is attached via [`register`](Self::register) or
+    /// [`register_service`](Self::register_service). Unlike a bare
+    /// `register` call per path, a typo'd service/method name passed to
+    /// `register_service` is then caught at setup time instead of silently
+    /// falling through to `Unimplemented` the first time a request for it
+    /// arrives.
+    pub fn from_descriptor(file_descriptor_set: &[u8]) -> Self {
+        let mut router = Self::new();
+
+        for service in extract_services_from_descriptor(file_descriptor_set) {
+            let mut paths = Vec::with_capacity(service.methods.len());
+            for method in &service.methods {
+                let path = format!("/{}/{}", service.name, method);
+                let unimplemented_path = path.clone();
+                router.handlers.insert(
+                    path.clone(),
+                    Box::new(move |_req: &GrpcRequest| GrpcResponse::unimplemented(&unimplemented_path)),
+                );
+                paths.push(path);
+            }
+            router.known_service_paths.insert(service.name, paths);
         }
+
+        router
     }
 
     /// Register a handler for a method path
@@ -335,6 +572,33 @@ impl GrpcRouter {
         self.handlers.insert(path.to_string(), Box::new(handler));
     }
 
+    /// Wire every method path known for `service_name` (from
+    /// [`from_descriptor`](Self::from_descriptor)) to `handler`, so one
+    /// [`GrpcHandler`] impl can serve a whole service without the caller
+    /// re-listing each method path.
+    ///
+    /// Returns an error naming `service_name` if this router wasn't built
+    /// with [`from_descriptor`](Self::from_descriptor) or the name isn't
+    /// present in the descriptor set it was built from.
+    pub fn register_service(
+        &mut self,
+        service_name: &str,
+        handler: Arc<dyn GrpcHandler>,
+    ) -> Result<(), String> {
+        let paths = self
+            .known_service_paths
+            .get(service_name)
+            .ok_or_else(|| format!("Unknown service in descriptor set: {}", service_name))?
+            .clone();
+
+        for path in paths {
+            let handler = handler.clone();
+            self.handlers.insert(path, Box::new(move |req| handler.handle(req)));
+        }
+
+        Ok(())
+    }
+
     /// Handle a request
     pub fn handle(&self, request: &GrpcRequest) -> GrpcResponse {
         if let Some(handler) = self.handlers.get(&request.path) {
@@ -362,22 +626,36 @@ pub fn process_request(data: &[u8], router: &GrpcRouter) -> Vec<u8> {
             if let Some(request_id) = request.headers.get("x-request-id") {
                 response.headers.insert("x-request-id".to_string(), request_id.clone());
             }
+            propagate_content_type(&mut response, &request);
 
             encode_response(&response)
         }
         Err(e) => {
             tracing::error!("Failed to parse gRPC request: {}", e);
-            let response = GrpcResponse::error(StatusCode::Internal, e);
+            let status = status_for_parse_error(&e);
+            let response = GrpcResponse::error(status, e);
             encode_response(&response)
         }
     }
 }
 
+/// Default size (bytes), summed across all messages of one side of a call,
+/// above which [`TonicServiceBridge::call`] logs a large-message warning.
+/// Chosen well above a typical scrape response but well under what would
+/// visibly congest a WebRTC DataChannel.
+pub const DEFAULT_LARGE_MESSAGE_THRESHOLD_BYTES: usize = 1024 * 1024;
+
+/// Default call duration above which [`TonicServiceBridge::call`] logs a
+/// slow-request warning.
+pub const DEFAULT_SLOW_REQUEST_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Bridge to tonic gRPC services
 ///
 /// This allows routing P2P DataChannel requests to tonic-generated services.
 pub struct TonicServiceBridge<S> {
     service: Arc<Mutex<S>>,
+    large_message_threshold_bytes: usize,
+    slow_request_threshold: std::time::Duration,
 }
 
 impl<S> TonicServiceBridge<S>
@@ -389,17 +667,67 @@ where
     pub fn new(service: S) -> Self {
         Self {
             service: Arc::new(Mutex::new(service)),
+            large_message_threshold_bytes: DEFAULT_LARGE_MESSAGE_THRESHOLD_BYTES,
+            slow_request_threshold: DEFAULT_SLOW_REQUEST_THRESHOLD,
         }
     }
 
+    /// Override the size/duration thresholds that trigger a warning log (see
+    /// [`DEFAULT_LARGE_MESSAGE_THRESHOLD_BYTES`]/[`DEFAULT_SLOW_REQUEST_THRESHOLD`]).
+    pub fn with_thresholds(mut self, large_message_threshold_bytes: usize, slow_request_threshold: std::time::Duration) -> Self {
+        self.large_message_threshold_bytes = large_message_threshold_bytes;
+        self.slow_request_threshold = slow_request_threshold;
+        self
+    }
+
     /// Call the tonic service with a gRPC request
     pub async fn call(&self, request: &GrpcRequest) -> GrpcResponse {
-        // Build gRPC frame from message
+        let started_at = std::time::Instant::now();
+        let response = self.call_inner(request).await;
+        let elapsed = started_at.elapsed();
+
+        metrics::counter!(
+            "grpc_requests_total",
+            "method" => request.path.clone(),
+            "status" => format!("{:?}", response.status),
+        )
+        .increment(1);
+        metrics::histogram!("grpc_request_duration_seconds", "method" => request.path.clone())
+            .record(elapsed.as_secs_f64());
+
+        let request_bytes: usize = request.messages.iter().map(|m| m.len()).sum();
+        let response_bytes: usize = response.messages.iter().map(|m| m.len()).sum();
+        metrics::histogram!("grpc_request_bytes", "method" => request.path.clone())
+            .record(request_bytes as f64);
+        metrics::histogram!("grpc_response_bytes", "method" => request.path.clone())
+            .record(response_bytes as f64);
+
+        if request_bytes > self.large_message_threshold_bytes
+            || response_bytes > self.large_message_threshold_bytes
+            || elapsed > self.slow_request_threshold
+        {
+            let request_id = request.headers.get("x-request-id").map(String::as_str).unwrap_or("");
+            tracing::warn!(
+                "Slow/large gRPC call: method={} request_id={} duration={:?} request_bytes={} response_bytes={}",
+                request.path,
+                request_id,
+                elapsed,
+                request_bytes,
+                response_bytes,
+            );
+        }
+
+        response
+    }
+
+    async fn call_inner(&self, request: &GrpcRequest) -> GrpcResponse {
+        // Build the gRPC body as one data frame per client message, so
+        // client-streaming and bidi-streaming calls forward every message
+        // the client sent instead of just the first.
         let mut grpc_body = Vec::new();
-        grpc_body.push(0x00); // flags = data frame
-        let msg_len = request.message.len() as u32;
-        grpc_body.extend_from_slice(&msg_len.to_be_bytes());
-        grpc_body.extend_from_slice(&request.message);
+        for message in &request.messages {
+            grpc_body.extend_from_slice(&encode_grpc_data_frame(message));
+        }
 
         // Build HTTP request
         let uri = format!("http://localhost{}", request.path);
@@ -426,15 +754,41 @@ where
             }
         }
 
-        // Call the service
+        // Call the service, applying the client's grpc-timeout deadline (if
+        // any) so a hung call gets cancelled instead of hanging forever.
         let mut service = self.service.lock().await;
-        match service.call(http_req).await {
+        let deadline = request.headers.get("grpc-timeout").and_then(|v| parse_grpc_timeout(v));
+
+        let call_future = service.call(http_req);
+        let call_result = match deadline {
+            Some(duration) => match tokio::time::timeout(duration, call_future).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!("gRPC call to {} exceeded deadline {:?}", request.path, duration);
+                    return GrpcResponse::error(StatusCode::DeadlineExceeded, "deadline exceeded");
+                }
+            },
+            None => call_future.await,
+        };
+
+        let mut response = match call_result {
             Ok(response) => self.parse_http_response(response).await,
             Err(e) => {
                 tracing::error!("Service call failed: {:?}", e);
                 GrpcResponse::error(StatusCode::Internal, format!("Service call failed: {:?}", e))
             }
+        };
+
+        // Compress the response if the caller advertised gzip support, so
+        // large payloads (e.g. scrape CSVs) don't have to cross the
+        // DataChannel uncompressed.
+        if accepts_gzip(&request.headers) && !response.messages.is_empty() {
+            response.messages = response.messages.iter().map(|m| gzip_compress(m)).collect();
+            response.compressed = true;
+            response.headers.insert("grpc-encoding".to_string(), "gzip".to_string());
         }
+
+        response
     }
 
     async fn parse_http_response(&self, response: http::Response<BoxBody>) -> GrpcResponse {
@@ -448,17 +802,44 @@ where
             }
         }
 
-        // Read body
-        let body_bytes = match body.collect().await {
-            Ok(collected) => collected.to_bytes().to_vec(),
+        // Read body, capturing HTTP trailers (where tonic puts `grpc-status`/
+        // `grpc-message` for a unary/server-streaming call, plus anything
+        // else a service set, e.g. `grpc-status-details-bin` for rich error
+        // details) separately from the leading headers.
+        let (body_bytes, mut trailers) = match body.collect().await {
+            Ok(collected) => {
+                let trailers = collected
+                    .trailers()
+                    .map(|t| {
+                        t.iter()
+                            .filter_map(|(key, value)| {
+                                value.to_str().ok().map(|v| (key.to_string(), v.to_string()))
+                            })
+                            .collect::<HashMap<String, String>>()
+                    })
+                    .unwrap_or_default();
+                (collected.to_bytes().to_vec(), trailers)
+            }
             Err(e) => {
                 tracing::error!("Failed to read response body: {:?}", e);
                 return GrpcResponse::error(StatusCode::Internal, "Failed to read response body");
             }
         };
 
-        // Parse gRPC status from trailers or headers
-        let status = headers
+        // A Trailers-Only response (e.g. an error before any data was sent)
+        // carries `grpc-status`/`grpc-message` on the leading headers instead
+        // of real HTTP trailers; fall back to those so both shapes work.
+        for key in ["grpc-status", "grpc-message"] {
+            if !trailers.contains_key(key) {
+                if let Some(value) = headers.get(key) {
+                    trailers.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+
+        // Parse gRPC status from trailers, falling back to headers for a
+        // Trailers-Only response.
+        let status = trailers
             .get("grpc-status")
             .and_then(|s| s.parse::<u32>().ok())
             .map(|code| match code {
@@ -483,24 +864,136 @@ where
             })
             .unwrap_or(StatusCode::Ok);
 
-        let status_message = headers.get("grpc-message").cloned();
+        let status_message = trailers.remove("grpc-message");
+        // `grpc-status` already has a dedicated `status` field above; the
+        // rest (e.g. `grpc-status-details-bin`) is carried through on
+        // `GrpcResponse::trailers` so `encode_response` can re-emit it.
+        trailers.remove("grpc-status");
 
         // Extract messages from gRPC frames (supports multiple frames for streaming)
-        let messages = parse_grpc_frames(&body_bytes);
+        let parsed_frames = parse_grpc_frames(&body_bytes);
+        if parsed_frames.incomplete {
+            // The HTTP response body is already fully buffered by this
+            // point, so a trailing partial frame here means truncated or
+            // corrupted data rather than "more is coming" - there's nothing
+            // to buffer towards, just surface what was decodable.
+            tracing::warn!(
+                "gRPC response body ended with a partial frame ({} of {} bytes parsed)",
+                parsed_frames.consumed,
+                body_bytes.len()
+            );
+        }
+        let messages = parsed_frames.messages;
 
         GrpcResponse {
             headers,
             messages,
             status,
             status_message,
+            trailers,
+            compressed: false,
         }
     }
 }
 
+/// Upper bound on how large a single gRPC message may expand to after
+/// gzip decompression. A peer could otherwise send a tiny, highly
+/// compressible gzip payload that expands to gigabytes before any
+/// application-level request validation runs (a decompression bomb); this
+/// caps the damage at a size well above any legitimate message this bridge
+/// handles.
+const MAX_DECOMPRESSED_MESSAGE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Marker prefix on the error [`gzip_decompress`] returns when
+/// [`MAX_DECOMPRESSED_MESSAGE_BYTES`] is exceeded, so callers can map it to
+/// `StatusCode::ResourceExhausted` instead of the generic `Internal` used
+/// for other parse failures - see [`status_for_parse_error`].
+const DECOMPRESSED_TOO_LARGE_PREFIX: &str = "decompressed message exceeds limit";
+
+/// gzip-decompress a single gRPC message payload, capped at
+/// [`MAX_DECOMPRESSED_MESSAGE_BYTES`]
+fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let mut decoder = GzDecoder::new(data).take(MAX_DECOMPRESSED_MESSAGE_BYTES + 1);
+    let mut out = Vec::new();
+    decoder
+        .read_to_end(&mut out)
+        .map_err(|e| format!("gzip decompress failed: {}", e))?;
+
+    if out.len() as u64 > MAX_DECOMPRESSED_MESSAGE_BYTES {
+        return Err(format!(
+            "{}: decompressed past {} bytes",
+            DECOMPRESSED_TOO_LARGE_PREFIX, MAX_DECOMPRESSED_MESSAGE_BYTES
+        ));
+    }
+
+    Ok(out)
+}
+
+/// Map a `parse_request` failure string to a gRPC status: a
+/// [`gzip_decompress`] size-cap hit is `ResourceExhausted`, everything else
+/// (malformed framing, bad base64, ...) stays `Internal` as before.
+fn status_for_parse_error(message: &str) -> StatusCode {
+    if message.contains(DECOMPRESSED_TOO_LARGE_PREFIX) {
+        StatusCode::ResourceExhausted
+    } else {
+        StatusCode::Internal
+    }
+}
+
+/// gzip-compress a single gRPC message payload
+fn gzip_compress(data: &[u8]) -> Vec<u8> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).expect("in-memory gzip write never fails");
+    encoder.finish().expect("in-memory gzip finish never fails")
+}
+
+/// Parse a `grpc-timeout` header value (e.g. `"10S"`, `"500m"`) into a
+/// [`std::time::Duration`], per the gRPC wire format: a positive integer
+/// followed by a unit (H=hours, M=minutes, S=seconds, m=milliseconds,
+/// u=microseconds, n=nanoseconds). Returns `None` if the value is malformed.
+fn parse_grpc_timeout(value: &str) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let (digits, unit) = value.split_at(value.len() - 1);
+    let amount: u64 = digits.parse().ok()?;
+
+    let duration = match unit {
+        "H" => std::time::Duration::from_secs(amount.checked_mul(3600)?),
+        "M" => std::time::Duration::from_secs(amount.checked_mul(60)?),
+        "S" => std::time::Duration::from_secs(amount),
+        "m" => std::time::Duration::from_millis(amount),
+        "u" => std::time::Duration::from_micros(amount),
+        "n" => std::time::Duration::from_nanos(amount),
+        _ => return None,
+    };
+
+    Some(duration)
+}
+
+/// Whether `headers` asks for a gzip-encoded response via `grpc-accept-encoding`
+fn accepts_gzip(headers: &HashMap<String, String>) -> bool {
+    headers
+        .get("grpc-accept-encoding")
+        .map(|v| v.split(',').any(|enc| enc.trim().eq_ignore_ascii_case("gzip")))
+        .unwrap_or(false)
+}
+
 impl<S> Clone for TonicServiceBridge<S> {
     fn clone(&self) -> Self {
         Self {
             service: self.service.clone(),
+            large_message_threshold_bytes: self.large_message_threshold_bytes,
+            slow_request_threshold: self.slow_request_threshold,
         }
     }
 }
@@ -548,43 +1041,66 @@ where
             let request_id = request.headers.get("x-request-id").cloned()
                 .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
+            // Correlates everything the bridged call logs (scraper/pdf service
+            // methods, the inner tonic router, etc.) back to this one browser
+            // request, so a single `request_id` can be grepped across the P2P
+            // boundary instead of only at entry/exit.
+            let span = tracing::info_span!("p2p_grpc", request_id = %request_id, path = %request.path);
+
             // Handle custom reflection requests
             if is_list_services_request(&request.path) {
-                if let Some(fds) = file_descriptor_set {
-                    let mut response = handle_list_services(fds);
-                    // Always include x-request-id in response
-                    response.headers.insert("x-request-id".to_string(), request_id);
-                    return GrpcProcessResult::Unary(encode_response(&response));
+                let mut response = if let Some(fds) = file_descriptor_set {
+                    handle_list_services(fds).with_header("x-request-id", request_id)
                 } else {
                     tracing::warn!("ListServices requested but no FILE_DESCRIPTOR_SET provided");
-                    let mut response = GrpcResponse::error(StatusCode::Unimplemented, "Reflection not configured");
-                    response.headers.insert("x-request-id".to_string(), request_id);
-                    return GrpcProcessResult::Unary(encode_response(&response));
-                }
+                    GrpcResponse::error(StatusCode::Unimplemented, "Reflection not configured")
+                        .with_header("x-request-id", request_id)
+                };
+                propagate_content_type(&mut response, &request);
+                return GrpcProcessResult::Unary(encode_response(&response));
             }
 
             // Handle FileContainingSymbol request for reflection
             if is_file_containing_symbol_request(&request.path) {
-                if let Some(fds) = file_descriptor_set {
-                    let mut response = handle_file_containing_symbol(fds, &request.message);
-                    // Always include x-request-id in response
-                    response.headers.insert("x-request-id".to_string(), request_id);
-                    return GrpcProcessResult::Unary(encode_response(&response));
+                let mut response = if let Some(fds) = file_descriptor_set {
+                    handle_file_containing_symbol(fds, request.message()).with_header("x-request-id", request_id)
                 } else {
                     tracing::warn!("FileContainingSymbol requested but no FILE_DESCRIPTOR_SET provided");
-                    let mut response = GrpcResponse::error(StatusCode::Unimplemented, "Reflection not configured");
-                    response.headers.insert("x-request-id".to_string(), request_id);
-                    return GrpcProcessResult::Unary(encode_response(&response));
-                }
+                    GrpcResponse::error(StatusCode::Unimplemented, "Reflection not configured")
+                        .with_header("x-request-id", request_id)
+                };
+                propagate_content_type(&mut response, &request);
+                return GrpcProcessResult::Unary(encode_response(&response));
+            }
+
+            // Handle the standard binary ServerReflectionInfo request, so
+            // off-the-shelf gRPC-Web reflection clients work alongside the
+            // JSON ListServices path above.
+            if super::reflection::is_server_reflection_info_request(&request.path) {
+                let mut response = if let Some(fds) = file_descriptor_set {
+                    super::reflection::handle_server_reflection_info(fds, &request)
+                        .with_header("x-request-id", request_id)
+                } else {
+                    tracing::warn!("ServerReflectionInfo requested but no FILE_DESCRIPTOR_SET provided");
+                    GrpcResponse::error(StatusCode::Unimplemented, "Reflection not configured")
+                        .with_header("x-request-id", request_id)
+                };
+                propagate_content_type(&mut response, &request);
+                return GrpcProcessResult::Unary(encode_response(&response));
             }
 
-            // Check if this is a streaming request
-            let is_streaming = request.path.contains("StreamDownload");
+            // Check if this is a streaming request by looking up the method's
+            // `server_streaming` flag in the descriptor set, rather than
+            // matching on a hardcoded method name.
+            let is_streaming = file_descriptor_set
+                .map(|fds| method_is_server_streaming(fds, &request.path))
+                .unwrap_or(false);
 
-            let mut response = bridge.call(&request).await;
+            let mut response = bridge.call(&request).instrument(span.clone()).await;
 
             // Always include x-request-id in response headers
             response.headers.insert("x-request-id".to_string(), request_id.clone());
+            propagate_content_type(&mut response, &request);
 
             if is_streaming {
                 // For streaming, return individual stream messages
@@ -599,7 +1115,8 @@ where
         }
         Err(e) => {
             tracing::error!("Failed to parse gRPC request: {}", e);
-            let response = GrpcResponse::error(StatusCode::Internal, e);
+            let status = status_for_parse_error(&e);
+            let response = GrpcResponse::error(status, e);
             GrpcProcessResult::Unary(encode_response(&response))
         }
     }
@@ -618,7 +1135,7 @@ fn encode_streaming_response(request_id: &str, response: &GrpcResponse) -> GrpcP
     }
 
     // Send END message with trailer
-    let trailer_frame = encode_trailer_frame(response.status, response.status_message.as_deref());
+    let trailer_frame = encode_trailer_frame(response);
     let end_msg = encode_stream_message(request_id, STREAM_FLAG_END, &trailer_frame);
     tracing::debug!("Encoded stream END message ({} bytes)", end_msg.len());
     messages.push(end_msg);
@@ -651,6 +1168,51 @@ pub struct ListServicesResponse {
     pub services: Vec<ServiceInfo>,
 }
 
+/// Split a gRPC path (`/package.Service/Method`) into its service and method parts
+fn split_grpc_path(path: &str) -> Option<(&str, &str)> {
+    path.strip_prefix('/')?.rsplit_once('/')
+}
+
+/// Look up whether the method at `path` is server-streaming according to the
+/// FILE_DESCRIPTOR_SET, instead of matching on a hardcoded method name.
+///
+/// Returns `false` if the descriptor set can't be parsed or doesn't contain
+/// the method (e.g. for the custom reflection paths handled above).
+pub fn method_is_server_streaming(file_descriptor_set: &[u8], path: &str) -> bool {
+    let Some((service_name, method_name)) = split_grpc_path(path) else {
+        return false;
+    };
+
+    let fds = match prost_types::FileDescriptorSet::decode(file_descriptor_set) {
+        Ok(fds) => fds,
+        Err(_) => return false,
+    };
+
+    for file in &fds.file {
+        let package = file.package.as_deref().unwrap_or("");
+        for service in &file.service {
+            let service_ident = service.name.as_deref().unwrap_or("");
+            let full_service_name = if package.is_empty() {
+                service_ident.to_string()
+            } else {
+                format!("{}.{}", package, service_ident)
+            };
+
+            if full_service_name != service_name {
+                continue;
+            }
+
+            for method in &service.method {
+                if method.name.as_deref() == Some(method_name) {
+                    return method.server_streaming.unwrap_or(false);
+                }
+            }
+        }
+    }
+
+    false
+}
+
 /// Extract services with their methods from FILE_DESCRIPTOR_SET
 pub fn extract_services_from_descriptor(file_descriptor_set: &[u8]) -> Vec<ServiceInfo> {
     let mut services = Vec::new();
@@ -877,7 +1439,7 @@ mod tests {
         let request = parse_request(&data).unwrap();
         assert_eq!(request.path, "/scraper.ETCScraper/Health");
         assert_eq!(request.headers.get("x-request-id"), Some(&"test-123".to_string()));
-        assert_eq!(request.message, message);
+        assert_eq!(request.messages, vec![message]);
     }
 
     #[test]
@@ -893,6 +1455,203 @@ mod tests {
         assert!(headers_len < encoded.len());
     }
 
+    #[test]
+    fn test_encode_response_includes_custom_trailers() {
+        let response = GrpcResponse::builder()
+            .message(vec![0x01])
+            .trailer("grpc-status-details-bin", "abc123")
+            .build();
+
+        let encoded = encode_response(&response);
+        let encoded_text = String::from_utf8_lossy(&encoded);
+
+        assert!(encoded_text.contains("grpc-status-details-bin: abc123"));
+    }
+
+    #[test]
+    fn test_grpc_response_builder() {
+        let response = GrpcResponse::builder()
+            .message(vec![0x01])
+            .header("x-request-id", "req-1")
+            .status(StatusCode::NotFound)
+            .status_message("missing")
+            .build();
+
+        assert_eq!(response.messages, vec![vec![0x01]]);
+        assert_eq!(response.headers.get("x-request-id"), Some(&"req-1".to_string()));
+        assert_eq!(response.status, StatusCode::NotFound);
+        assert_eq!(response.status_message, Some("missing".to_string()));
+    }
+
+    #[test]
+    fn test_grpc_response_ok_stream() {
+        let response = GrpcResponse::ok_stream(vec![vec![0x01], vec![0x02]]);
+
+        assert_eq!(response.status, StatusCode::Ok);
+        assert_eq!(response.messages, vec![vec![0x01], vec![0x02]]);
+    }
+
+    #[test]
+    fn test_grpc_response_with_header() {
+        let response = GrpcResponse::ok(vec![0x01]).with_header("x-request-id", "req-2");
+
+        assert_eq!(response.headers.get("x-request-id"), Some(&"req-2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_call_surfaces_error_status_from_trailers() {
+        // A tonic service reports `grpc-status`/`grpc-message` as real HTTP
+        // trailers, not headers, for anything but a Trailers-Only response.
+        // Before `parse_http_response` read `Collected::trailers()`, this
+        // error would have silently come back as `StatusCode::Ok`.
+        let service = tower::service_fn(|_req: http::Request<BoxBody>| async {
+            let mut trailers = http::HeaderMap::new();
+            trailers.insert("grpc-status", http::HeaderValue::from_static("5"));
+            trailers.insert("grpc-message", http::HeaderValue::from_static("not found"));
+
+            let body = Full::new(Bytes::new())
+                .map_err(|_: std::convert::Infallible| Status::internal("body error"))
+                .with_trailers(async move { Some(Ok(trailers)) });
+
+            Ok::<_, std::convert::Infallible>(http::Response::new(BoxBody::new(body)))
+        });
+
+        let bridge = TonicServiceBridge::new(service);
+        let request = GrpcRequest {
+            path: "/test.Service/Method".to_string(),
+            headers: HashMap::new(),
+            messages: vec![],
+        };
+
+        let response = bridge.call(&request).await;
+        assert_eq!(response.status, StatusCode::NotFound);
+        assert_eq!(response.status_message, Some("not found".to_string()));
+    }
+
+    /// Split a unary `encode_response` payload back into its data frame
+    /// payload and trailer frame text, mirroring `parse_request`'s framing
+    /// in reverse. Only handles the binary (non grpc-web-text) case, which
+    /// is what a request with no `content-type` header in its DataChannel
+    /// frame produces (see `propagate_content_type`).
+    fn decode_unary_response(data: &[u8]) -> (Vec<u8>, String) {
+        let headers_len = u32::from_be_bytes(data[0..4].try_into().unwrap()) as usize;
+        let mut pos = 4 + headers_len;
+
+        let data_len = u32::from_be_bytes(data[pos + 1..pos + 5].try_into().unwrap()) as usize;
+        let payload = data[pos + 5..pos + 5 + data_len].to_vec();
+        pos += 5 + data_len;
+
+        let trailer_len = u32::from_be_bytes(data[pos + 1..pos + 5].try_into().unwrap()) as usize;
+        let trailer_text = String::from_utf8(data[pos + 5..pos + 5 + trailer_len].to_vec()).unwrap();
+
+        (payload, trailer_text)
+    }
+
+    #[tokio::test]
+    async fn test_process_request_with_reflection_round_trips_health_check() {
+        // End-to-end: real `EtcScraperService` behind a real `tonic::service::Routes`,
+        // through `TonicServiceBridge` and `process_request_with_reflection`, decoded
+        // back into a `HealthResponse`. Unlike `test_call_surfaces_error_status_from_trailers`,
+        // which exercises the bridge against a hand-built mock, this catches bugs that only
+        // show up against tonic's real request/response machinery.
+        let config = crate::GatewayConfig::default();
+        let job_queue = Arc::new(tokio::sync::RwLock::new(crate::JobQueue::new()));
+        let scraper_service = crate::EtcScraperService::new(config, job_queue);
+        let routes = tonic::service::Routes::new(
+            crate::grpc::scraper_server::etc_scraper_server::EtcScraperServer::new(scraper_service),
+        );
+        let bridge = TonicServiceBridge::new(routes);
+
+        let data = build_request_bytes("/scraper.ETCScraper/Health", "{}", &[]);
+        let result = process_request_with_reflection(&data, &bridge, None).await;
+
+        let GrpcProcessResult::Unary(encoded) = result else {
+            panic!("Health is unary, expected GrpcProcessResult::Unary");
+        };
+        let (payload, trailer_text) = decode_unary_response(&encoded);
+        assert!(trailer_text.starts_with("grpc-status: 0"), "trailer: {}", trailer_text);
+
+        let response = crate::grpc::scraper_server::HealthResponse::decode(payload.as_slice()).unwrap();
+        assert!(response.healthy);
+    }
+
+    fn build_request_bytes(path: &str, headers_json: &str, message: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(path.len() as u32).to_be_bytes());
+        data.extend_from_slice(path.as_bytes());
+        data.extend_from_slice(&(headers_json.len() as u32).to_be_bytes());
+        data.extend_from_slice(headers_json.as_bytes());
+        data.push(0x00); // data frame
+        data.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_parse_request_round_trips_binary_frames() {
+        let message = vec![0x0a, 0x02, 0x6f, 0x6b];
+        let data = build_request_bytes(
+            "/test.Service/Method",
+            r#"{"content-type":"application/grpc-web+proto"}"#,
+            &message,
+        );
+
+        let request = parse_request(&data).unwrap();
+        assert_eq!(request.messages, vec![message]);
+    }
+
+    #[test]
+    fn test_parse_request_decodes_grpc_web_text_frames() {
+        use base64::Engine;
+
+        let message = vec![0x0a, 0x02, 0x6f, 0x6b];
+        let mut frame = Vec::new();
+        frame.push(0x00);
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&message);
+        let encoded_frame = base64::engine::general_purpose::STANDARD.encode(&frame);
+
+        let path = "/test.Service/Method";
+        let headers_json = r#"{"content-type":"application/grpc-web-text"}"#;
+        let mut data = Vec::new();
+        data.extend_from_slice(&(path.len() as u32).to_be_bytes());
+        data.extend_from_slice(path.as_bytes());
+        data.extend_from_slice(&(headers_json.len() as u32).to_be_bytes());
+        data.extend_from_slice(headers_json.as_bytes());
+        data.extend_from_slice(encoded_frame.as_bytes());
+
+        let request = parse_request(&data).unwrap();
+        assert_eq!(request.messages, vec![message]);
+    }
+
+    #[test]
+    fn test_encode_response_round_trips_binary_frames() {
+        let response = GrpcResponse::ok(vec![0x0a, 0x02, 0x6f, 0x6b]);
+        let encoded = encode_response(&response);
+
+        let headers_len = u32::from_be_bytes([encoded[0], encoded[1], encoded[2], encoded[3]]) as usize;
+        let frames = &encoded[4 + headers_len..];
+        assert_eq!(frames[0], 0x00); // data frame flag, not base64
+    }
+
+    #[test]
+    fn test_encode_response_base64_encodes_grpc_web_text_frames() {
+        use base64::Engine;
+
+        let mut response = GrpcResponse::ok(vec![0x0a, 0x02, 0x6f, 0x6b]);
+        response.headers.insert("content-type".to_string(), "application/grpc-web-text".to_string());
+        let encoded = encode_response(&response);
+
+        let headers_len = u32::from_be_bytes([encoded[0], encoded[1], encoded[2], encoded[3]]) as usize;
+        let frames_text = std::str::from_utf8(&encoded[4 + headers_len..]).unwrap();
+        let decoded = base64::engine::general_purpose::STANDARD.decode(frames_text).unwrap();
+
+        // Decoded bytes should be the plain data frame (flag 0x00) followed
+        // by a trailer frame (flag 0x01), exactly as the binary path would
+        // produce before base64-encoding.
+        assert_eq!(decoded[0], 0x00);
+    }
+
     #[test]
     fn test_router() {
         let mut router = GrpcRouter::new();
@@ -903,7 +1662,7 @@ mod tests {
         let request = GrpcRequest {
             path: "/test.Service/Method".to_string(),
             headers: HashMap::new(),
-            message: vec![],
+            messages: vec![],
         };
 
         let response = router.handle(&request);
@@ -917,13 +1676,71 @@ mod tests {
         let request = GrpcRequest {
             path: "/unknown.Service/Method".to_string(),
             headers: HashMap::new(),
-            message: vec![],
+            messages: vec![],
+        };
+
+        let response = router.handle(&request);
+        assert_eq!(response.status, StatusCode::Unimplemented);
+    }
+
+    #[test]
+    fn test_from_descriptor_prepopulates_unimplemented_paths() {
+        let router = GrpcRouter::from_descriptor(proto::FILE_DESCRIPTOR_SET);
+
+        // ServerReflection is always present (see extract_services_from_descriptor),
+        // so its methods should already route to Unimplemented rather than
+        // falling through to the generic "unknown path" response.
+        let request = GrpcRequest {
+            path: "/grpc.reflection.v1alpha.ServerReflection/ListServices".to_string(),
+            headers: HashMap::new(),
+            messages: vec![],
         };
 
         let response = router.handle(&request);
         assert_eq!(response.status, StatusCode::Unimplemented);
     }
 
+    #[test]
+    fn test_register_service_wires_all_methods() {
+        struct EchoHandler;
+        impl GrpcHandler for EchoHandler {
+            fn handle(&self, _request: &GrpcRequest) -> GrpcResponse {
+                GrpcResponse::ok(vec![0x2a])
+            }
+        }
+
+        let mut router = GrpcRouter::from_descriptor(proto::FILE_DESCRIPTOR_SET);
+        router
+            .register_service("grpc.reflection.v1alpha.ServerReflection", Arc::new(EchoHandler))
+            .expect("ServerReflection should be a known service");
+
+        let request = GrpcRequest {
+            path: "/grpc.reflection.v1alpha.ServerReflection/ListServices".to_string(),
+            headers: HashMap::new(),
+            messages: vec![],
+        };
+
+        let response = router.handle(&request);
+        assert_eq!(response.status, StatusCode::Ok);
+        assert_eq!(response.messages, vec![vec![0x2a]]);
+    }
+
+    #[test]
+    fn test_register_service_rejects_unknown_service() {
+        struct EchoHandler;
+        impl GrpcHandler for EchoHandler {
+            fn handle(&self, _request: &GrpcRequest) -> GrpcResponse {
+                GrpcResponse::ok(vec![])
+            }
+        }
+
+        let mut router = GrpcRouter::from_descriptor(proto::FILE_DESCRIPTOR_SET);
+        let err = router
+            .register_service("not.A.RealService", Arc::new(EchoHandler))
+            .unwrap_err();
+        assert!(err.contains("not.A.RealService"));
+    }
+
     #[test]
     fn test_parse_grpc_frames_single() {
         // Single data frame: [0x00][len=4][data]
@@ -932,9 +1749,10 @@ mod tests {
         data.extend_from_slice(&4u32.to_be_bytes());
         data.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]);
 
-        let messages = parse_grpc_frames(&data);
-        assert_eq!(messages.len(), 1);
-        assert_eq!(messages[0], vec![0x01, 0x02, 0x03, 0x04]);
+        let parsed = parse_grpc_frames(&data);
+        assert!(!parsed.incomplete);
+        assert_eq!(parsed.messages.len(), 1);
+        assert_eq!(parsed.messages[0], vec![0x01, 0x02, 0x03, 0x04]);
     }
 
     #[test]
@@ -957,11 +1775,12 @@ mod tests {
         data.extend_from_slice(&4u32.to_be_bytes());
         data.extend_from_slice(&[0x0f, 0x10, 0x11, 0x12]);
 
-        let messages = parse_grpc_frames(&data);
-        assert_eq!(messages.len(), 3);
-        assert_eq!(messages[0], vec![0x0a, 0x0b, 0x0c]);
-        assert_eq!(messages[1], vec![0x0d, 0x0e]);
-        assert_eq!(messages[2], vec![0x0f, 0x10, 0x11, 0x12]);
+        let parsed = parse_grpc_frames(&data);
+        assert!(!parsed.incomplete);
+        assert_eq!(parsed.messages.len(), 3);
+        assert_eq!(parsed.messages[0], vec![0x0a, 0x0b, 0x0c]);
+        assert_eq!(parsed.messages[1], vec![0x0d, 0x0e]);
+        assert_eq!(parsed.messages[2], vec![0x0f, 0x10, 0x11, 0x12]);
     }
 
     #[test]
@@ -980,16 +1799,116 @@ mod tests {
         data.extend_from_slice(&(trailer.len() as u32).to_be_bytes());
         data.extend_from_slice(trailer);
 
-        let messages = parse_grpc_frames(&data);
-        assert_eq!(messages.len(), 1);
-        assert_eq!(messages[0], vec![0x01, 0x02, 0x03]);
+        let parsed = parse_grpc_frames(&data);
+        assert!(!parsed.incomplete);
+        assert_eq!(parsed.messages.len(), 1);
+        assert_eq!(parsed.messages[0], vec![0x01, 0x02, 0x03]);
     }
 
     #[test]
     fn test_parse_grpc_frames_empty() {
         let data: Vec<u8> = Vec::new();
-        let messages = parse_grpc_frames(&data);
-        assert!(messages.is_empty());
+        let parsed = parse_grpc_frames(&data);
+        assert!(!parsed.incomplete);
+        assert!(parsed.messages.is_empty());
+    }
+
+    #[test]
+    fn test_parse_grpc_frames_incomplete_header_buffered_separately() {
+        // Only 3 of the 5 header bytes have arrived so far.
+        let data = vec![0x00, 0x00, 0x00];
+        let parsed = parse_grpc_frames(&data);
+        assert!(parsed.incomplete);
+        assert!(parsed.messages.is_empty());
+        assert_eq!(parsed.consumed, 0);
+    }
+
+    #[test]
+    fn test_parse_grpc_frames_split_payload_across_two_buffers() {
+        let message = vec![0xaa; 10];
+        let mut full = Vec::new();
+        full.push(0x00);
+        full.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        full.extend_from_slice(&message);
+
+        // Simulate DataChannel fragmentation: the frame's header arrives,
+        // but only part of its payload does.
+        let (first_buffer, second_buffer) = full.split_at(7);
+
+        let parsed_first = parse_grpc_frames(first_buffer);
+        assert!(parsed_first.incomplete);
+        assert!(parsed_first.messages.is_empty());
+        assert_eq!(parsed_first.consumed, 0);
+
+        // Caller buffers the unconsumed bytes and appends the rest once it
+        // arrives, rather than decoding the truncated payload.
+        let mut reassembled = first_buffer[parsed_first.consumed..].to_vec();
+        reassembled.extend_from_slice(second_buffer);
+
+        let parsed_full = parse_grpc_frames(&reassembled);
+        assert!(!parsed_full.incomplete);
+        assert_eq!(parsed_full.messages, vec![message]);
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let original = b"hello gzip world".repeat(10);
+        let compressed = gzip_compress(&original);
+        assert_ne!(compressed, original);
+        let decompressed = gzip_decompress(&compressed).unwrap();
+        assert_eq!(decompressed, original);
+    }
+
+    #[test]
+    fn test_gzip_decompress_rejects_decompression_bomb() {
+        // A small, highly-compressible payload that expands well past
+        // MAX_DECOMPRESSED_MESSAGE_BYTES.
+        let huge = vec![0u8; (MAX_DECOMPRESSED_MESSAGE_BYTES * 2) as usize];
+        let compressed = gzip_compress(&huge);
+        assert!(compressed.len() < huge.len() / 100);
+
+        let err = gzip_decompress(&compressed).unwrap_err();
+        assert!(err.contains(DECOMPRESSED_TOO_LARGE_PREFIX), "unexpected error: {}", err);
+        assert_eq!(status_for_parse_error(&err), StatusCode::ResourceExhausted);
+    }
+
+    #[test]
+    fn test_status_for_parse_error_defaults_to_internal() {
+        assert_eq!(status_for_parse_error("invalid base64 grpc-web-text body"), StatusCode::Internal);
+    }
+
+    #[test]
+    fn test_parse_client_frames_decompresses_compressed_flag() {
+        let message = b"compressed payload".to_vec();
+        let compressed = gzip_compress(&message);
+
+        let mut data = Vec::new();
+        data.push(0x01); // compressed data frame
+        data.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        data.extend_from_slice(&compressed);
+
+        let messages = parse_client_frames(&data).unwrap();
+        assert_eq!(messages, vec![message]);
+    }
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("10S"), Some(std::time::Duration::from_secs(10)));
+        assert_eq!(parse_grpc_timeout("500m"), Some(std::time::Duration::from_millis(500)));
+        assert_eq!(parse_grpc_timeout("2H"), Some(std::time::Duration::from_secs(7200)));
+        assert_eq!(parse_grpc_timeout(""), None);
+        assert_eq!(parse_grpc_timeout("10X"), None);
+        assert_eq!(parse_grpc_timeout("S"), None);
+    }
+
+    #[test]
+    fn test_accepts_gzip() {
+        let mut headers = HashMap::new();
+        headers.insert("grpc-accept-encoding".to_string(), "identity, gzip".to_string());
+        assert!(accepts_gzip(&headers));
+
+        let headers = HashMap::new();
+        assert!(!accepts_gzip(&headers));
     }
 
     #[test]
@@ -1035,6 +1954,8 @@ mod tests {
             ],
             status: StatusCode::Ok,
             status_message: None,
+            trailers: HashMap::new(),
+            compressed: false,
         };
 
         let result = encode_streaming_response("stream-test-123", &response);
@@ -1068,6 +1989,30 @@ mod tests {
         assert!(!is_list_services_request("/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo"));
     }
 
+    #[test]
+    fn test_method_is_server_streaming_detects_stream_download() {
+        assert!(method_is_server_streaming(
+            proto::FILE_DESCRIPTOR_SET,
+            "/scraper.ETCScraper/StreamDownload"
+        ));
+    }
+
+    #[test]
+    fn test_method_is_server_streaming_false_for_unary() {
+        assert!(!method_is_server_streaming(
+            proto::FILE_DESCRIPTOR_SET,
+            "/scraper.ETCScraper/Health"
+        ));
+    }
+
+    #[test]
+    fn test_method_is_server_streaming_unknown_method() {
+        assert!(!method_is_server_streaming(
+            proto::FILE_DESCRIPTOR_SET,
+            "/unknown.Service/Method"
+        ));
+    }
+
     #[test]
     fn test_extract_services_from_descriptor() {
         // Test with our actual FILE_DESCRIPTOR_SET
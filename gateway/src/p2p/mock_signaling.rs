@@ -0,0 +1,138 @@
+//! In-process mock of the cf-wbrtc-auth signaling server, for driving
+//! [`super::AuthenticatedSignalingClient`] through the auth/register/offer/ice
+//! handshake in tests without a live server. See `MockScraperService` in
+//! `crate::scraper` for the same "mock stands in for the real backend"
+//! pattern used elsewhere in this crate.
+
+use std::net::SocketAddr;
+
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// One accepted connection on a [`MockSignalingServer`], scriptable from the
+/// test: call the `send_*` methods in whatever order the scenario needs the
+/// client to receive them.
+pub struct MockSignalingConnection {
+    write: SplitSink<WebSocketStream<TcpStream>, Message>,
+}
+
+impl MockSignalingConnection {
+    async fn send_json(&mut self, value: serde_json::Value) {
+        let text = serde_json::to_string(&value).expect("mock signaling payload should serialize");
+        self.write
+            .send(Message::Text(text.into()))
+            .await
+            .expect("mock signaling server send should succeed");
+    }
+
+    /// Push an `auth_ok` message, as sent once the client's `apiKey` checks out.
+    pub async fn send_auth_ok(&mut self, user_id: &str, user_type: &str) {
+        self.send_json(json!({
+            "type": "auth_ok",
+            "payload": { "userId": user_id, "type": user_type },
+        }))
+        .await;
+    }
+
+    /// Push an `auth_error` message, as sent when the presented `apiKey` is rejected.
+    pub async fn send_auth_error(&mut self, error: &str) {
+        self.send_json(json!({
+            "type": "auth_error",
+            "payload": { "error": error },
+        }))
+        .await;
+    }
+
+    /// Push an `app_registered` message, as sent after the client's `app_register`.
+    pub async fn send_app_registered(&mut self, app_id: &str) {
+        self.send_json(json!({
+            "type": "app_registered",
+            "payload": { "appId": app_id },
+        }))
+        .await;
+    }
+
+    /// Push an `offer` message carrying an SDP offer for a new peer.
+    pub async fn send_offer(&mut self, sdp: &str, request_id: Option<&str>) {
+        let mut msg = json!({
+            "type": "offer",
+            "payload": { "sdp": sdp },
+        });
+        if let Some(request_id) = request_id {
+            msg["requestId"] = json!(request_id);
+        }
+        self.send_json(msg).await;
+    }
+
+    /// Push an `ice` message carrying a remote ICE candidate.
+    pub async fn send_ice(&mut self, candidate: serde_json::Value) {
+        self.send_json(json!({
+            "type": "ice",
+            "payload": { "candidate": candidate },
+        }))
+        .await;
+    }
+}
+
+/// A minimal stand-in for the cf-wbrtc-auth signaling server.
+///
+/// Binds an ephemeral local port, accepts WebSocket connections, and checks
+/// each one's `apiKey` query parameter - the same check the real server
+/// performs before ever looking at the `auth` message sent over the socket.
+pub struct MockSignalingServer {
+    listener: TcpListener,
+    addr: SocketAddr,
+}
+
+impl MockSignalingServer {
+    /// Bind to an ephemeral local port.
+    pub async fn bind() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("mock signaling server should bind to an ephemeral port");
+        let addr = listener
+            .local_addr()
+            .expect("bound mock signaling listener should have a local addr");
+        Self { listener, addr }
+    }
+
+    /// The `ws://` URL a client should connect to. `AuthenticatedSignalingClient::connect`
+    /// appends the `apiKey` query parameter itself, so it isn't included here.
+    pub fn url(&self) -> String {
+        format!("ws://{}/ws/app", self.addr)
+    }
+
+    /// Accept the next incoming connection and validate its `apiKey` query
+    /// parameter. Returns `None` (and closes the connection) if the presented
+    /// key doesn't match `expected_api_key`.
+    pub async fn accept(&self, expected_api_key: &str) -> Option<MockSignalingConnection> {
+        let (stream, _) = self
+            .listener
+            .accept()
+            .await
+            .expect("mock signaling server accept should succeed");
+
+        let mut received_api_key = None;
+        let ws_stream = tokio_tungstenite::accept_hdr_async(stream, |req: &tokio_tungstenite::tungstenite::handshake::server::Request, response| {
+            received_api_key = req
+                .uri()
+                .query()
+                .and_then(|query| url::form_urlencoded::parse(query.as_bytes()).find(|(k, _)| k == "apiKey"))
+                .map(|(_, v)| v.into_owned());
+            Ok(response)
+        })
+        .await
+        .expect("mock signaling server handshake should succeed");
+
+        if received_api_key.as_deref() != Some(expected_api_key) {
+            return None;
+        }
+
+        let (write, _read) = ws_stream.split();
+        Some(MockSignalingConnection { write })
+    }
+}
@@ -38,20 +38,27 @@ mod channel;
 pub mod auth;
 pub mod credentials;
 pub mod grpc_handler;
+pub mod mock_signaling;
+pub mod rate_limiter;
+pub mod reflection;
+pub mod runtime;
 
 pub use signaling::{
     SignalingClient, SignalingMessage, AuthenticatedSignalingClient,
     SignalingConfig, SignalingEventHandler, AuthOKPayload, AuthErrorPayload,
-    AppRegisteredPayload, WSMessage, msg_types, ReconnectConfig,
+    AppRegisteredPayload, AppStatusPayload, WSMessage, msg_types, ReconnectConfig, RefreshContext,
 };
-pub use credentials::{P2PCredentials, CredentialsError};
+pub use credentials::{P2PCredentials, CredentialsError, set_plaintext_only as set_credentials_plaintext_only};
 pub use auth::{AuthError, SetupConfig, OAuthSetup};
-pub use peer::{P2PPeer, PeerConfig, PeerEvent, TurnServer, ConnectionState, PeerRecreator};
+pub use peer::{P2PPeer, PeerConfig, PeerEvent, TurnServer, ConnectionState, PeerRecreator, DataChannelOptions};
 pub use channel::{DataChannel, ChannelMessage};
+pub use rate_limiter::{PeerRateLimiter, RateLimitConfig};
+pub use runtime::P2PRuntime;
+pub use mock_signaling::{MockSignalingConnection, MockSignalingServer};
 
 use thiserror::Error;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 
 /// Errors that can occur during P2P communication
 #[derive(Error, Debug)]
@@ -92,6 +99,10 @@ pub struct P2PConfig {
 
     /// Connection timeout in seconds
     pub connection_timeout_secs: u64,
+
+    /// Ordering/reliability settings for the data channel we create (see
+    /// [`PeerConfig::data_channel`])
+    pub data_channel: DataChannelOptions,
 }
 
 // TurnServer is re-exported from peer module
@@ -107,159 +118,521 @@ impl Default for P2PConfig {
             turn_servers: vec![],
             peer_id: None,
             connection_timeout_secs: 30,
+            data_channel: DataChannelOptions::default(),
         }
     }
 }
 
+/// Error parsing ICE server configuration from the environment
+#[derive(Error, Debug)]
+pub enum IceServerConfigError {
+    #[error("invalid STUN server URL '{0}': must start with stun: or stuns:")]
+    InvalidStunUrl(String),
+
+    #[error("invalid TURN server entry '{0}': expected urls|username|credential")]
+    InvalidTurnEntry(String),
+
+    #[error("invalid TURN server URL '{0}': must start with turn: or turns:")]
+    InvalidTurnUrl(String),
+}
+
+impl P2PConfig {
+    /// Build ICE server configuration from the environment, falling back to
+    /// the defaults above when the variables are unset. This is the single
+    /// source of STUN/TURN configuration for both the console and service
+    /// P2P paths — air-gapped deployments that need an internal STUN server
+    /// set `P2P_STUN_SERVERS` instead of patching hardcoded URLs.
+    ///
+    /// - `P2P_STUN_SERVERS`: comma-separated STUN URLs,
+    ///   e.g. `stun:stun.internal:3478,stun:stun2.internal:3478`
+    /// - `P2P_TURN_SERVERS`: semicolon-separated TURN entries, each
+    ///   `urls|username|credential` where `urls` is itself comma-separated,
+    ///   e.g. `turn:turn.internal:3478|user|pass`
+    pub fn from_env() -> Result<Self, IceServerConfigError> {
+        Self::from_env_with_stun_defaults(Self::default().stun_servers)
+    }
+
+    /// Like [`from_env`], but falls back to `default_stun_servers` instead of
+    /// the compiled-in Google STUN list when `P2P_STUN_SERVERS` is unset.
+    /// Lets callers seed the fallback from [`crate::config::GatewayConfig`]'s
+    /// file/env layered defaults instead of the hardcoded ones here.
+    pub fn from_env_with_stun_defaults(
+        default_stun_servers: Vec<String>,
+    ) -> Result<Self, IceServerConfigError> {
+        let mut config = Self {
+            stun_servers: default_stun_servers,
+            ..Self::default()
+        };
+
+        if let Ok(raw) = std::env::var("P2P_STUN_SERVERS") {
+            config.stun_servers = parse_stun_servers(&raw)?;
+        }
+
+        if let Ok(raw) = std::env::var("P2P_TURN_SERVERS") {
+            config.turn_servers = parse_turn_servers(&raw)?;
+        }
+
+        if let Ok(raw) = std::env::var("P2P_DATA_CHANNEL_ORDERED") {
+            config.data_channel.ordered = raw.to_lowercase() == "true" || raw == "1";
+        }
+
+        if let Ok(raw) = std::env::var("P2P_DATA_CHANNEL_MAX_RETRANSMITS") {
+            if let Ok(n) = raw.parse() {
+                config.data_channel.max_retransmits = Some(n);
+            }
+        }
+
+        if let Ok(raw) = std::env::var("P2P_DATA_CHANNEL_MAX_PACKET_LIFETIME") {
+            if let Ok(n) = raw.parse() {
+                config.data_channel.max_packet_life_time = Some(n);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Project this config down to the subset [`PeerConfig`] needs
+    pub fn to_peer_config(&self) -> PeerConfig {
+        PeerConfig {
+            stun_servers: self.stun_servers.clone(),
+            turn_servers: self.turn_servers.clone(),
+            data_channel: self.data_channel,
+        }
+    }
+}
+
+pub(crate) fn parse_stun_servers(raw: &str) -> Result<Vec<String>, IceServerConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|url| {
+            if url.starts_with("stun:") || url.starts_with("stuns:") {
+                Ok(url.to_string())
+            } else {
+                Err(IceServerConfigError::InvalidStunUrl(url.to_string()))
+            }
+        })
+        .collect()
+}
+
+fn parse_turn_servers(raw: &str) -> Result<Vec<TurnServer>, IceServerConfigError> {
+    raw.split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let parts: Vec<&str> = entry.split('|').collect();
+            if parts.len() != 3 {
+                return Err(IceServerConfigError::InvalidTurnEntry(entry.to_string()));
+            }
+            let (urls_part, username, credential) = (parts[0], parts[1], parts[2]);
+
+            let urls: Vec<String> = urls_part.split(',').map(|u| u.trim().to_string()).collect();
+            for url in &urls {
+                if !(url.starts_with("turn:") || url.starts_with("turns:")) {
+                    return Err(IceServerConfigError::InvalidTurnUrl(url.clone()));
+                }
+            }
+
+            Ok(TurnServer {
+                urls,
+                username: username.to_string(),
+                credential: credential.to_string(),
+            })
+        })
+        .collect()
+}
+
 /// P2P Network Manager
 ///
-/// Manages peer connections and data channels for P2P communication.
+/// Manages peer connections and data channels for P2P communication, driving
+/// a real [`AuthenticatedSignalingClient`] underneath (the same transport
+/// [`crate::p2p::runtime::P2PRuntime`] uses for the `gateway --p2p-run`
+/// entry points) rather than the legacy [`SignalingClient`].
+///
+/// `P2PManager` implements [`SignalingEventHandler`] itself, so unlike
+/// `P2PRuntime` - which is wired up by `main.rs` calling
+/// `set_signaling_client`/`set_event_handler` separately - a `P2PManager` is
+/// ready to use as soon as [`connect`](Self::connect) returns; there's no
+/// external wiring step for a consumer of this type to get wrong.
+///
+/// Cheaply [`Clone`]able: clones share the same peer map and signaling
+/// client via an internal `Arc`.
+#[derive(Clone)]
 pub struct P2PManager {
+    inner: Arc<P2PManagerInner>,
+}
+
+struct P2PManagerInner {
     config: P2PConfig,
-    peers: Arc<RwLock<std::collections::HashMap<String, Arc<P2PPeer>>>>,
-    signaling: SignalingClient,
-    local_peer_id: String,
+    peers: RwLock<std::collections::HashMap<String, Arc<P2PPeer>>>,
+    peer_counter: std::sync::atomic::AtomicU64,
+    signaling: RwLock<AuthenticatedSignalingClient>,
+    /// Offers we've sent via [`P2PManagerInner::connect_to_peer`] and are
+    /// still waiting on an answer for, keyed by the peer/app ID we offered
+    /// to. `on_answer` resolves these; see its doc comment for how an answer
+    /// is matched back to one of these when the server doesn't echo the
+    /// target app ID.
+    pending_answers: RwLock<std::collections::HashMap<String, oneshot::Sender<String>>>,
 }
 
 impl P2PManager {
-    /// Create a new P2P manager with the given configuration
-    pub fn new(config: P2PConfig) -> Self {
-        let local_peer_id = config.peer_id.clone()
-            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-
-        let signaling = SignalingClient::new(config.signaling_url.clone());
-
+    /// Create a new P2P manager. `config` supplies ICE servers and data
+    /// channel options; `signaling_config` supplies the cf-wbrtc-auth
+    /// connection details (API key, app name, capabilities) for the
+    /// underlying [`AuthenticatedSignalingClient`].
+    pub fn new(config: P2PConfig, signaling_config: SignalingConfig) -> Self {
         Self {
-            config,
-            peers: Arc::new(RwLock::new(std::collections::HashMap::new())),
-            signaling,
-            local_peer_id,
+            inner: Arc::new(P2PManagerInner {
+                config,
+                peers: RwLock::new(std::collections::HashMap::new()),
+                peer_counter: std::sync::atomic::AtomicU64::new(0),
+                signaling: RwLock::new(AuthenticatedSignalingClient::new(signaling_config)),
+                pending_answers: RwLock::new(std::collections::HashMap::new()),
+            }),
         }
     }
 
-    /// Get the local peer ID
-    pub fn local_peer_id(&self) -> &str {
-        &self.local_peer_id
+    /// The app ID the signaling server assigned us on registration, or empty
+    /// if we haven't registered yet.
+    pub async fn app_id(&self) -> String {
+        self.inner.signaling.read().await.get_app_id().await
     }
 
-    /// Connect to the signaling server
-    pub async fn connect(&mut self) -> Result<(), P2PError> {
-        self.signaling.connect(&self.local_peer_id).await
+    /// Connect to the signaling server and authenticate. The app is
+    /// registered automatically once authentication succeeds (see
+    /// `on_authenticated` below) - callers don't need a separate
+    /// `register_app` step.
+    pub async fn connect(&self) -> Result<(), P2PError> {
+        let mut client = self.inner.signaling.write().await;
+        client.set_event_handler(self.inner.clone());
+        client.connect().await
     }
 
     /// Disconnect from the signaling server
-    pub async fn disconnect(&mut self) -> Result<(), P2PError> {
-        self.signaling.disconnect().await
+    pub async fn disconnect(&self) -> Result<(), P2PError> {
+        self.inner.signaling.write().await.close().await
     }
 
-    /// Create a peer config from the manager config
-    fn create_peer_config(&self) -> PeerConfig {
-        PeerConfig {
-            stun_servers: self.config.stun_servers.clone(),
-            turn_servers: self.config.turn_servers.clone(),
+    /// Connect to a remote peer (identified by its signaling app ID) by
+    /// sending it a WebRTC offer and waiting for the matching answer.
+    pub async fn connect_to_peer(&self, peer_id: &str) -> Result<Arc<P2PPeer>, P2PError> {
+        self.inner.connect_to_peer(peer_id).await
+    }
+
+    /// Get a connected peer by ID
+    pub async fn get_peer(&self, peer_id: &str) -> Option<Arc<P2PPeer>> {
+        self.inner.peers.read().await.get(peer_id).cloned()
+    }
+
+    /// Send data to a specific peer
+    pub async fn send_to_peer(&self, peer_id: &str, data: &[u8]) -> Result<(), P2PError> {
+        let peers = self.inner.peers.read().await;
+        let peer = peers.get(peer_id)
+            .ok_or_else(|| P2PError::PeerNotFound(peer_id.to_string()))?;
+
+        peer.send(data).await
+    }
+
+    /// Broadcast data to all connected peers, fire-and-forget. Per-peer
+    /// failures are logged and otherwise swallowed; use
+    /// [`broadcast_collect`](Self::broadcast_collect) when the caller needs
+    /// to know which peers didn't get the message.
+    pub async fn broadcast(&self, data: &[u8]) -> Result<(), P2PError> {
+        let peers = self.inner.peers.read().await;
+
+        for peer in peers.values() {
+            if let Err(e) = peer.send(data).await {
+                tracing::warn!("Failed to send to peer {}: {:?}", peer.remote_id(), e);
+            }
         }
+
+        Ok(())
     }
 
-    /// Connect to a remote peer by ID
-    pub async fn connect_to_peer(&self, peer_id: &str) -> Result<Arc<P2PPeer>, P2PError> {
-        let peer_config = self.create_peer_config();
+    /// Broadcast data to all connected peers, returning the per-peer
+    /// outcome instead of swallowing failures. One peer's send failing
+    /// doesn't stop the others from being attempted - useful for fan-out
+    /// scenarios that need delivery confirmation per recipient.
+    pub async fn broadcast_collect(&self, data: &[u8]) -> Vec<(String, Result<(), P2PError>)> {
+        let peers = self.inner.peers.read().await;
+
+        let mut results = Vec::with_capacity(peers.len());
+        for (peer_id, peer) in peers.iter() {
+            results.push((peer_id.clone(), peer.send(data).await));
+        }
+
+        results
+    }
+
+    /// Get list of connected peer IDs
+    pub async fn connected_peers(&self) -> Vec<String> {
+        self.inner.peers.read().await.keys().cloned().collect()
+    }
+}
+
+impl P2PManagerInner {
+    fn next_peer_id(&self) -> String {
+        let n = self.peer_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        format!("peer-{}", n)
+    }
 
-        let peer = P2PPeer::new(peer_id.to_string(), peer_config).await?;
+    async fn connect_to_peer(&self, peer_id: &str) -> Result<Arc<P2PPeer>, P2PError> {
+        let peer = P2PPeer::new(peer_id.to_string(), self.config.to_peer_config()).await?;
         peer.setup_handlers().await?;
 
-        // Create offer and send via signaling
         let offer = peer.create_offer().await?;
-        self.signaling.send(SignalingMessage::Offer {
-            from: self.local_peer_id.clone(),
-            to: peer_id.to_string(),
-            sdp: offer,
-        }).await?;
-
-        // Wait for answer
-        let answer = self.wait_for_answer(peer_id).await?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_answers.write().await.insert(peer_id.to_string(), tx);
+
+        let send_result = self
+            .signaling
+            .read()
+            .await
+            .send_offer(&offer, Some(peer_id), None)
+            .await;
+        if let Err(e) = send_result {
+            self.pending_answers.write().await.remove(peer_id);
+            return Err(e);
+        }
+
+        let timeout = std::time::Duration::from_secs(self.config.connection_timeout_secs);
+        let answer = match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(sdp)) => sdp,
+            Ok(Err(_)) => {
+                return Err(P2PError::Signaling(
+                    "Answer channel dropped before an answer arrived".to_string(),
+                ));
+            }
+            Err(_) => {
+                self.pending_answers.write().await.remove(peer_id);
+                return Err(P2PError::Timeout);
+            }
+        };
+
         peer.set_remote_answer(&answer).await?;
 
-        // Store peer
         let peer = Arc::new(peer);
         self.peers.write().await.insert(peer_id.to_string(), peer.clone());
 
         Ok(peer)
     }
+}
 
-    /// Wait for an answer from a specific peer
-    async fn wait_for_answer(&self, peer_id: &str) -> Result<String, P2PError> {
-        let timeout = std::time::Duration::from_secs(self.config.connection_timeout_secs);
-        let start = std::time::Instant::now();
+#[async_trait::async_trait]
+impl SignalingEventHandler for P2PManagerInner {
+    async fn on_authenticated(&self, payload: AuthOKPayload) {
+        tracing::info!(
+            "P2PManager authenticated! User ID: {}, Type: {}",
+            payload.user_id, payload.user_type
+        );
+
+        // There's no separate "wait for auth, then register" step for
+        // callers to get wrong here - we register as soon as auth succeeds.
+        let client = self.signaling.read().await;
+        if let Err(e) = client.register_app().await {
+            tracing::error!("P2PManager failed to register app: {:?}", e);
+        }
+    }
 
-        loop {
-            if start.elapsed() > timeout {
-                return Err(P2PError::Timeout);
+    async fn on_auth_error(&self, payload: AuthErrorPayload) {
+        tracing::error!("P2PManager auth error: {}", payload.error);
+    }
+
+    async fn on_app_registered(&self, payload: AppRegisteredPayload) {
+        tracing::info!("P2PManager app registered! App ID: {}", payload.app_id);
+    }
+
+    async fn on_offer(&self, sdp: String, request_id: Option<String>) {
+        let peer_id = self.next_peer_id();
+
+        let peer = match P2PPeer::new(peer_id.clone(), self.config.to_peer_config()).await {
+            Ok(peer) => peer,
+            Err(e) => {
+                tracing::error!("P2PManager failed to create peer connection: {:?}", e);
+                return;
             }
+        };
 
-            if let Some(msg) = self.signaling.receive().await? {
-                if let SignalingMessage::Answer { from, sdp, .. } = msg {
-                    if from == peer_id {
-                        return Ok(sdp);
-                    }
-                }
+        if let Err(e) = peer.setup_handlers().await {
+            tracing::error!("P2PManager failed to set up peer handlers: {:?}", e);
+            return;
+        }
+        if let Err(e) = peer.setup_data_channel_handler().await {
+            tracing::error!("P2PManager failed to set up data channel handler: {:?}", e);
+            return;
+        }
+
+        let answer = match peer.create_answer(&sdp).await {
+            Ok(answer) => answer,
+            Err(e) => {
+                tracing::error!("P2PManager failed to create answer: {:?}", e);
+                return;
             }
+        };
+
+        if let Err(e) = self
+            .signaling
+            .read()
+            .await
+            .send_answer(&answer, request_id.as_deref())
+            .await
+        {
+            tracing::error!("P2PManager failed to send answer: {:?}", e);
+            return;
+        }
+
+        self.peers.write().await.insert(peer_id.clone(), Arc::new(peer));
+        tracing::info!("P2PManager: peer {} connected via incoming offer", peer_id);
+    }
 
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    /// Resolve a pending [`P2PManagerInner::connect_to_peer`] call. If the
+    /// answer carries an `app_id`, it's matched against that exact pending
+    /// peer ID; otherwise, as with
+    /// [`crate::p2p::runtime::P2PRuntime::on_answer`], we fall back to
+    /// resolving the single outstanding offer (multiple concurrent
+    /// `connect_to_peer` calls without the server echoing back an `app_id`
+    /// can't be disambiguated).
+    async fn on_answer(&self, sdp: String, app_id: Option<String>) {
+        let mut pending = self.pending_answers.write().await;
+
+        let sender = if let Some(app_id) = app_id {
+            pending.remove(&app_id)
+        } else if pending.len() == 1 {
+            let key = pending.keys().next().cloned();
+            key.and_then(|k| pending.remove(&k))
+        } else {
+            None
+        };
+        drop(pending);
+
+        match sender {
+            Some(sender) => {
+                let _ = sender.send(sdp);
+            }
+            None => {
+                tracing::warn!("P2PManager received an answer with no matching pending offer");
+            }
         }
     }
 
-    /// Handle an incoming connection offer
-    pub async fn handle_offer(&self, from: &str, sdp: String) -> Result<Arc<P2PPeer>, P2PError> {
-        let peer_config = self.create_peer_config();
+    async fn on_ice(&self, candidate: serde_json::Value) {
+        let candidate_str = candidate.get("candidate").and_then(|v| v.as_str()).unwrap_or("");
+        let sdp_mid = candidate.get("sdpMid").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let sdp_mline_index = candidate.get("sdpMLineIndex").and_then(|v| v.as_u64()).map(|v| v as u16);
 
-        let peer = P2PPeer::new(from.to_string(), peer_config).await?;
-        peer.setup_handlers().await?;
-        peer.setup_data_channel_handler().await?;
+        if candidate_str.is_empty() {
+            return;
+        }
 
-        // Create answer
-        let answer = peer.create_answer(&sdp).await?;
-        self.signaling.send(SignalingMessage::Answer {
-            from: self.local_peer_id.clone(),
-            to: from.to_string(),
-            sdp: answer,
-        }).await?;
+        // Added to all peers (in practice, should be targeted to the
+        // specific peer this candidate belongs to) - same limitation as
+        // P2PRuntime::on_ice.
+        let peers = self.peers.read().await;
+        for (peer_id, peer) in peers.iter() {
+            if let Err(e) = peer.add_ice_candidate(candidate_str, sdp_mid.clone(), sdp_mline_index).await {
+                tracing::warn!("P2PManager failed to add ICE candidate to peer {}: {:?}", peer_id, e);
+            }
+        }
+    }
 
-        // Store peer
-        let peer = Arc::new(peer);
-        self.peers.write().await.insert(from.to_string(), peer.clone());
+    async fn on_error(&self, message: String) {
+        tracing::error!("P2PManager signaling error: {}", message);
+    }
 
-        Ok(peer)
+    async fn on_connected(&self) {
+        tracing::info!("P2PManager connected to signaling server");
     }
 
-    /// Get a connected peer by ID
-    pub async fn get_peer(&self, peer_id: &str) -> Option<Arc<P2PPeer>> {
-        self.peers.read().await.get(peer_id).cloned()
+    async fn on_disconnected(&self) {
+        tracing::warn!("P2PManager disconnected from signaling server");
     }
+}
 
-    /// Send data to a specific peer
-    pub async fn send_to_peer(&self, peer_id: &str, data: &[u8]) -> Result<(), P2PError> {
-        let peers = self.peers.read().await;
-        let peer = peers.get(peer_id)
-            .ok_or_else(|| P2PError::PeerNotFound(peer_id.to_string()))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        peer.send(data).await
+    #[test]
+    fn test_parse_stun_servers() {
+        let servers = parse_stun_servers("stun:a.example:3478, stun:b.example:3478").unwrap();
+        assert_eq!(servers, vec!["stun:a.example:3478", "stun:b.example:3478"]);
     }
 
-    /// Broadcast data to all connected peers
-    pub async fn broadcast(&self, data: &[u8]) -> Result<(), P2PError> {
-        let peers = self.peers.read().await;
+    #[test]
+    fn test_parse_stun_servers_rejects_bad_scheme() {
+        let err = parse_stun_servers("turn:a.example:3478").unwrap_err();
+        assert!(matches!(err, IceServerConfigError::InvalidStunUrl(_)));
+    }
 
-        for peer in peers.values() {
-            if let Err(e) = peer.send(data).await {
-                tracing::warn!("Failed to send to peer {}: {:?}", peer.remote_id(), e);
-            }
-        }
+    #[test]
+    fn test_parse_turn_servers() {
+        let servers = parse_turn_servers("turn:a.example:3478,turn:b.example:3478|alice|secret").unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].urls, vec!["turn:a.example:3478", "turn:b.example:3478"]);
+        assert_eq!(servers[0].username, "alice");
+        assert_eq!(servers[0].credential, "secret");
+    }
 
-        Ok(())
+    #[test]
+    fn test_parse_turn_servers_rejects_malformed_entry() {
+        let err = parse_turn_servers("turn:a.example:3478|alice").unwrap_err();
+        assert!(matches!(err, IceServerConfigError::InvalidTurnEntry(_)));
     }
 
-    /// Get list of connected peer IDs
-    pub async fn connected_peers(&self) -> Vec<String> {
-        self.peers.read().await.keys().cloned().collect()
+    #[test]
+    fn test_parse_turn_servers_rejects_bad_scheme() {
+        let err = parse_turn_servers("stun:a.example:3478|alice|secret").unwrap_err();
+        assert!(matches!(err, IceServerConfigError::InvalidTurnUrl(_)));
+    }
+
+    #[test]
+    fn test_from_env_with_stun_defaults_uses_default_when_unset() {
+        std::env::remove_var("P2P_STUN_SERVERS");
+        let config =
+            P2PConfig::from_env_with_stun_defaults(vec!["stun:custom.example:3478".to_string()])
+                .unwrap();
+        assert_eq!(config.stun_servers, vec!["stun:custom.example:3478"]);
+    }
+
+    #[test]
+    fn test_from_env_with_stun_defaults_env_still_wins() {
+        std::env::set_var("P2P_STUN_SERVERS", "stun:env.example:3478");
+        let config =
+            P2PConfig::from_env_with_stun_defaults(vec!["stun:custom.example:3478".to_string()])
+                .unwrap();
+        assert_eq!(config.stun_servers, vec!["stun:env.example:3478"]);
+        std::env::remove_var("P2P_STUN_SERVERS");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_collect_reports_every_peer_without_aborting() {
+        let config = P2PConfig::default();
+        let manager = P2PManager::new(config.clone(), SignalingConfig::default());
+
+        // Neither peer has an established data channel, so both sends fail -
+        // the point of this test is that broadcast_collect still reports
+        // both outcomes instead of stopping after the first failure.
+        let peer_a = P2PPeer::new("peer-a".to_string(), config.to_peer_config())
+            .await
+            .unwrap();
+        let peer_b = P2PPeer::new("peer-b".to_string(), config.to_peer_config())
+            .await
+            .unwrap();
+
+        {
+            let mut peers = manager.inner.peers.write().await;
+            peers.insert("peer-a".to_string(), Arc::new(peer_a));
+            peers.insert("peer-b".to_string(), Arc::new(peer_b));
+        }
+
+        let results = manager.broadcast_collect(b"ping").await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, r)| r.is_err()));
+
+        let ids: std::collections::HashSet<_> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert!(ids.contains("peer-a"));
+        assert!(ids.contains("peer-b"));
     }
 }
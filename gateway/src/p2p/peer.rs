@@ -3,10 +3,12 @@
 use super::P2PError;
 use prost::bytes::Bytes;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use webrtc::api::interceptor_registry::register_default_interceptors;
 use webrtc::api::media_engine::MediaEngine;
 use webrtc::api::APIBuilder;
+use webrtc::data_channel::data_channel_init::RTCDataChannelInit;
 use webrtc::data_channel::data_channel_message::DataChannelMessage;
 use webrtc::data_channel::RTCDataChannel;
 use webrtc::ice_transport::ice_candidate::RTCIceCandidateInit;
@@ -25,6 +27,10 @@ pub struct PeerConfig {
 
     /// TURN server configurations
     pub turn_servers: Vec<TurnServer>,
+
+    /// Ordering/reliability settings for the data channel this peer creates
+    /// in [`P2PPeer::create_offer`]
+    pub data_channel: DataChannelOptions,
 }
 
 /// TURN server configuration
@@ -35,6 +41,52 @@ pub struct TurnServer {
     pub credential: String,
 }
 
+/// Ordering/reliability settings for a WebRTC data channel, mapped directly
+/// onto [`webrtc::data_channel::data_channel_init::RTCDataChannelInit`].
+/// `max_retransmits` and `max_packet_life_time` are mutually exclusive per
+/// the WebRTC spec - setting both makes the channel unreliable by count and
+/// by time at once, which most implementations reject, so callers should
+/// only set one.
+///
+/// The default is ordered and reliable (unlimited retransmits), matching
+/// the behavior before this was configurable. Bulk transfer channels that
+/// can tolerate loss/reordering (e.g. `StreamDownload`) can relax this for
+/// lower latency - see [`PeerConfig::data_channel`].
+#[derive(Clone, Copy, Debug)]
+pub struct DataChannelOptions {
+    /// Deliver messages in the order they were sent
+    pub ordered: bool,
+
+    /// Give up retransmitting a message after this many attempts, making
+    /// the channel partially reliable. `None` means unlimited retransmits.
+    pub max_retransmits: Option<u16>,
+
+    /// Give up retransmitting a message after this many milliseconds,
+    /// making the channel partially reliable. `None` means no time limit.
+    pub max_packet_life_time: Option<u16>,
+}
+
+impl Default for DataChannelOptions {
+    fn default() -> Self {
+        Self {
+            ordered: true,
+            max_retransmits: None,
+            max_packet_life_time: None,
+        }
+    }
+}
+
+impl DataChannelOptions {
+    fn to_init(self) -> RTCDataChannelInit {
+        RTCDataChannelInit {
+            ordered: Some(self.ordered),
+            max_retransmits: self.max_retransmits,
+            max_packet_life_time: self.max_packet_life_time,
+            ..Default::default()
+        }
+    }
+}
+
 /// Events that can occur during peer communication
 #[derive(Clone, Debug)]
 pub enum PeerEvent {
@@ -54,6 +106,10 @@ pub enum PeerEvent {
         sdp_mline_index: Option<u16>,
     },
 
+    /// ICE gathering has finished; no more `IceCandidate` events will follow
+    /// for this peer.
+    IceGatheringComplete,
+
     /// Error occurred
     Error(String),
 }
@@ -76,6 +132,7 @@ pub struct P2PPeer {
     data_channel: Arc<RwLock<Option<Arc<RTCDataChannel>>>>,
     event_tx: Arc<RwLock<Option<mpsc::Sender<PeerEvent>>>>,
     ice_candidates: Arc<RwLock<Vec<RTCIceCandidateInit>>>,
+    last_activity: Arc<RwLock<Instant>>,
 }
 
 impl P2PPeer {
@@ -93,6 +150,7 @@ impl P2PPeer {
             data_channel: Arc::new(RwLock::new(None)),
             event_tx: Arc::new(RwLock::new(None)),
             ice_candidates: Arc::new(RwLock::new(Vec::new())),
+            last_activity: Arc::new(RwLock::new(Instant::now())),
         })
     }
 
@@ -190,30 +248,44 @@ impl P2PPeer {
             let ice_candidates = ice_candidates.clone();
 
             Box::pin(async move {
-                if let Some(candidate) = candidate {
-                    let candidate_json = match candidate.to_json() {
-                        Ok(json) => json,
-                        Err(e) => {
-                            tracing::error!("Failed to serialize ICE candidate: {}", e);
-                            return;
-                        }
-                    };
-
-                    // Store the candidate
-                    ice_candidates.write().await.push(RTCIceCandidateInit {
-                        candidate: candidate_json.candidate.clone(),
-                        sdp_mid: candidate_json.sdp_mid.clone(),
-                        sdp_mline_index: candidate_json.sdp_mline_index,
-                        ..Default::default()
-                    });
-
-                    // Notify via event
-                    if let Some(ref tx) = *event_tx.read().await {
-                        let _ = tx.send(PeerEvent::IceCandidate {
-                            candidate: candidate_json.candidate,
-                            sdp_mid: candidate_json.sdp_mid,
+                match candidate {
+                    Some(candidate) => {
+                        let candidate_json = match candidate.to_json() {
+                            Ok(json) => json,
+                            Err(e) => {
+                                tracing::error!("Failed to serialize ICE candidate: {}", e);
+                                return;
+                            }
+                        };
+
+                        // Store the candidate
+                        ice_candidates.write().await.push(RTCIceCandidateInit {
+                            candidate: candidate_json.candidate.clone(),
+                            sdp_mid: candidate_json.sdp_mid.clone(),
                             sdp_mline_index: candidate_json.sdp_mline_index,
-                        }).await;
+                            ..Default::default()
+                        });
+
+                        // Notify via event, immediately - trickling candidates
+                        // out one at a time as they're gathered gets them to
+                        // the remote peer sooner than batching them up.
+                        if let Some(ref tx) = *event_tx.read().await {
+                            let _ = tx.send(PeerEvent::IceCandidate {
+                                candidate: candidate_json.candidate,
+                                sdp_mid: candidate_json.sdp_mid,
+                                sdp_mline_index: candidate_json.sdp_mline_index,
+                            }).await;
+                        }
+                    }
+                    // webrtc-rs calls the callback with `None` once ICE
+                    // gathering has finished; forward that as an explicit
+                    // end-of-candidates signal instead of leaving the remote
+                    // peer to guess when trickling is done.
+                    None => {
+                        tracing::debug!("ICE gathering complete");
+                        if let Some(ref tx) = *event_tx.read().await {
+                            let _ = tx.send(PeerEvent::IceGatheringComplete).await;
+                        }
                     }
                 }
             })
@@ -250,13 +322,20 @@ impl P2PPeer {
     pub async fn setup_data_channel_handler(&self) -> Result<(), P2PError> {
         let data_channel_store = self.data_channel.clone();
         let event_tx = self.event_tx.clone();
+        let last_activity = self.last_activity.clone();
 
         self.peer_connection.on_data_channel(Box::new(move |dc| {
             let data_channel_store = data_channel_store.clone();
             let event_tx = event_tx.clone();
+            let last_activity = last_activity.clone();
             let dc_label = dc.label().to_string();
 
             Box::pin(async move {
+                // Ordered/reliability is negotiated by whichever side calls
+                // create_data_channel (see `PeerConfig::data_channel` /
+                // `create_offer`); this side is the answerer and just
+                // accepts whatever the remote end already set, so there's
+                // nothing to configure here.
                 tracing::info!("New data channel: {}", dc_label);
 
                 // Store the data channel
@@ -264,13 +343,17 @@ impl P2PPeer {
 
                 // Set up message handler
                 let event_tx_msg = event_tx.clone();
+                let last_activity_msg = last_activity.clone();
                 dc.on_message(Box::new(move |msg: DataChannelMessage| {
                     let event_tx = event_tx_msg.clone();
+                    let last_activity = last_activity_msg.clone();
                     let data = msg.data.to_vec();
 
                     Box::pin(async move {
                         tracing::debug!("Received {} bytes on data channel", data.len());
 
+                        *last_activity.write().await = Instant::now();
+
                         if let Some(ref tx) = *event_tx.read().await {
                             let _ = tx.send(PeerEvent::DataReceived(data)).await;
                         }
@@ -299,18 +382,24 @@ impl P2PPeer {
     /// Create an SDP offer for initiating a connection
     pub async fn create_offer(&self) -> Result<String, P2PError> {
         // Create a data channel first (offerer creates the channel)
-        let dc = self.peer_connection.create_data_channel("data", None).await
+        let dc = self.peer_connection
+            .create_data_channel("data", Some(self.config.data_channel.to_init()))
+            .await
             .map_err(|e| P2PError::Channel(format!("Failed to create data channel: {}", e)))?;
 
         *self.data_channel.write().await = Some(dc.clone());
 
         // Set up data channel handlers
         let event_tx = self.event_tx.clone();
+        let last_activity = self.last_activity.clone();
         dc.on_message(Box::new(move |msg: DataChannelMessage| {
             let event_tx = event_tx.clone();
+            let last_activity = last_activity.clone();
             let data = msg.data.to_vec();
 
             Box::pin(async move {
+                *last_activity.write().await = Instant::now();
+
                 if let Some(ref tx) = *event_tx.read().await {
                     let _ = tx.send(PeerEvent::DataReceived(data)).await;
                 }
@@ -481,6 +570,21 @@ impl P2PPeer {
         self.state() == ConnectionState::Connected
     }
 
+    /// Record that data was sent or received on this peer's DataChannel just
+    /// now, resetting [`idle_duration`](Self::idle_duration) to zero.
+    pub async fn touch_activity(&self) {
+        *self.last_activity.write().await = Instant::now();
+    }
+
+    /// How long it's been since [`touch_activity`](Self::touch_activity) was
+    /// last called (or since the peer was created, if never). Used by the
+    /// reaper in [`crate::p2p::runtime::P2PRuntime`] to find peers whose
+    /// DataChannel has gone quiet without the underlying WebRTC connection
+    /// ever reporting `Disconnected`/`Failed`.
+    pub async fn idle_duration(&self) -> Duration {
+        self.last_activity.read().await.elapsed()
+    }
+
     /// Get the peer configuration for recreation
     pub async fn get_config(&self) -> PeerConfig {
         let config = self.peer_connection.get_configuration().await;
@@ -0,0 +1,134 @@
+//! Per-peer token-bucket rate limiting for DataChannel gRPC requests.
+//!
+//! A single misbehaving (or buggy) browser peer can flood the gateway with
+//! gRPC requests over its DataChannel, starving background scrape jobs of
+//! CPU/lock time. Each peer gets its own bucket, checked before the request
+//! reaches `process_request_with_reflection`; a peer's bucket is dropped on
+//! disconnect so a reconnecting peer starts with a fresh allowance.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::RwLock;
+
+/// Requests/sec and burst size for a [`PeerRateLimiter`].
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    pub requests_per_sec: f64,
+    pub burst: f64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_sec: 20.0,
+            burst: 40.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            tokens: config.burst,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn try_consume(&mut self, config: &RateLimitConfig) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * config.requests_per_sec).min(config.burst);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Tracks one [`TokenBucket`] per connected peer, keyed by peer ID.
+#[derive(Clone)]
+pub struct PeerRateLimiter {
+    config: RateLimitConfig,
+    buckets: Arc<RwLock<HashMap<String, TokenBucket>>>,
+}
+
+impl PeerRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Consume one token for `peer_id`, creating its bucket (full) on first
+    /// use. Returns `false` if the peer has no tokens left and the request
+    /// should be rejected instead of processed.
+    pub async fn check(&self, peer_id: &str) -> bool {
+        let mut buckets = self.buckets.write().await;
+        let bucket = buckets
+            .entry(peer_id.to_string())
+            .or_insert_with(|| TokenBucket::new(&self.config));
+        bucket.try_consume(&self.config)
+    }
+
+    /// Drop `peer_id`'s bucket, e.g. once its DataChannel disconnects.
+    pub async fn remove(&self, peer_id: &str) {
+        self.buckets.write().await.remove(peer_id);
+    }
+}
+
+impl Default for PeerRateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_allows_requests_within_burst() {
+        let limiter = PeerRateLimiter::new(RateLimitConfig { requests_per_sec: 1.0, burst: 3.0 });
+        assert!(limiter.check("peer-1").await);
+        assert!(limiter.check("peer-1").await);
+        assert!(limiter.check("peer-1").await);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_requests_beyond_burst() {
+        let limiter = PeerRateLimiter::new(RateLimitConfig { requests_per_sec: 1.0, burst: 2.0 });
+        assert!(limiter.check("peer-1").await);
+        assert!(limiter.check("peer-1").await);
+        assert!(!limiter.check("peer-1").await);
+    }
+
+    #[tokio::test]
+    async fn test_peers_are_tracked_independently() {
+        let limiter = PeerRateLimiter::new(RateLimitConfig { requests_per_sec: 1.0, burst: 1.0 });
+        assert!(limiter.check("peer-1").await);
+        assert!(!limiter.check("peer-1").await);
+        assert!(limiter.check("peer-2").await);
+    }
+
+    #[tokio::test]
+    async fn test_remove_drops_bucket_state() {
+        let limiter = PeerRateLimiter::new(RateLimitConfig { requests_per_sec: 1.0, burst: 1.0 });
+        assert!(limiter.check("peer-1").await);
+        assert!(!limiter.check("peer-1").await);
+        limiter.remove("peer-1").await;
+        assert!(limiter.check("peer-1").await);
+    }
+}
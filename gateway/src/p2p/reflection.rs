@@ -0,0 +1,261 @@
+//! Standard gRPC Server Reflection (`grpc.reflection.v1alpha.ServerReflection`)
+//! over the DataChannel gRPC-Web bridge.
+//!
+//! [`handle_list_services`](super::grpc_handler::handle_list_services) and
+//! [`handle_file_containing_symbol`](super::grpc_handler::handle_file_containing_symbol)
+//! answer a bespoke JSON shape that only the cf-wbrtc-auth frontend
+//! understands. This module decodes the *real* `ServerReflectionRequest`
+//! protobuf message and emits `ServerReflectionResponse` messages, so
+//! off-the-shelf gRPC-Web reflection clients (grpcurl-web, Buf Studio) can
+//! introspect the gateway over P2P too. The JSON path is left untouched for
+//! the existing frontend.
+
+use prost::{Message, Oneof};
+use prost_types::FileDescriptorSet;
+
+use super::grpc_handler::{GrpcRequest, GrpcResponse, StatusCode};
+
+/// `grpc.reflection.v1alpha.ServerReflectionRequest`
+#[derive(Clone, PartialEq, Message)]
+pub struct ServerReflectionRequest {
+    #[prost(string, tag = "1")]
+    pub host: String,
+    #[prost(oneof = "MessageRequest", tags = "3, 4, 6, 7")]
+    pub message_request: Option<MessageRequest>,
+}
+
+#[derive(Clone, PartialEq, Oneof)]
+#[prost(skip_debug)]
+pub enum MessageRequest {
+    #[prost(string, tag = "3")]
+    FileByFilename(String),
+    #[prost(string, tag = "4")]
+    FileContainingSymbol(String),
+    #[prost(string, tag = "6")]
+    AllExtensionNumbersOfType(String),
+    #[prost(string, tag = "7")]
+    ListServices(String),
+}
+
+/// `grpc.reflection.v1alpha.ServerReflectionResponse`
+#[derive(Clone, PartialEq, Message)]
+pub struct ServerReflectionResponse {
+    #[prost(string, tag = "1")]
+    pub valid_host: String,
+    #[prost(oneof = "MessageResponse", tags = "4, 6, 7")]
+    pub message_response: Option<MessageResponse>,
+}
+
+#[derive(Clone, PartialEq, Oneof)]
+pub enum MessageResponse {
+    #[prost(message, tag = "4")]
+    FileDescriptorResponse(FileDescriptorResponse),
+    #[prost(message, tag = "6")]
+    ListServicesResponse(ListServiceResponse),
+    #[prost(message, tag = "7")]
+    ErrorResponse(ErrorResponse),
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct FileDescriptorResponse {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub file_descriptor_proto: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ListServiceResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub service: Vec<ServiceResponse>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ServiceResponse {
+    #[prost(string, tag = "1")]
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ErrorResponse {
+    #[prost(int32, tag = "1")]
+    pub error_code: i32,
+    #[prost(string, tag = "2")]
+    pub error_message: String,
+}
+
+/// Standard `ServerReflectionInfo` method path (bidi-streaming, but we accept
+/// one request per DataChannel message like the rest of this bridge).
+pub const SERVER_REFLECTION_INFO_PATH: &str =
+    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo";
+
+/// Whether `path` is the standard binary reflection RPC
+pub fn is_server_reflection_info_request(path: &str) -> bool {
+    path == SERVER_REFLECTION_INFO_PATH
+        || path == "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo"
+}
+
+/// Handle a `ServerReflectionInfo` request using the real protobuf wire format
+pub fn handle_server_reflection_info(file_descriptor_set: &[u8], request: &GrpcRequest) -> GrpcResponse {
+    let reflection_request = match ServerReflectionRequest::decode(request.message()) {
+        Ok(req) => req,
+        Err(e) => {
+            tracing::warn!("ServerReflectionInfo: invalid request protobuf: {}", e);
+            return GrpcResponse::error(StatusCode::InvalidArgument, "invalid ServerReflectionRequest");
+        }
+    };
+
+    let fds = match FileDescriptorSet::decode(file_descriptor_set) {
+        Ok(fds) => fds,
+        Err(e) => {
+            tracing::error!("ServerReflectionInfo: failed to parse FILE_DESCRIPTOR_SET: {}", e);
+            return GrpcResponse::error(StatusCode::Internal, "failed to parse descriptor set");
+        }
+    };
+
+    let message_response = match &reflection_request.message_request {
+        Some(MessageRequest::ListServices(_)) => {
+            MessageResponse::ListServicesResponse(list_services_response(&fds))
+        }
+        Some(MessageRequest::FileContainingSymbol(symbol)) => {
+            match file_descriptor_response_for_symbol(&fds, symbol) {
+                Some(resp) => MessageResponse::FileDescriptorResponse(resp),
+                None => MessageResponse::ErrorResponse(ErrorResponse {
+                    error_code: StatusCode::NotFound as i32,
+                    error_message: format!("symbol not found: {}", symbol),
+                }),
+            }
+        }
+        Some(other) => MessageResponse::ErrorResponse(ErrorResponse {
+            error_code: StatusCode::Unimplemented as i32,
+            error_message: format!("unsupported reflection request: {:?}", other),
+        }),
+        None => MessageResponse::ErrorResponse(ErrorResponse {
+            error_code: StatusCode::InvalidArgument as i32,
+            error_message: "missing message_request".to_string(),
+        }),
+    };
+
+    let response = ServerReflectionResponse {
+        valid_host: reflection_request.host.clone(),
+        message_response: Some(message_response),
+    };
+
+    GrpcResponse::ok(response.encode_to_vec())
+}
+
+impl std::fmt::Debug for MessageRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageRequest::FileByFilename(v) => write!(f, "FileByFilename({})", v),
+            MessageRequest::FileContainingSymbol(v) => write!(f, "FileContainingSymbol({})", v),
+            MessageRequest::AllExtensionNumbersOfType(v) => write!(f, "AllExtensionNumbersOfType({})", v),
+            MessageRequest::ListServices(v) => write!(f, "ListServices({})", v),
+        }
+    }
+}
+
+fn list_services_response(fds: &FileDescriptorSet) -> ListServiceResponse {
+    let mut service = Vec::new();
+    for file in &fds.file {
+        let package = file.package.as_deref().unwrap_or("");
+        for svc in &file.service {
+            let svc_name = svc.name.as_deref().unwrap_or("");
+            let full_name = if package.is_empty() {
+                svc_name.to_string()
+            } else {
+                format!("{}.{}", package, svc_name)
+            };
+            service.push(ServiceResponse { name: full_name });
+        }
+    }
+    ListServiceResponse { service }
+}
+
+fn file_descriptor_response_for_symbol(fds: &FileDescriptorSet, symbol: &str) -> Option<FileDescriptorResponse> {
+    for file in &fds.file {
+        let package = file.package.as_deref().unwrap_or("");
+
+        let matches_service_or_method = file.service.iter().any(|service| {
+            let service_name = service.name.as_deref().unwrap_or("");
+            let full_service_name = if package.is_empty() {
+                service_name.to_string()
+            } else {
+                format!("{}.{}", package, service_name)
+            };
+
+            if full_service_name == symbol {
+                return true;
+            }
+
+            service.method.iter().any(|method| {
+                let method_name = method.name.as_deref().unwrap_or("");
+                format!("{}.{}", full_service_name, method_name) == symbol
+            })
+        });
+
+        if matches_service_or_method {
+            return Some(FileDescriptorResponse {
+                file_descriptor_proto: vec![file.encode_to_vec()],
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request_with(message_request: MessageRequest) -> GrpcRequest {
+        let req = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(message_request),
+        };
+        GrpcRequest {
+            path: SERVER_REFLECTION_INFO_PATH.to_string(),
+            headers: HashMap::new(),
+            messages: vec![req.encode_to_vec()],
+        }
+    }
+
+    #[test]
+    fn test_is_server_reflection_info_request() {
+        assert!(is_server_reflection_info_request(SERVER_REFLECTION_INFO_PATH));
+        assert!(!is_server_reflection_info_request("/scraper.ETCScraper/Health"));
+    }
+
+    #[test]
+    fn test_handle_list_services() {
+        let request = request_with(MessageRequest::ListServices(String::new()));
+        let response = handle_server_reflection_info(proto::FILE_DESCRIPTOR_SET, &request);
+
+        assert_eq!(response.status, StatusCode::Ok);
+        let decoded = ServerReflectionResponse::decode(response.messages[0].as_slice()).unwrap();
+        match decoded.message_response {
+            Some(MessageResponse::ListServicesResponse(resp)) => {
+                assert!(!resp.service.is_empty());
+            }
+            other => panic!("expected ListServicesResponse, got {:?}", other.is_some()),
+        }
+    }
+
+    #[test]
+    fn test_handle_file_containing_symbol_found() {
+        let request = request_with(MessageRequest::FileContainingSymbol("scraper.ETCScraper".to_string()));
+        let response = handle_server_reflection_info(proto::FILE_DESCRIPTOR_SET, &request);
+
+        assert_eq!(response.status, StatusCode::Ok);
+        let decoded = ServerReflectionResponse::decode(response.messages[0].as_slice()).unwrap();
+        assert!(matches!(decoded.message_response, Some(MessageResponse::FileDescriptorResponse(_))));
+    }
+
+    #[test]
+    fn test_handle_file_containing_symbol_not_found() {
+        let request = request_with(MessageRequest::FileContainingSymbol("nonexistent.Service".to_string()));
+        let response = handle_server_reflection_info(proto::FILE_DESCRIPTOR_SET, &request);
+
+        let decoded = ServerReflectionResponse::decode(response.messages[0].as_slice()).unwrap();
+        assert!(matches!(decoded.message_response, Some(MessageResponse::ErrorResponse(_))));
+    }
+}
@@ -0,0 +1,766 @@
+//! Shared event-handling runtime for the two P2P entry points in `main.rs`.
+//!
+//! `run_p2p_client` (interactive console) and `run_p2p_service` (Windows
+//! service) used to carry their own copies of `P2PState`, `P2PEventHandler`,
+//! and the `on_offer` offer/answer/ICE dance, and the copies had already
+//! drifted (background-task fan-out for `DataReceived`, richer ICE logging).
+//! `P2PRuntime` owns that shared logic once; the two `main.rs` functions are
+//! now thin wrappers that build a runtime, hand it to the signaling client,
+//! and own only what's genuinely different between them: how they wait for a
+//! shutdown signal and how chatty they are on stdout.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+
+use crate::config::GatewayConfig;
+use crate::job::JobQueue;
+use crate::p2p::grpc_handler::TonicServiceBridge;
+use crate::p2p::{
+    AppRegisteredPayload, AppStatusPayload, AuthErrorPayload, AuthOKPayload,
+    AuthenticatedSignalingClient, ConnectionState, P2PConfig, P2PError, P2PPeer, PeerEvent,
+    PeerRateLimiter, PeerRecreator, SignalingEventHandler,
+};
+
+/// Default maximum number of simultaneously connected P2P peers (see
+/// [`GatewayConfig::p2p_max_peers`]). Generous enough for normal single-user
+/// or small-team usage while still bounding a connection storm.
+pub const DEFAULT_MAX_PEERS: usize = 100;
+
+/// Default maximum number of times a failed/disconnected peer is
+/// transparently recreated and re-offered before being dropped for good
+/// (see [`GatewayConfig::p2p_peer_recreate_max_retries`]).
+pub const DEFAULT_PEER_RECREATE_MAX_RETRIES: u32 = 3;
+
+/// gRPC services bridged over the P2P DataChannel, combined into one
+/// `tonic::service::Routes` so a single `TonicServiceBridge` can dispatch to
+/// all of them.
+pub type RoutesBridge = TonicServiceBridge<tonic::service::Routes>;
+
+/// One active peer connection plus the bookkeeping `spawn_peer_event_loop`
+/// needs to transparently recreate it: the signaling `request_id` its
+/// offer/answer used (so a recreated peer's new offer is addressed back to
+/// the same browser session via [`AuthenticatedSignalingClient::send_offer`])
+/// and how many times it's already been recreated (see
+/// [`DEFAULT_PEER_RECREATE_MAX_RETRIES`]).
+struct PeerSession {
+    peer: Arc<P2PPeer>,
+    request_id: Option<String>,
+    recreate_attempts: u32,
+}
+
+/// Peer connections and the signaling client, shared between the event
+/// handler and whichever `main.rs` function drives connect/shutdown.
+struct P2PState {
+    signaling_client: Option<Arc<RwLock<AuthenticatedSignalingClient>>>,
+    peers: HashMap<String, PeerSession>,
+    peer_counter: u64,
+}
+
+impl P2PState {
+    fn new() -> Self {
+        Self {
+            signaling_client: None,
+            peers: HashMap::new(),
+            peer_counter: 0,
+        }
+    }
+
+    fn next_peer_id(&mut self) -> String {
+        self.peer_counter += 1;
+        format!("peer-{}", self.peer_counter)
+    }
+
+    fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+}
+
+/// Shared state, gRPC bridge, and rate limiter for one P2P session, plus the
+/// [`SignalingEventHandler`] implementation that drives it.
+///
+/// `verbose` controls whether connection/offer events are *also* printed to
+/// stdout/stderr for a human watching a console, on top of the `tracing`
+/// calls that are always made; `run_p2p_client` passes `true`, `run_p2p_service`
+/// passes `false` since nothing is watching its stdout.
+pub struct P2PRuntime {
+    state: Arc<RwLock<P2PState>>,
+    grpc_bridge: Arc<RoutesBridge>,
+    rate_limiter: PeerRateLimiter,
+    verbose: bool,
+    /// Requests currently being processed, for the `busy` field of the
+    /// `app_status` heartbeat (see `spawn_status_heartbeat`).
+    active_requests: Arc<std::sync::atomic::AtomicUsize>,
+    /// Maximum number of simultaneously connected peers; `on_offer` rejects
+    /// further offers once `state.peer_count()` reaches this.
+    max_peers: usize,
+    /// Maximum number of times `spawn_peer_event_loop` will recreate and
+    /// re-offer a failed/disconnected peer before giving up on it.
+    max_peer_recreate_retries: u32,
+}
+
+impl P2PRuntime {
+    /// Build the gRPC `Routes` bridge and rate limiter from `config`, with
+    /// empty peer/signaling-client state.
+    pub fn new(config: GatewayConfig, verbose: bool) -> Self {
+        let rate_limiter = PeerRateLimiter::new(config.p2p_rate_limit_config());
+        let large_message_threshold_bytes = config.p2p_large_message_threshold_bytes;
+        let slow_request_threshold = config.p2p_slow_request_threshold();
+        let max_peers = config.p2p_max_peers;
+        let max_peer_recreate_retries = config.p2p_peer_recreate_max_retries;
+
+        let job_queue = Arc::new(RwLock::new(JobQueue::new()));
+        let scraper_service = crate::EtcScraperService::new(config, job_queue);
+        let pdf_service = crate::PdfGeneratorService::new();
+        let timecard_service = timecard_service::TimecardGrpcService::new();
+
+        let mut routes = tonic::service::Routes::new(
+            crate::grpc::scraper_server::etc_scraper_server::EtcScraperServer::new(scraper_service),
+        )
+        .add_service(crate::grpc::pdf_server::pdf_generator_server::PdfGeneratorServer::new(pdf_service))
+        .add_service(crate::grpc::timecard_server::timecard_service_server::TimecardServiceServer::new(timecard_service));
+
+        // A bad/missing FILE_DESCRIPTOR_SET shouldn't take down the P2P gRPC
+        // bridge entirely: log it and serve without reflection instead of
+        // panicking at startup.
+        match tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+            .build_v1()
+        {
+            Ok(reflection_service) => {
+                routes = routes.add_service(reflection_service);
+                tracing::info!("P2P gRPC reflection: enabled");
+            }
+            Err(e) => {
+                tracing::warn!("P2P gRPC reflection: disabled (failed to build reflection service: {})", e);
+            }
+        }
+
+        Self {
+            state: Arc::new(RwLock::new(P2PState::new())),
+            grpc_bridge: Arc::new(
+                TonicServiceBridge::new(routes)
+                    .with_thresholds(large_message_threshold_bytes, slow_request_threshold),
+            ),
+            rate_limiter,
+            verbose,
+            active_requests: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            max_peers,
+            max_peer_recreate_retries,
+        }
+    }
+
+    /// Record the signaling client, so `on_offer`/`on_connected` can send
+    /// answers/ICE/re-registration back through it.
+    pub async fn set_signaling_client(&self, client: Arc<RwLock<AuthenticatedSignalingClient>>) {
+        self.state.write().await.signaling_client = Some(client);
+    }
+
+    /// Current number of connected peers.
+    pub async fn peer_count(&self) -> usize {
+        self.state.read().await.peer_count()
+    }
+
+    /// Clean up every connected peer. Called on shutdown by both entry
+    /// points, after they've stopped the signaling client.
+    pub async fn close_all_peers(&self) {
+        let sessions: Vec<(String, PeerSession)> = {
+            let mut state = self.state.write().await;
+            state.peers.drain().collect()
+        };
+
+        tracing::info!("Closing {} peer connections", sessions.len());
+        for (peer_id, session) in sessions {
+            tracing::info!("Closing peer {}", peer_id);
+            if let Err(e) = session.peer.cleanup().await {
+                tracing::warn!("Failed to cleanup peer {}: {:?}", peer_id, e);
+            }
+        }
+    }
+
+    /// Spawn the background reaper that periodically sweeps the peer map for
+    /// connections that are already `Disconnected`/`Failed` (belt-and-braces
+    /// against a missed `Disconnected` event) or whose DataChannel has gone
+    /// idle longer than `idle_timeout`. Half-open WebRTC connections - e.g.
+    /// an abandoned browser tab that never sends a clean close - don't
+    /// always fire `on_peer_connection_state_change`, so without this peers
+    /// like that would sit in the map forever.
+    ///
+    /// Runs for as long as the returned handle isn't dropped/aborted; both
+    /// `main.rs` entry points let it run until the process is shutting down.
+    pub fn spawn_peer_reaper(&self, idle_timeout: Duration) -> tokio::task::JoinHandle<()> {
+        const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+        let state = self.state.clone();
+        let rate_limiter = self.rate_limiter.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(REAP_INTERVAL);
+            loop {
+                ticker.tick().await;
+
+                let mut stale = Vec::new();
+                for (peer_id, session) in state.read().await.peers.iter() {
+                    let dead = matches!(session.peer.state(), ConnectionState::Disconnected | ConnectionState::Failed);
+                    let idle = session.peer.idle_duration().await >= idle_timeout;
+                    if dead || idle {
+                        stale.push((peer_id.clone(), dead, idle));
+                    }
+                }
+
+                if stale.is_empty() {
+                    continue;
+                }
+
+                for (peer_id, dead, idle) in stale {
+                    let removed = state.write().await.peers.remove(&peer_id);
+                    let Some(session) = removed else { continue };
+
+                    tracing::info!(
+                        "Reaping peer {} (dead={}, idle={})",
+                        peer_id, dead, idle
+                    );
+                    if let Err(e) = session.peer.cleanup().await {
+                        tracing::warn!("Failed to cleanup reaped peer {}: {:?}", peer_id, e);
+                    }
+                    rate_limiter.remove(&peer_id).await;
+                }
+
+                metrics::gauge!("p2p_active_peers").set(state.read().await.peer_count() as f64);
+            }
+        })
+    }
+
+    /// Spawn a background task that sends an `app_status` heartbeat over the
+    /// signaling client on a fixed interval, for as long as a client has
+    /// been set via [`set_signaling_client`](Self::set_signaling_client).
+    /// Ticks where no client is set yet (e.g. the brief window before the
+    /// first connect) are silently skipped rather than treated as errors.
+    pub fn spawn_status_heartbeat(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let state = self.state.clone();
+        let active_requests = self.active_requests.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                let (peer_count, client) = {
+                    let state = state.read().await;
+                    (state.peer_count(), state.signaling_client.clone())
+                };
+
+                let Some(client) = client else { continue };
+
+                let status = AppStatusPayload {
+                    peer_count,
+                    busy: active_requests.load(std::sync::atomic::Ordering::Relaxed) > 0,
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                };
+
+                let client = client.read().await;
+                if let Err(e) = client.send_app_status(status).await {
+                    tracing::warn!("Failed to send app_status heartbeat: {:?}", e);
+                }
+            }
+        })
+    }
+
+    fn log_info(&self, message: impl AsRef<str>) {
+        let message = message.as_ref();
+        tracing::info!("{}", message);
+        if self.verbose {
+            println!("{}", message);
+        }
+    }
+
+    fn log_error(&self, message: impl AsRef<str>) {
+        let message = message.as_ref();
+        tracing::error!("{}", message);
+        if self.verbose {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// Spawn the per-peer event loop: connect/disconnect bookkeeping plus
+    /// routing `DataReceived` payloads to the gRPC bridge. Each inbound
+    /// request gets its own task (rate-limited per peer), so a slow request
+    /// doesn't block others behind it on the same DataChannel, while a
+    /// single request's own response messages are still sent in order.
+    ///
+    /// On `Disconnected` (which also covers a `Failed` WebRTC state, see
+    /// `P2PPeer::needs_recreation`), this attempts to transparently recreate
+    /// the peer via [`PeerRecreator`] and re-offer it to the same browser
+    /// session instead of just dropping it - see
+    /// [`spawn_peer_event_loop_inner`]'s `PeerEvent::Disconnected` arm.
+    fn spawn_peer_event_loop(
+        &self,
+        peer: Arc<P2PPeer>,
+        peer_id: String,
+        event_rx: tokio::sync::mpsc::Receiver<PeerEvent>,
+    ) {
+        spawn_peer_event_loop_inner(
+            self.state.clone(),
+            self.grpc_bridge.clone(),
+            self.rate_limiter.clone(),
+            self.active_requests.clone(),
+            self.max_peer_recreate_retries,
+            peer,
+            peer_id,
+            event_rx,
+        );
+    }
+}
+
+/// Shared body of [`P2PRuntime::spawn_peer_event_loop`], factored out of the
+/// method so a successful recreation (see the `PeerEvent::Disconnected` arm)
+/// can spawn a fresh event loop for the new peer without borrowing `&self`
+/// across a `'static` task.
+fn spawn_peer_event_loop_inner(
+    state: Arc<RwLock<P2PState>>,
+    grpc_bridge: Arc<RoutesBridge>,
+    rate_limiter: PeerRateLimiter,
+    active_requests: Arc<std::sync::atomic::AtomicUsize>,
+    max_peer_recreate_retries: u32,
+    peer: Arc<P2PPeer>,
+    peer_id: String,
+    mut event_rx: tokio::sync::mpsc::Receiver<PeerEvent>,
+) {
+    tokio::spawn(async move {
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                PeerEvent::Connected => {
+                    tracing::info!("WebRTC peer {} connected!", peer_id);
+                    let count = state.read().await.peer_count();
+                    metrics::gauge!("p2p_active_peers").set(count as f64);
+                }
+                PeerEvent::Disconnected => {
+                    tracing::info!("WebRTC peer {} disconnected", peer_id);
+
+                    let removed_session = {
+                        let mut state = state.write().await;
+                        let removed = state.peers.remove(&peer_id);
+                        metrics::gauge!("p2p_active_peers").set(state.peer_count() as f64);
+                        removed
+                    };
+
+                    let Some(session) = removed_session else { break };
+
+                    // Capture the config (ICE/TURN servers) before
+                    // cleanup closes the underlying connection.
+                    let recreator = PeerRecreator::from_peer(&session.peer).await;
+
+                    if let Err(e) = session.peer.cleanup().await {
+                        tracing::warn!("Failed to cleanup peer {}: {:?}", peer_id, e);
+                    } else {
+                        tracing::debug!("Peer {} cleanup complete", peer_id);
+                    }
+
+                    if session.recreate_attempts >= max_peer_recreate_retries {
+                        if session.recreate_attempts > 0 {
+                            tracing::warn!(
+                                "Peer {} exceeded max recreation attempts ({}), giving up",
+                                peer_id, max_peer_recreate_retries
+                            );
+                            metrics::counter!("p2p_peer_recreations_total", "outcome" => "exhausted").increment(1);
+                        }
+                        rate_limiter.remove(&peer_id).await;
+                        break;
+                    }
+
+                    let attempt = session.recreate_attempts + 1;
+                    tracing::info!(
+                        "Attempting to recreate peer {} (attempt {}/{})",
+                        peer_id, attempt, max_peer_recreate_retries
+                    );
+
+                    let outcome = recreate_and_reoffer(
+                        &state,
+                        &recreator,
+                        &peer_id,
+                        session.request_id.as_deref(),
+                    )
+                    .await;
+
+                    match outcome {
+                        Ok(new_peer) => {
+                            let new_event_rx = new_peer.subscribe().await;
+
+                            {
+                                let mut state = state.write().await;
+                                state.peers.insert(
+                                    peer_id.clone(),
+                                    PeerSession {
+                                        peer: new_peer.clone(),
+                                        request_id: session.request_id.clone(),
+                                        recreate_attempts: attempt,
+                                    },
+                                );
+                                metrics::gauge!("p2p_active_peers").set(state.peer_count() as f64);
+                            }
+
+                            metrics::counter!("p2p_peer_recreations_total", "outcome" => "success").increment(1);
+                            tracing::info!("Peer {} recreated and re-offered (attempt {})", peer_id, attempt);
+
+                            spawn_peer_event_loop_inner(
+                                state.clone(),
+                                grpc_bridge.clone(),
+                                rate_limiter.clone(),
+                                active_requests.clone(),
+                                max_peer_recreate_retries,
+                                new_peer,
+                                peer_id.clone(),
+                                new_event_rx,
+                            );
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to recreate peer {}: {:?}", peer_id, e);
+                            metrics::counter!("p2p_peer_recreations_total", "outcome" => "failed").increment(1);
+                            rate_limiter.remove(&peer_id).await;
+                        }
+                    }
+
+                    break;
+                }
+                PeerEvent::DataReceived(data) => {
+                    tracing::debug!("Received data ({} bytes) from peer {}", data.len(), peer_id);
+
+                    let peer = peer.clone();
+                    let grpc_bridge = grpc_bridge.clone();
+                    let rate_limiter = rate_limiter.clone();
+                    let peer_id = peer_id.clone();
+                    let active_requests = active_requests.clone();
+                    tokio::spawn(async move {
+                        if !rate_limiter.check(&peer_id).await {
+                            tracing::warn!("Peer {} exceeded its DataChannel rate limit, rejecting request", peer_id);
+                            let response = crate::p2p::grpc_handler::GrpcResponse::error(
+                                crate::p2p::grpc_handler::StatusCode::ResourceExhausted,
+                                "rate limit exceeded",
+                            );
+                            let encoded = crate::p2p::grpc_handler::encode_response(&response);
+                            if let Err(e) = peer.send(&encoded).await {
+                                tracing::error!("Failed to send rate limit response to {}: {:?}", peer_id, e);
+                            }
+                            return;
+                        }
+
+                        active_requests.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        let result = crate::p2p::grpc_handler::process_request_with_reflection(
+                            &data,
+                            &grpc_bridge,
+                            Some(proto::FILE_DESCRIPTOR_SET),
+                        )
+                        .await;
+                        active_requests.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+
+                        match result {
+                            crate::p2p::grpc_handler::GrpcProcessResult::Unary(response) => {
+                                if let Err(e) = peer.send(&response).await {
+                                    tracing::error!("Failed to send response to {}: {:?}", peer_id, e);
+                                }
+                            }
+                            crate::p2p::grpc_handler::GrpcProcessResult::Streaming(messages) => {
+                                tracing::info!("Sending {} stream messages to {}", messages.len(), peer_id);
+                                for (i, msg) in messages.iter().enumerate() {
+                                    if let Err(e) = peer.send(msg).await {
+                                        tracing::error!(
+                                            "Failed to send stream message {}/{} to {}: {:?}",
+                                            i + 1,
+                                            messages.len(),
+                                            peer_id,
+                                            e
+                                        );
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    });
+                }
+                PeerEvent::IceCandidate { candidate, sdp_mid, sdp_mline_index } => {
+                    tracing::debug!(
+                        "Local ICE candidate for {}: {} (mid: {:?}, index: {:?})",
+                        peer_id,
+                        candidate,
+                        sdp_mid,
+                        sdp_mline_index
+                    );
+
+                    let client = {
+                        let state = state.read().await;
+                        state.signaling_client.clone()
+                    };
+                    if let Some(client) = client {
+                        let client = client.read().await;
+                        let candidate_json = serde_json::json!({
+                            "candidate": candidate,
+                            "sdpMid": sdp_mid,
+                            "sdpMLineIndex": sdp_mline_index,
+                        });
+                        if let Err(e) = client.send_ice(candidate_json).await {
+                            tracing::warn!("Failed to send ICE candidate for {}: {:?}", peer_id, e);
+                        }
+                    }
+                }
+                PeerEvent::IceGatheringComplete => {
+                    tracing::debug!("ICE gathering complete for peer {}", peer_id);
+
+                    let client = {
+                        let state = state.read().await;
+                        state.signaling_client.clone()
+                    };
+                    if let Some(client) = client {
+                        let client = client.read().await;
+                        // End-of-candidates marker (RFC 8840): an empty
+                        // candidate string tells the remote side trickling
+                        // is done instead of it having to guess.
+                        let marker = serde_json::json!({ "candidate": "" });
+                        if let Err(e) = client.send_ice(marker).await {
+                            tracing::warn!(
+                                "Failed to send end-of-candidates marker for {}: {:?}",
+                                peer_id,
+                                e
+                            );
+                        }
+                    }
+                }
+                PeerEvent::Error(e) => {
+                    tracing::error!("Peer {} error: {}", peer_id, e);
+                }
+            }
+        }
+        tracing::debug!("Event handler task for peer {} exiting", peer_id);
+    });
+}
+
+/// Build a fresh `P2PPeer` from `recreator`'s captured config, create a new
+/// offer for it, and send that offer through the current signaling client
+/// addressed to `request_id` - the same ID the original offer used - so the
+/// signaling server routes it back to the same browser session instead of
+/// treating it as an unrelated new connection.
+async fn recreate_and_reoffer(
+    state: &Arc<RwLock<P2PState>>,
+    recreator: &PeerRecreator,
+    peer_id: &str,
+    request_id: Option<&str>,
+) -> Result<Arc<P2PPeer>, P2PError> {
+    let peer = recreator.recreate().await?;
+    let offer_sdp = peer.create_offer().await?;
+    tracing::debug!("Re-offer SDP for {}:\n{}", peer_id, offer_sdp);
+
+    let client = {
+        let state = state.read().await;
+        state.signaling_client.clone()
+    };
+    let client = client.ok_or_else(|| P2PError::Signaling("no signaling client set".to_string()))?;
+    let client = client.read().await;
+    client.send_offer(&offer_sdp, None, request_id).await?;
+
+    Ok(Arc::new(peer))
+}
+
+#[async_trait::async_trait]
+impl SignalingEventHandler for P2PRuntime {
+    async fn on_authenticated(&self, payload: AuthOKPayload) {
+        self.log_info(format!(
+            "Authenticated! User ID: {}, Type: {}",
+            payload.user_id, payload.user_type
+        ));
+    }
+
+    async fn on_auth_error(&self, payload: AuthErrorPayload) {
+        self.log_error(format!("Auth error: {}", payload.error));
+    }
+
+    async fn on_app_registered(&self, payload: AppRegisteredPayload) {
+        self.log_info(format!("App registered! App ID: {}", payload.app_id));
+        if self.verbose {
+            println!("Waiting for WebRTC offers from browsers...");
+        }
+    }
+
+    async fn on_app_id_changed(&self, old_app_id: Option<String>, new_app_id: String) {
+        self.log_info(format!(
+            "App ID changed from {:?} to {} - browsers that connected to the old id can no longer reach us, closing their peer connections",
+            old_app_id, new_app_id
+        ));
+        self.close_all_peers().await;
+    }
+
+    async fn on_offer(&self, sdp: String, request_id: Option<String>) {
+        let peer_count = self.state.read().await.peer_count();
+        if peer_count >= self.max_peers {
+            self.log_error(format!(
+                "Rejecting offer: at max_peers limit ({}/{})",
+                peer_count, self.max_peers
+            ));
+            metrics::counter!("p2p_offers_rejected_total").increment(1);
+
+            let client = {
+                let state = self.state.read().await;
+                state.signaling_client.clone()
+            };
+            if let Some(client) = client {
+                let client = client.read().await;
+                if let Err(e) = client
+                    .send_error("Gateway is at its maximum number of connections", request_id.as_deref())
+                    .await
+                {
+                    self.log_error(format!("Failed to send max_peers rejection: {:?}", e));
+                }
+            }
+            return;
+        }
+
+        let peer_id = {
+            let mut state = self.state.write().await;
+            state.next_peer_id()
+        };
+
+        tracing::info!(peer_id = %peer_id, request_id = ?request_id, "Received WebRTC offer");
+        tracing::debug!("Offer SDP:\n{}", sdp);
+
+        let peer_config = P2PConfig::from_env_with_stun_defaults(GatewayConfig::from_env().stun_servers)
+            .unwrap_or_else(|e| {
+                self.log_error(format!("Invalid P2P ICE server configuration, using defaults: {}", e));
+                P2PConfig::default()
+            })
+            .to_peer_config();
+
+        let peer = match P2PPeer::new(peer_id.clone(), peer_config).await {
+            Ok(peer) => peer,
+            Err(e) => {
+                self.log_error(format!("Failed to create peer connection: {:?}", e));
+                return;
+            }
+        };
+
+        if let Err(e) = peer.setup_handlers().await {
+            self.log_error(format!("Failed to setup peer handlers: {:?}", e));
+            return;
+        }
+
+        if let Err(e) = peer.setup_data_channel_handler().await {
+            self.log_error(format!("Failed to setup data channel handler: {:?}", e));
+            return;
+        }
+
+        let event_rx = peer.subscribe().await;
+        let peer = Arc::new(peer);
+
+        self.spawn_peer_event_loop(peer.clone(), peer_id.clone(), event_rx);
+
+        match peer.create_answer(&sdp).await {
+            Ok(answer_sdp) => {
+                tracing::debug!("Answer SDP:\n{}", answer_sdp);
+
+                let client = {
+                    let state = self.state.read().await;
+                    state.signaling_client.clone()
+                };
+
+                if let Some(client) = client {
+                    let client = client.read().await;
+                    if let Err(e) = client.send_answer(&answer_sdp, request_id.as_deref()).await {
+                        self.log_error(format!("Failed to send answer: {:?}", e));
+                    } else {
+                        self.log_info(format!("Answer sent for peer {}", peer_id));
+                        // ICE candidates are trickled to the signaling server
+                        // as they're gathered by the event loop above, rather
+                        // than collected and sent in one batch here.
+                    }
+                }
+
+                let mut state = self.state.write().await;
+                state.peers.insert(
+                    peer_id.clone(),
+                    PeerSession {
+                        peer,
+                        request_id,
+                        recreate_attempts: 0,
+                    },
+                );
+                tracing::info!("Peer {} added to state. Total peers: {}", peer_id, state.peer_count());
+            }
+            Err(e) => {
+                self.log_error(format!("Failed to create answer: {:?}", e));
+            }
+        }
+    }
+
+    async fn on_answer(&self, sdp: String, app_id: Option<String>) {
+        tracing::debug!("Received answer (app_id: {:?})", app_id);
+
+        // For multi-peer, we would need to identify which peer this is for.
+        // Currently this only matters when we are the offerer, which isn't
+        // the typical flow for either entry point.
+        let state = self.state.read().await;
+        if let Some((_id, session)) = state.peers.iter().next() {
+            if let Err(e) = session.peer.set_remote_answer(&sdp).await {
+                self.log_error(format!("Failed to set remote answer: {:?}", e));
+            } else {
+                self.log_info("Remote answer set successfully".to_string());
+            }
+        }
+    }
+
+    async fn on_ice(&self, candidate: serde_json::Value) {
+        tracing::debug!("Received remote ICE candidate: {:?}", candidate);
+
+        let candidate_str = candidate.get("candidate").and_then(|v| v.as_str()).unwrap_or("");
+        let sdp_mid = candidate.get("sdpMid").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let sdp_mline_index = candidate.get("sdpMLineIndex").and_then(|v| v.as_u64()).map(|v| v as u16);
+
+        if candidate_str.is_empty() {
+            return;
+        }
+
+        // Added to all peers (in practice, should be targeted to the
+        // specific peer this candidate belongs to).
+        let state = self.state.read().await;
+        for (peer_id, session) in state.peers.iter() {
+            if let Err(e) = session.peer.add_ice_candidate(candidate_str, sdp_mid.clone(), sdp_mline_index).await {
+                tracing::warn!("Failed to add ICE candidate to peer {}: {:?}", peer_id, e);
+            } else {
+                tracing::debug!("Added remote ICE candidate to peer {}", peer_id);
+            }
+        }
+    }
+
+    async fn on_error(&self, message: String) {
+        self.log_error(format!("Signaling error: {}", message));
+    }
+
+    async fn on_connected(&self) {
+        self.log_info("Connected to signaling server!".to_string());
+
+        // Re-register app on reconnection (the initial registration after
+        // the first connect is driven by the entry point itself).
+        let client = {
+            let state = self.state.read().await;
+            state.signaling_client.clone()
+        };
+
+        if let Some(client) = client {
+            let client = client.read().await;
+            if let Err(e) = client.register_app().await {
+                tracing::error!("Failed to register app on reconnect: {:?}", e);
+            } else {
+                self.log_info("App re-registered after reconnection".to_string());
+            }
+        }
+    }
+
+    async fn on_disconnected(&self) {
+        tracing::warn!("Disconnected from signaling server");
+        if self.verbose {
+            println!("Disconnected from signaling server (will reconnect automatically)");
+        }
+        // Don't cleanup peers - they may still be connected via WebRTC. The
+        // signaling server is only needed for establishing new connections.
+        let state = self.state.read().await;
+        tracing::info!("Signaling disconnected, keeping {} active peers", state.peer_count());
+    }
+}
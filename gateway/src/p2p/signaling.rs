@@ -3,12 +3,15 @@
 //! Implements WebSocket-based signaling with API key authentication,
 //! compatible with cf-wbrtc-auth signaling server.
 
+use super::auth;
+use super::credentials::{P2PCredentials, CREDENTIALS_VERSION};
 use super::P2PError;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use url::Url;
 
@@ -84,18 +87,19 @@ struct AppRegisterPayload {
 }
 
 /// Response from successful app registration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct AppRegisteredPayload {
     #[serde(rename = "appId")]
     pub app_id: String,
 }
 
-/// Offer payload from signaling server
-#[derive(Debug, Deserialize)]
+/// Offer payload, both received from the signaling server (a browser's
+/// offer relayed to us) and sent through it (an offer we initiate via
+/// [`AuthenticatedSignalingClient::send_offer`]).
+#[derive(Debug, Serialize, Deserialize)]
 struct OfferPayload {
     sdp: String,
-    #[serde(rename = "targetAppId")]
-    #[allow(dead_code)]
+    #[serde(rename = "targetAppId", skip_serializing_if = "Option::is_none")]
     target_app_id: Option<String>,
 }
 
@@ -107,6 +111,22 @@ struct AnswerPayload {
     app_id: Option<String>,
 }
 
+/// Heartbeat payload sent periodically via [`msg_types::APP_STATUS`] so the
+/// signaling server/dashboard can tell a healthy gateway apart from a
+/// zombie one that's still connected but has stopped doing useful work.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppStatusPayload {
+    /// Number of WebRTC peers currently connected to this gateway
+    #[serde(rename = "peerCount")]
+    pub peer_count: usize,
+
+    /// `true` if this gateway is currently serving at least one request
+    pub busy: bool,
+
+    /// Gateway binary version (`CARGO_PKG_VERSION`)
+    pub version: String,
+}
+
 /// ICE payload
 #[derive(Debug, Serialize, Deserialize)]
 struct ICEPayload {
@@ -117,8 +137,9 @@ struct ICEPayload {
     app_id: Option<String>,
 }
 
-/// Error payload
-#[derive(Debug, Deserialize)]
+/// Error payload, both received from the signaling server and sent through
+/// it (e.g. [`AuthenticatedSignalingClient::send_error`] rejecting an offer).
+#[derive(Debug, Serialize, Deserialize)]
 struct ErrorPayload {
     message: String,
 }
@@ -152,6 +173,20 @@ pub trait SignalingEventHandler: Send + Sync {
     async fn on_connected(&self);
     async fn on_disconnected(&self);
 
+    /// Called when `app_registered` assigns a different `app_id` than the
+    /// one we were previously using (`old_app_id` is `None` on first
+    /// registration, so this only fires on a genuine change - typically
+    /// after a reconnect where the server didn't restore our previous
+    /// registration). Peers set up under `old_app_id` are now orphaned;
+    /// the default does nothing, so implementations that establish P2P
+    /// peers should override this to tear them down.
+    async fn on_app_id_changed(&self, old_app_id: Option<String>, new_app_id: String) {
+        tracing::warn!(
+            "app_id changed from {:?} to {} - peers registered under the old id may be orphaned",
+            old_app_id, new_app_id
+        );
+    }
+
     /// Called when reconnection is starting
     /// Returns true if reconnection should proceed, false to cancel
     async fn on_reconnecting(&self, attempt: u32, delay: Duration) -> bool {
@@ -165,6 +200,69 @@ pub trait SignalingEventHandler: Send + Sync {
     }
 }
 
+/// What's needed to refresh the API key when the signaling server rejects it.
+///
+/// Kept separate from [`SignalingConfig`]'s `api_key` field since the refresh
+/// token and credentials file are only relevant to the refresh flow, not the
+/// connection itself.
+#[derive(Clone, Debug)]
+pub struct RefreshContext {
+    /// Refresh token to exchange for a new API key
+    pub refresh_token: String,
+
+    /// cf-wbrtc-auth server base URL
+    pub auth_server_url: String,
+
+    /// Credentials file to update with the refreshed key, if any
+    pub credentials_path: Option<PathBuf>,
+}
+
+/// Event handler used by [`AuthenticatedSignalingClient::verify`] to report
+/// the first `auth_ok`/`auth_error` back through a oneshot channel and
+/// otherwise ignore everything else the signaling server sends.
+struct VerifyHandler {
+    tx: Mutex<Option<oneshot::Sender<Result<AuthOKPayload, P2PError>>>>,
+}
+
+impl VerifyHandler {
+    fn report(&self, result: Result<AuthOKPayload, P2PError>) {
+        if let Some(tx) = self.tx.lock().unwrap().take() {
+            let _ = tx.send(result);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SignalingEventHandler for VerifyHandler {
+    async fn on_authenticated(&self, payload: AuthOKPayload) {
+        self.report(Ok(payload));
+    }
+
+    async fn on_auth_error(&self, payload: AuthErrorPayload) {
+        self.report(Err(P2PError::Signaling(format!(
+            "Authentication failed: {}",
+            payload.error
+        ))));
+    }
+
+    async fn on_app_registered(&self, _payload: AppRegisteredPayload) {}
+    async fn on_offer(&self, _sdp: String, _request_id: Option<String>) {}
+    async fn on_answer(&self, _sdp: String, _app_id: Option<String>) {}
+    async fn on_ice(&self, _candidate: serde_json::Value) {}
+
+    async fn on_error(&self, message: String) {
+        self.report(Err(P2PError::Signaling(message)));
+    }
+
+    async fn on_connected(&self) {}
+
+    async fn on_disconnected(&self) {
+        self.report(Err(P2PError::Signaling(
+            "Disconnected before receiving an auth result".to_string(),
+        )));
+    }
+}
+
 /// Configuration for SignalingClient
 #[derive(Clone, Debug)]
 pub struct SignalingConfig {
@@ -185,6 +283,16 @@ pub struct SignalingConfig {
 
     /// Reconnection configuration
     pub reconnect: ReconnectConfig,
+
+    /// Unix timestamp (seconds) `api_key` expires at, when known. Used to
+    /// refresh proactively before connecting rather than waiting for the
+    /// server to reject the key.
+    pub expires_at: Option<i64>,
+
+    /// How to refresh `api_key` when it's expired or rejected. `None` means
+    /// refresh is not possible (e.g. no refresh token) and auth errors are
+    /// surfaced to the event handler as-is.
+    pub refresh: Option<RefreshContext>,
 }
 
 impl Default for SignalingConfig {
@@ -196,6 +304,8 @@ impl Default for SignalingConfig {
             capabilities: vec![],
             ping_interval: Duration::from_secs(30),
             reconnect: ReconnectConfig::default(),
+            expires_at: None,
+            refresh: None,
         }
     }
 }
@@ -261,6 +371,10 @@ struct ClientState {
     app_id: String,
     reconnect_attempt: u32,
     should_reconnect: bool,
+    /// Whether we've already tried a refresh-and-retry for the current
+    /// connection attempt. Limits us to one retry per connection so a
+    /// server that keeps rejecting the refreshed key can't loop forever.
+    auth_retried: bool,
 }
 
 /// Authenticated signaling client for P2P communication
@@ -283,6 +397,7 @@ impl AuthenticatedSignalingClient {
                 app_id: String::new(),
                 reconnect_attempt: 0,
                 should_reconnect,
+                auth_retried: false,
             })),
             send_tx: None,
             event_handler: None,
@@ -300,6 +415,8 @@ impl AuthenticatedSignalingClient {
             return Err(P2PError::Signaling("Signaling URL not configured".to_string()));
         }
 
+        self.refresh_api_key_if_expiring().await;
+
         // Build URL with API key
         let mut url = Url::parse(&self.config.server_url)
             .map_err(|e| P2PError::Signaling(format!("Invalid URL: {}", e)))?;
@@ -325,6 +442,7 @@ impl AuthenticatedSignalingClient {
             let mut state = self.state.write().await;
             state.is_connected = true;
             state.reconnect_attempt = 0;  // Reset on successful connection
+            state.auth_retried = false;  // Allow one refresh-and-retry for this connection
         }
 
         // Notify handler (send_tx is now available for register_app)
@@ -339,6 +457,7 @@ impl AuthenticatedSignalingClient {
         let state = Arc::clone(&self.state);
         let event_handler = self.event_handler.clone();
         let config = self.config.clone();
+        let read_send_tx = self.send_tx.clone();
 
         // Spawn write task
         let write_state = Arc::clone(&state);
@@ -357,7 +476,8 @@ impl AuthenticatedSignalingClient {
             while let Some(result) = read.next().await {
                 match result {
                     Ok(Message::Text(text)) => {
-                        Self::handle_message(&state, &event_handler, &config, &text).await;
+                        Self::handle_message(&state, &event_handler, &config, &read_send_tx, &text)
+                            .await;
                     }
                     Ok(Message::Close(_)) => {
                         let mut s = state.write().await;
@@ -392,6 +512,7 @@ impl AuthenticatedSignalingClient {
         state: &Arc<RwLock<ClientState>>,
         event_handler: &Option<Arc<dyn SignalingEventHandler>>,
         config: &SignalingConfig,
+        send_tx: &Option<mpsc::Sender<Message>>,
         text: &str,
     ) {
         let msg: WSMessage = match serde_json::from_str(text) {
@@ -421,6 +542,9 @@ impl AuthenticatedSignalingClient {
             }
             msg_types::AUTH_ERROR => {
                 if let Ok(payload) = serde_json::from_value::<AuthErrorPayload>(msg.payload) {
+                    if Self::try_refresh_and_retry_auth(state, config, send_tx).await {
+                        return;
+                    }
                     if let Some(ref handler) = event_handler {
                         handler.on_auth_error(payload).await;
                     }
@@ -428,12 +552,21 @@ impl AuthenticatedSignalingClient {
             }
             msg_types::APP_REGISTERED => {
                 if let Ok(payload) = serde_json::from_value::<AppRegisteredPayload>(msg.payload) {
-                    {
+                    let old_app_id = {
                         let mut s = state.write().await;
+                        let old_app_id = if s.app_id.is_empty() { None } else { Some(s.app_id.clone()) };
                         s.app_id = payload.app_id.clone();
-                    }
+                        old_app_id
+                    };
+
                     if let Some(ref handler) = event_handler {
-                        handler.on_app_registered(payload).await;
+                        handler.on_app_registered(payload.clone()).await;
+
+                        if let Some(old_app_id) = old_app_id {
+                            if old_app_id != payload.app_id {
+                                handler.on_app_id_changed(Some(old_app_id), payload.app_id).await;
+                            }
+                        }
                     }
                 }
             }
@@ -471,6 +604,109 @@ impl AuthenticatedSignalingClient {
         }
     }
 
+    /// Try to refresh the API key and re-send auth after the server rejects
+    /// it, once per connection. Returns `true` if a retry was sent (the
+    /// caller should skip its normal `on_auth_error` handling), `false` if
+    /// no refresh was attempted (no refresh context, already retried, or the
+    /// refresh itself failed).
+    async fn try_refresh_and_retry_auth(
+        state: &Arc<RwLock<ClientState>>,
+        config: &SignalingConfig,
+        send_tx: &Option<mpsc::Sender<Message>>,
+    ) -> bool {
+        let Some(ref refresh) = config.refresh else {
+            return false;
+        };
+
+        {
+            let mut s = state.write().await;
+            if s.auth_retried {
+                return false;
+            }
+            s.auth_retried = true;
+        }
+
+        let credentials = P2PCredentials::with_refresh_token(
+            config.api_key.clone(),
+            String::new(),
+            refresh.refresh_token.clone(),
+        );
+
+        let refreshed = match auth::refresh_if_needed(&credentials, &refresh.auth_server_url).await {
+            Ok(refreshed) => refreshed,
+            Err(e) => {
+                tracing::warn!("API key refresh after auth_error failed: {}", e);
+                return false;
+            }
+        };
+
+        if let Some(ref path) = refresh.credentials_path {
+            if let Err(e) = refreshed.save(path) {
+                tracing::warn!("Failed to save refreshed credentials to {:?}: {}", path, e);
+            }
+        }
+
+        let Some(ref tx) = send_tx else {
+            return false;
+        };
+
+        let payload = AuthPayload {
+            api_key: refreshed.api_key,
+        };
+        let msg = WSMessage {
+            msg_type: msg_types::AUTH.to_string(),
+            payload: serde_json::to_value(payload).unwrap(),
+            request_id: None,
+        };
+        let json = match serde_json::to_string(&msg) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize retried auth message: {}", e);
+                return false;
+            }
+        };
+
+        tx.send(Message::Text(json.into())).await.is_ok()
+    }
+
+    /// Refresh `self.config.api_key` in place if it's expired or about to
+    /// expire, so [`connect`](Self::connect) doesn't have to wait for the
+    /// server to reject it first. Errors are logged and swallowed -
+    /// connecting with a stale key still gives the `auth_error` retry path
+    /// a chance to recover.
+    async fn refresh_api_key_if_expiring(&mut self) {
+        let Some(ref refresh) = self.config.refresh else {
+            return;
+        };
+
+        let credentials = P2PCredentials {
+            version: CREDENTIALS_VERSION,
+            api_key: self.config.api_key.clone(),
+            app_id: String::new(),
+            refresh_token: Some(refresh.refresh_token.clone()),
+            expires_at: self.config.expires_at,
+        };
+
+        if !credentials.needs_refresh(Duration::from_secs(60)) {
+            return;
+        }
+
+        match auth::refresh_if_needed(&credentials, &refresh.auth_server_url).await {
+            Ok(refreshed) => {
+                if let Some(ref path) = refresh.credentials_path {
+                    if let Err(e) = refreshed.save(path) {
+                        tracing::warn!("Failed to save refreshed credentials to {:?}: {}", path, e);
+                    }
+                }
+                self.config.api_key = refreshed.api_key;
+                self.config.expires_at = refreshed.expires_at;
+            }
+            Err(e) => {
+                tracing::warn!("Proactive API key refresh failed: {}", e);
+            }
+        }
+    }
+
     /// Send auth message
     async fn send_auth(&self) -> Result<(), P2PError> {
         let payload = AuthPayload {
@@ -480,6 +716,29 @@ impl AuthenticatedSignalingClient {
             .await
     }
 
+    /// Connect and wait for the server to accept or reject our credentials,
+    /// without registering the app or waiting for peers. Used by
+    /// `gateway --p2p-verify` so provisioning scripts can confirm a saved
+    /// credentials file actually authenticates before walking away.
+    ///
+    /// Replaces the client's event handler for the duration of the call.
+    pub async fn verify(&mut self, timeout: Duration) -> Result<AuthOKPayload, P2PError> {
+        let (tx, rx) = oneshot::channel();
+        self.set_event_handler(Arc::new(VerifyHandler {
+            tx: Mutex::new(Some(tx)),
+        }));
+
+        self.connect().await?;
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(P2PError::Signaling(
+                "Verification handler dropped before reporting a result".to_string(),
+            )),
+            Err(_) => Err(P2PError::Timeout),
+        }
+    }
+
     /// Register app with name and capabilities
     pub async fn register_app(&self) -> Result<(), P2PError> {
         let payload = AppRegisterPayload {
@@ -494,6 +753,26 @@ impl AuthenticatedSignalingClient {
         .await
     }
 
+    /// Send a WebRTC offer SDP, optionally targeting a specific peer app by
+    /// ID. Used when this gateway initiates the connection instead of
+    /// answering an incoming one - either [`crate::p2p::P2PManager::connect_to_peer`]
+    /// dialing out to a known app, or [`crate::p2p::runtime::P2PRuntime`]
+    /// re-offering a recreated peer, in which case `request_id` should be
+    /// the original offer's request ID so the signaling server routes this
+    /// new offer back to the same browser session.
+    pub async fn send_offer(&self, sdp: &str, target_app_id: Option<&str>, request_id: Option<&str>) -> Result<(), P2PError> {
+        let payload = OfferPayload {
+            sdp: sdp.to_string(),
+            target_app_id: target_app_id.map(|s| s.to_string()),
+        };
+        self.send_message(
+            msg_types::OFFER,
+            serde_json::to_value(payload).unwrap(),
+            request_id.map(|s| s.to_string()),
+        )
+        .await
+    }
+
     /// Send WebRTC answer SDP
     pub async fn send_answer(&self, sdp: &str, request_id: Option<&str>) -> Result<(), P2PError> {
         let payload = AnswerPayload {
@@ -508,6 +787,34 @@ impl AuthenticatedSignalingClient {
         .await
     }
 
+    /// Send an `error` message back through the signaling server, e.g. to
+    /// reject an offer the gateway can't accept (see
+    /// [`crate::p2p::runtime::P2PRuntime::on_offer`]'s `max_peers` check).
+    pub async fn send_error(&self, message: &str, request_id: Option<&str>) -> Result<(), P2PError> {
+        let payload = ErrorPayload {
+            message: message.to_string(),
+        };
+        self.send_message(
+            msg_types::ERROR,
+            serde_json::to_value(payload).unwrap(),
+            request_id.map(|s| s.to_string()),
+        )
+        .await
+    }
+
+    /// Send an `app_status` heartbeat so the signaling server/dashboard can
+    /// distinguish a healthy gateway from a zombie one. Callers (see
+    /// [`crate::p2p::runtime::P2PRuntime`]) send this on a fixed interval
+    /// for as long as the connection is up; there's no response to wait for.
+    pub async fn send_app_status(&self, status: AppStatusPayload) -> Result<(), P2PError> {
+        self.send_message(
+            msg_types::APP_STATUS,
+            serde_json::to_value(status).unwrap(),
+            None,
+        )
+        .await
+    }
+
     /// Send ICE candidate
     pub async fn send_ice(&self, candidate: serde_json::Value) -> Result<(), P2PError> {
         let payload = ICEPayload {
@@ -700,35 +1007,88 @@ impl SignalingClient {
         }
     }
 
-    /// Connect to the signaling server
+    /// Connect to the signaling server and register `peer_id`.
+    ///
+    /// Unlike [`AuthenticatedSignalingClient`], there's no API key handshake
+    /// here - messages are [`SignalingMessage`] values serialized directly
+    /// as JSON text frames (via its `#[serde(tag = "type")]` encoding), and
+    /// the very first frame sent is `Register { peer_id }` so the server
+    /// can associate this connection with an ID before any offers arrive.
     pub async fn connect(&mut self, peer_id: &str) -> Result<(), P2PError> {
         if self.url.is_empty() {
             return Err(P2PError::Signaling("Signaling URL not configured".to_string()));
         }
 
+        let (ws_stream, _) = connect_async(self.url.as_str())
+            .await
+            .map_err(|e| P2PError::Signaling(format!("WebSocket connection failed: {}", e)))?;
+
+        let (mut write, mut read) = ws_stream.split();
+
         let (send_tx, mut send_rx) = mpsc::channel::<SignalingMessage>(100);
         let (recv_tx, recv_rx) = mpsc::channel::<SignalingMessage>(100);
 
-        self.send_tx = Some(send_tx);
+        self.send_tx = Some(send_tx.clone());
         *self.recv_rx.write().await = Some(recv_rx);
         *self.connected.write().await = true;
 
-        let url = self.url.clone();
-        let peer_id = peer_id.to_string();
+        tracing::info!("Signaling client connected to {}", self.url);
 
-        tokio::spawn(async move {
-            tracing::info!("Signaling client connected to {}", url);
-
-            let _ = recv_tx
-                .send(SignalingMessage::Register {
-                    peer_id: peer_id.clone(),
-                })
-                .await;
+        send_tx
+            .send(SignalingMessage::Register {
+                peer_id: peer_id.to_string(),
+            })
+            .await
+            .map_err(|e| P2PError::Signaling(format!("Failed to queue registration: {}", e)))?;
 
+        // Spawn write task: serialize outgoing SignalingMessages to JSON text frames
+        let connected_write = self.connected.clone();
+        tokio::spawn(async move {
             while let Some(msg) = send_rx.recv().await {
+                let text = match serde_json::to_string(&msg) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        tracing::error!("Failed to serialize signaling message: {}", e);
+                        continue;
+                    }
+                };
+
                 tracing::debug!("Signaling: sending {:?}", msg);
+                if write.send(Message::Text(text)).await.is_err() {
+                    *connected_write.write().await = false;
+                    break;
+                }
+            }
+        });
+
+        // Spawn read task: deserialize incoming JSON text frames into SignalingMessages
+        let connected_read = self.connected.clone();
+        tokio::spawn(async move {
+            while let Some(result) = read.next().await {
+                match result {
+                    Ok(Message::Text(text)) => match serde_json::from_str::<SignalingMessage>(&text) {
+                        Ok(msg) => {
+                            if recv_tx.send(msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to parse signaling message {:?}: {}", text, e);
+                        }
+                    },
+                    Ok(Message::Close(_)) => {
+                        tracing::info!("Signaling server closed the connection");
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::error!("Signaling WebSocket error: {}", e);
+                        break;
+                    }
+                    _ => {}
+                }
             }
 
+            *connected_read.write().await = false;
             tracing::info!("Signaling client disconnected");
         });
 
@@ -3,8 +3,24 @@
 //! Routes requests to internal services via InProcess calls using tower::ServiceExt.
 //! This enables direct function calls without network overhead.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use anyhow::Result;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use prost::Message;
+use thiserror::Error;
 use timecard_service::TimecardService;
+use tokio::sync::Mutex;
+use tonic::body::BoxBody;
+use tonic::Status;
+use tower::Service;
+
+/// Names under which `ServiceRouter` registers its backing services, as
+/// returned by [`ServiceRouter::registered_services`] and used as the keys
+/// in [`ServiceRouter::health_all`].
+const REGISTERED_SERVICES: &[&str] = &["timecard"];
 
 /// Timecard data for gateway communication
 pub struct TimecardData {
@@ -51,6 +67,21 @@ impl ServiceRouter {
             .await?;
         Ok(())
     }
+
+    /// Names of the backing services wired into this router, for operators
+    /// enumerating what's available without reading the source.
+    pub fn registered_services(&self) -> Vec<&'static str> {
+        REGISTERED_SERVICES.to_vec()
+    }
+
+    /// Health-check every registered backing service via its InProcess
+    /// call path, keyed by the same name [`ServiceRouter::registered_services`]
+    /// reports it under.
+    pub async fn health_all(&self) -> HashMap<String, bool> {
+        let mut health = HashMap::with_capacity(REGISTERED_SERVICES.len());
+        health.insert("timecard".to_string(), self.timecard_service.health().await);
+        health
+    }
 }
 
 impl Default for ServiceRouter {
@@ -58,3 +89,179 @@ impl Default for ServiceRouter {
         Self::new()
     }
 }
+
+/// Errors from an [`InProcessClient`] call.
+#[derive(Error, Debug)]
+pub enum InProcessError {
+    #[error("failed to build request body: {0}")]
+    Encode(#[from] prost::EncodeError),
+
+    #[error("failed to decode response body: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    #[error("failed to read response body: {0}")]
+    Body(String),
+
+    #[error("service returned grpc-status {0}: {1}")]
+    Status(i32, String),
+
+    #[error("service call failed: {0}")]
+    Service(String),
+}
+
+/// Typed, in-process gRPC client over a `tower::Service` - e.g. a
+/// tonic-generated server type such as `TimecardServiceServer`.
+///
+/// Mirrors [`crate::p2p::grpc_handler::TonicServiceBridge`]'s `http::Request<BoxBody>`
+/// construction (gRPC framing, `content-type`/`te` headers, `http://localhost{path}`
+/// URI), but encodes/decodes a single prost message instead of passing raw bytes
+/// through, so callers get typed responses back without going over the network.
+pub struct InProcessClient<S> {
+    service: Arc<Mutex<S>>,
+}
+
+impl<S> InProcessClient<S>
+where
+    S: Service<http::Request<BoxBody>, Response = http::Response<BoxBody>> + Send + 'static,
+    S::Future: Send,
+    S::Error: std::fmt::Debug,
+{
+    pub fn new(service: S) -> Self {
+        Self {
+            service: Arc::new(Mutex::new(service)),
+        }
+    }
+
+    /// Call a unary method at `path` (e.g. `/timecard.TimecardService/GetEntry`)
+    /// with a typed request, returning the decoded typed response.
+    pub async fn call_unary<Req, Resp>(&self, path: &str, req: &Req) -> Result<Resp, InProcessError>
+    where
+        Req: Message,
+        Resp: Message + Default,
+    {
+        let mut payload = Vec::with_capacity(req.encoded_len());
+        req.encode(&mut payload)?;
+
+        let mut grpc_body = Vec::with_capacity(5 + payload.len());
+        grpc_body.push(0x00);
+        grpc_body.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        grpc_body.extend_from_slice(&payload);
+
+        let uri = format!("http://localhost{}", path);
+        let body = BoxBody::new(
+            Full::new(Bytes::from(grpc_body))
+                .map_err(|_: std::convert::Infallible| Status::internal("body error")),
+        );
+        let http_req = http::Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("content-type", "application/grpc")
+            .header("te", "trailers")
+            .body(body)
+            .unwrap();
+
+        let mut service = self.service.lock().await;
+        let response = service
+            .call(http_req)
+            .await
+            .map_err(|e| InProcessError::Service(format!("{:?}", e)))?;
+
+        let (parts, body) = response.into_parts();
+        let grpc_status = parts
+            .headers
+            .get("grpc-status")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i32>().ok())
+            .unwrap_or(0);
+        if grpc_status != 0 {
+            let message = parts
+                .headers
+                .get("grpc-message")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string();
+            return Err(InProcessError::Status(grpc_status, message));
+        }
+
+        let body_bytes = body
+            .collect()
+            .await
+            .map_err(|e| InProcessError::Body(format!("{:?}", e)))?
+            .to_bytes();
+
+        // Skip the 5-byte gRPC data frame header (flags + big-endian length)
+        // to get to the encoded message itself.
+        let message_bytes = body_bytes.get(5..).unwrap_or(&[]);
+        Ok(Resp::decode(message_bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_services_lists_timecard() {
+        let router = ServiceRouter::new();
+        assert_eq!(router.registered_services(), vec!["timecard"]);
+    }
+
+    #[tokio::test]
+    async fn test_in_process_client_calls_timecard_service_without_network() {
+        use crate::grpc::timecard_server::timecard_service_server::TimecardServiceServer;
+        use proto::timecard::{ClockInRequest, ClockInResponse};
+
+        let server = TimecardServiceServer::new(timecard_service::TimecardGrpcService::new());
+        let client = InProcessClient::new(server);
+
+        let request = ClockInRequest {
+            employee_id: "E001".to_string(),
+            date: "2026-01-10".to_string(),
+            time: "09:00".to_string(),
+        };
+        let response: ClockInResponse = client
+            .call_unary("/timecard.TimecardService/ClockIn", &request)
+            .await
+            .expect("ClockIn should succeed");
+
+        let entry = response.entry.expect("response should include the entry");
+        assert_eq!(entry.employee_id, "E001");
+        assert_eq!(entry.clock_in, "09:00");
+    }
+
+    #[tokio::test]
+    async fn test_in_process_client_surfaces_grpc_status_as_error() {
+        use crate::grpc::timecard_server::timecard_service_server::TimecardServiceServer;
+        use proto::timecard::ClockOutRequest;
+
+        let server = TimecardServiceServer::new(timecard_service::TimecardGrpcService::new());
+        let client = InProcessClient::new(server);
+
+        // No open clock-in entry exists for this employee, so the service
+        // should return a non-OK grpc-status that surfaces as an error.
+        let request = ClockOutRequest {
+            employee_id: "no-such-employee".to_string(),
+            date: "2026-01-10".to_string(),
+            time: "18:00".to_string(),
+        };
+        let err = client
+            .call_unary::<_, proto::timecard::ClockOutResponse>(
+                "/timecard.TimecardService/ClockOut",
+                &request,
+            )
+            .await
+            .expect_err("ClockOut without a clock-in should fail");
+        assert!(matches!(err, InProcessError::Status(_, _)));
+    }
+
+    #[tokio::test]
+    async fn test_health_all_reports_every_registered_service() {
+        let router = ServiceRouter::new();
+        let health = router.health_all().await;
+
+        for name in router.registered_services() {
+            assert_eq!(health.get(name), Some(&true));
+        }
+        assert_eq!(health.len(), router.registered_services().len());
+    }
+}
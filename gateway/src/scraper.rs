@@ -1,9 +1,69 @@
 //! ScraperService trait and related types for integration with scraper-service.
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
+/// Machine-readable classification of a scrape failure, independent of the
+/// specific error message. Carried on [`crate::job::AccountResult`] so gRPC
+/// responses and job summaries can report a stable error code instead of
+/// only a free-text message, and so callers can tell e.g. "login failed"
+/// (don't retry) apart from "site down" (retry).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScraperErrorKind {
+    /// Credentials were rejected
+    LoginFailed,
+    /// The operation took too long
+    Timeout,
+    /// The target site could not be reached (browser/network/navigation)
+    SiteUnavailable,
+    /// A page or downloaded file didn't have the expected shape
+    ParseFailed,
+    /// A local file I/O error
+    Io,
+    /// No more specific classification applies
+    Unknown,
+}
+
+impl ScraperErrorKind {
+    /// Whether retrying a scrape that failed with this kind of error could
+    /// plausibly succeed. Credential and parse failures are deterministic
+    /// given the same input, so retrying them only wastes time.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, Self::LoginFailed | Self::ParseFailed)
+    }
+
+    /// Classify an opaque scrape error message into a [`ScraperErrorKind`].
+    ///
+    /// `scraper-service` (an external crate) only exposes its error via
+    /// `Display`, so callers working with that error can't match on a typed
+    /// variant. This matches on keywords instead, as a best-effort
+    /// classification for retry and reporting purposes.
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        if ["login", "credential", "password", "unauthorized", "auth"]
+            .iter()
+            .any(|kw| lower.contains(kw))
+        {
+            Self::LoginFailed
+        } else if lower.contains("timeout") || lower.contains("timed out") {
+            Self::Timeout
+        } else if ["navigation", "connect", "network", "unreachable", "dns"]
+            .iter()
+            .any(|kw| lower.contains(kw))
+        {
+            Self::SiteUnavailable
+        } else if lower.contains("parse") || lower.contains("unexpected") {
+            Self::ParseFailed
+        } else if lower.contains("io error") || lower.contains("file") {
+            Self::Io
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
 /// Errors that can occur during scraping operations
 #[derive(Error, Debug)]
 pub enum ScraperError {
@@ -29,6 +89,21 @@ pub enum ScraperError {
     Internal(String),
 }
 
+impl ScraperError {
+    /// Machine-readable classification of this error, for reporting
+    /// alongside the human-readable message.
+    pub fn kind(&self) -> ScraperErrorKind {
+        match self {
+            Self::Login(_) => ScraperErrorKind::LoginFailed,
+            Self::Timeout(_) => ScraperErrorKind::Timeout,
+            Self::BrowserInit(_) | Self::Navigation(_) => ScraperErrorKind::SiteUnavailable,
+            Self::Download(_) => ScraperErrorKind::ParseFailed,
+            Self::FileIO(_) => ScraperErrorKind::Io,
+            Self::Internal(_) => ScraperErrorKind::Unknown,
+        }
+    }
+}
+
 /// Configuration for a scrape operation
 #[derive(Debug, Clone)]
 pub struct ScrapeConfig {
@@ -129,4 +204,37 @@ mod tests {
         // Cleanup
         let _ = std::fs::remove_file(&result.csv_path);
     }
+
+    #[test]
+    fn test_classify_login_failure_is_not_retryable() {
+        let kind = ScraperErrorKind::classify("Login error: invalid password");
+        assert_eq!(kind, ScraperErrorKind::LoginFailed);
+        assert!(!kind.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_timeout_is_retryable() {
+        let kind = ScraperErrorKind::classify("request timed out after 30s");
+        assert_eq!(kind, ScraperErrorKind::Timeout);
+        assert!(kind.is_retryable());
+    }
+
+    #[test]
+    fn test_classify_unknown_defaults_unknown_and_retryable() {
+        let kind = ScraperErrorKind::classify("something went sideways");
+        assert_eq!(kind, ScraperErrorKind::Unknown);
+        assert!(kind.is_retryable());
+    }
+
+    #[test]
+    fn test_scraper_error_kind_mapping() {
+        assert_eq!(
+            ScraperError::Login("bad creds".to_string()).kind(),
+            ScraperErrorKind::LoginFailed
+        );
+        assert_eq!(
+            ScraperError::Timeout("slow".to_string()).kind(),
+            ScraperErrorKind::Timeout
+        );
+    }
 }
@@ -0,0 +1,100 @@
+//! Unifies the ways this process can be asked to stop.
+//!
+//! `run_server`, `run_p2p_client`, and `run_p2p_service` in `main.rs` each
+//! used to wait on shutdown differently: some raced Ctrl+C, some awaited a
+//! `oneshot::Receiver` from the Windows service control handler, and only
+//! one did both with a fallback between them. [`Shutdown`] collapses all of
+//! that into one `recv().await` that every entry point calls the same way,
+//! so a future mode can't forget to wire one of the sources up, and the
+//! logic is unit-testable without a real Ctrl+C or service control event.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+/// A single shutdown signal, satisfied by whichever of Ctrl+C or its paired
+/// [`ShutdownTrigger`] fires first.
+pub struct Shutdown {
+    rx: oneshot::Receiver<()>,
+}
+
+/// Handle used to fire a [`Shutdown`] programmatically - from a Windows
+/// service control callback, or directly in a test. Cheap to clone; every
+/// clone shares the same underlying sender, so whichever one fires first
+/// wins and the rest are no-ops.
+#[derive(Clone)]
+pub struct ShutdownTrigger {
+    tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+}
+
+impl Shutdown {
+    /// Build a [`Shutdown`]/[`ShutdownTrigger`] pair. Pass the `Shutdown`
+    /// half to a `run_*` entry point and keep the `ShutdownTrigger` half to
+    /// fire it from elsewhere (a service control handler, a test, or just
+    /// drop it if the only shutdown source you want is Ctrl+C).
+    pub fn new() -> (Self, ShutdownTrigger) {
+        let (tx, rx) = oneshot::channel();
+        (
+            Shutdown { rx },
+            ShutdownTrigger {
+                tx: Arc::new(Mutex::new(Some(tx))),
+            },
+        )
+    }
+
+    /// Wait until Ctrl+C is pressed or the paired [`ShutdownTrigger`] fires
+    /// (including by being dropped without firing, e.g. if the task holding
+    /// it panics). Consumes `self`: a `Shutdown` is only good for one wait.
+    pub async fn recv(self) {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                if let Err(e) = result {
+                    tracing::warn!("Failed to listen for Ctrl+C: {}", e);
+                } else {
+                    tracing::info!("Ctrl+C received");
+                }
+            }
+            _ = self.rx => {
+                tracing::info!("Shutdown signal received");
+            }
+        }
+    }
+}
+
+impl ShutdownTrigger {
+    /// Fire the paired [`Shutdown`]. Idempotent: only the first call across
+    /// all clones of this trigger has any effect.
+    pub fn trigger(&self) {
+        if let Some(tx) = self.tx.lock().unwrap().take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_trigger_completes_recv() {
+        let (shutdown, trigger) = Shutdown::new();
+        trigger.trigger();
+        shutdown.recv().await;
+    }
+
+    #[tokio::test]
+    async fn test_dropping_trigger_completes_recv() {
+        let (shutdown, trigger) = Shutdown::new();
+        drop(trigger);
+        shutdown.recv().await;
+    }
+
+    #[tokio::test]
+    async fn test_trigger_is_idempotent_across_clones() {
+        let (shutdown, trigger) = Shutdown::new();
+        let other = trigger.clone();
+        trigger.trigger();
+        other.trigger();
+        shutdown.recv().await;
+    }
+}
@@ -3,13 +3,29 @@
 use super::{UpdateError, VersionInfo};
 use sha2::{Digest, Sha256};
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Prefix for per-run staging directories created under `temp_dir`, used by
+/// [`UpdateDownloader::cleanup_stale`] to recognize which subdirectories are
+/// safe to sweep.
+const STAGING_DIR_PREFIX: &str = "update-";
+
+/// Default age after which an abandoned staging directory (left behind by a
+/// crashed or interrupted update) is considered safe to delete.
+pub const DEFAULT_STALE_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Downloads updates from a remote server
 pub struct UpdateDownloader {
     download_base_url: String,
     temp_dir: PathBuf,
     client: reqwest::Client,
+    /// GitHub API token, sent as `Authorization: Bearer` when downloading
+    /// release assets. A private repository's asset URLs require this even
+    /// though the release metadata itself may already have been fetched
+    /// successfully with the same token.
+    github_token: Option<String>,
 }
 
 impl UpdateDownloader {
@@ -19,13 +35,84 @@ impl UpdateDownloader {
             download_base_url,
             temp_dir,
             client: reqwest::Client::new(),
+            github_token: None,
         }
     }
 
-    /// Download an update and return the path to the downloaded file
+    /// Set the GitHub API token to authenticate asset downloads with,
+    /// required to download release assets from a private repository.
+    pub fn with_github_token(mut self, token: Option<String>) -> Self {
+        self.github_token = token;
+        self
+    }
+
+    /// Build the GET request for an asset download, applying the configured
+    /// GitHub token (if any) as an `Authorization: Bearer` header. Private
+    /// repositories' `browser_download_url`s aren't publicly reachable, so
+    /// this is required, not just a rate-limit nicety.
+    fn build_download_request(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client
+            .get(url)
+            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")));
+
+        match &self.github_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Remove stale per-run staging directories left behind by crashed or
+    /// interrupted updates, so abandoned downloads don't accumulate
+    /// indefinitely on field machines. Returns the number of directories
+    /// removed.
+    pub async fn cleanup_stale(&self, max_age: Duration) -> Result<usize, UpdateError> {
+        let mut entries = match tokio::fs::read_dir(&self.temp_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut removed = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let Ok(file_name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if !file_name.starts_with(STAGING_DIR_PREFIX) {
+                continue;
+            }
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+
+            let modified = entry.metadata().await?.modified()?;
+            let age = modified.elapsed().unwrap_or_default();
+            if age < max_age {
+                continue;
+            }
+
+            tokio::fs::remove_dir_all(entry.path()).await?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Create a fresh, uniquely-named staging directory under `temp_dir`
+    /// for a single download/install run, so concurrent or repeated update
+    /// attempts never clobber each other's files.
+    async fn new_staging_dir(&self) -> Result<PathBuf, UpdateError> {
+        let staging_dir = self.temp_dir.join(format!("{}{}", STAGING_DIR_PREFIX, Uuid::new_v4()));
+        tokio::fs::create_dir_all(&staging_dir).await?;
+        Ok(staging_dir)
+    }
+
+    /// Download an update and return the path to the downloaded file. The
+    /// file is placed in its own per-run staging directory (see
+    /// [`Self::new_staging_dir`]), which the caller should remove with
+    /// `remove_dir_all` on the returned path's parent once the update has
+    /// been installed.
     pub async fn download(&self, version_info: &VersionInfo) -> Result<PathBuf, UpdateError> {
-        // Create temp directory if it doesn't exist
-        tokio::fs::create_dir_all(&self.temp_dir).await?;
+        let staging_dir = self.new_staging_dir().await?;
 
         // Determine download URL
         let download_url = if version_info.download_url.starts_with("http") {
@@ -37,9 +124,7 @@ impl UpdateDownloader {
         tracing::debug!("Downloading update from: {}", download_url);
 
         // Download the file
-        let response = self.client
-            .get(&download_url)
-            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+        let response = self.build_download_request(&download_url)
             .send()
             .await?;
 
@@ -51,7 +136,7 @@ impl UpdateDownloader {
 
         // Determine filename
         let filename = self.extract_filename(&download_url, &version_info.version);
-        let download_path = self.temp_dir.join(&filename);
+        let download_path = staging_dir.join(&filename);
 
         // Write to file
         let bytes = response.bytes().await?;
@@ -100,3 +185,87 @@ impl UpdateDownloader {
         hex::encode(hash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_asset_download_includes_auth_header() {
+        let downloader = UpdateDownloader::new(String::new(), PathBuf::new())
+            .with_github_token(Some("secret-token".to_string()));
+
+        let request = downloader
+            .build_download_request("https://api.github.com/repos/owner/repo/releases/assets/1")
+            .build()
+            .unwrap();
+
+        let auth = request.headers().get("authorization").unwrap().to_str().unwrap();
+        assert_eq!(auth, "Bearer secret-token");
+    }
+
+    #[test]
+    fn test_public_asset_download_omits_auth_header() {
+        let downloader = UpdateDownloader::new(String::new(), PathBuf::new());
+
+        let request = downloader
+            .build_download_request("https://github.com/owner/repo/releases/download/v1.0.0/gateway")
+            .build()
+            .unwrap();
+
+        assert!(request.headers().get("authorization").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_new_staging_dir_creates_unique_directories() {
+        let temp_dir = std::env::temp_dir().join(format!("gateway-downloader-test-{}", Uuid::new_v4()));
+        let downloader = UpdateDownloader::new(String::new(), temp_dir.clone());
+
+        let first = downloader.new_staging_dir().await.unwrap();
+        let second = downloader.new_staging_dir().await.unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.is_dir());
+        assert!(second.is_dir());
+
+        let _ = tokio::fs::remove_dir_all(&temp_dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_stale_removes_only_old_staging_directories() {
+        let temp_dir = std::env::temp_dir().join(format!("gateway-downloader-test-{}", Uuid::new_v4()));
+        let downloader = UpdateDownloader::new(String::new(), temp_dir.clone());
+
+        let stale_dir = downloader.new_staging_dir().await.unwrap();
+        let fresh_dir = downloader.new_staging_dir().await.unwrap();
+
+        // Back-date the "stale" directory's mtime so it looks abandoned.
+        let old_time = std::time::SystemTime::now() - Duration::from_secs(48 * 60 * 60);
+        filetime_set_mtime(&stale_dir, old_time);
+
+        let removed = downloader.cleanup_stale(DEFAULT_STALE_AGE).await.unwrap();
+
+        assert_eq!(removed, 1);
+        assert!(!stale_dir.exists());
+        assert!(fresh_dir.exists());
+
+        let _ = tokio::fs::remove_dir_all(&temp_dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_stale_on_missing_temp_dir_is_a_no_op() {
+        let temp_dir = std::env::temp_dir().join(format!("gateway-downloader-missing-{}", Uuid::new_v4()));
+        let downloader = UpdateDownloader::new(String::new(), temp_dir);
+
+        let removed = downloader.cleanup_stale(DEFAULT_STALE_AGE).await.unwrap();
+
+        assert_eq!(removed, 0);
+    }
+
+    /// Set a directory's modification time without pulling in a `filetime`
+    /// dependency, by calling `set_modified` on an opened handle to it.
+    fn filetime_set_mtime(path: &std::path::Path, time: std::time::SystemTime) {
+        let file = std::fs::File::open(path).unwrap();
+        file.set_modified(time).unwrap();
+    }
+}
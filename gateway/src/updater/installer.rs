@@ -2,6 +2,11 @@
 
 use super::UpdateError;
 use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// Minimum plausible size for a real gateway binary. Catches zero-byte or
+/// severely truncated downloads outright, ahead of the magic-byte check.
+const MIN_BINARY_SIZE: u64 = 4096;
 
 /// Service status check result
 #[derive(Debug, Clone, PartialEq)]
@@ -109,6 +114,27 @@ pub fn check_service_ready_for_install() -> Result<(), String> {
     }
 }
 
+/// True if `header` (a file's first 4 bytes) matches a known executable
+/// magic number: ELF, PE (the `MZ` DOS stub every PE file starts with), or
+/// Mach-O (32/64-bit, either byte order, plus the universal/fat binary
+/// magic).
+fn is_known_executable_magic(header: &[u8; 4]) -> bool {
+    const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+    const MACHO_MAGIC_32: [u8; 4] = [0xfe, 0xed, 0xfa, 0xce];
+    const MACHO_MAGIC_64: [u8; 4] = [0xfe, 0xed, 0xfa, 0xcf];
+    const MACHO_CIGAM_32: [u8; 4] = [0xce, 0xfa, 0xed, 0xfe];
+    const MACHO_CIGAM_64: [u8; 4] = [0xcf, 0xfa, 0xed, 0xfe];
+    const MACHO_FAT_MAGIC: [u8; 4] = [0xca, 0xfe, 0xba, 0xbe];
+
+    *header == ELF_MAGIC
+        || header[0..2] == [b'M', b'Z']
+        || *header == MACHO_MAGIC_32
+        || *header == MACHO_MAGIC_64
+        || *header == MACHO_CIGAM_32
+        || *header == MACHO_CIGAM_64
+        || *header == MACHO_FAT_MAGIC
+}
+
 /// Installs downloaded updates
 pub struct UpdateInstaller;
 
@@ -143,6 +169,8 @@ impl UpdateInstaller {
             }
         }
 
+        self.validate_binary(update_path).await?;
+
         let current_exe = std::env::current_exe()
             .map_err(|e| UpdateError::Install(format!("Failed to get current exe path: {}", e)))?;
 
@@ -165,6 +193,39 @@ impl UpdateInstaller {
         Ok(())
     }
 
+    /// Sanity-check a downloaded binary before letting it replace the
+    /// current executable: reject anything too small to be real, or that
+    /// doesn't start with a known ELF/PE/Mach-O magic number. Catches a
+    /// truncated download or an HTML/JSON error page served over a flaky
+    /// network, which would otherwise brick the machine mid-update.
+    /// Checksum verification (when the server provides one) already
+    /// happens in `UpdateDownloader::download`.
+    async fn validate_binary(&self, path: &Path) -> Result<(), UpdateError> {
+        let metadata = tokio::fs::metadata(path).await
+            .map_err(|e| UpdateError::Install(format!("Failed to read downloaded file: {}", e)))?;
+
+        if metadata.len() < MIN_BINARY_SIZE {
+            return Err(UpdateError::Install(format!(
+                "Downloaded file is too small to be a real binary ({} bytes)",
+                metadata.len()
+            )));
+        }
+
+        let mut header = [0u8; 4];
+        let mut file = tokio::fs::File::open(path).await
+            .map_err(|e| UpdateError::Install(format!("Failed to open downloaded file: {}", e)))?;
+        file.read_exact(&mut header).await
+            .map_err(|e| UpdateError::Install(format!("Failed to read downloaded file header: {}", e)))?;
+
+        if !is_known_executable_magic(&header) {
+            return Err(UpdateError::Install(
+                "Downloaded file doesn't look like a valid ELF/PE/Mach-O executable".to_string()
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Install an MSI package (Windows only)
     #[cfg(windows)]
     async fn install_msi(&self, msi_path: &Path) -> Result<(), UpdateError> {
@@ -179,6 +240,9 @@ impl UpdateInstaller {
         tracing::info!("Service status before install: {}", status);
 
         let msi_path_str = msi_path.display().to_string();
+        let staging_dir_str = msi_path.parent()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
 
         tracing::info!("Installing MSI package: {}", msi_path_str);
 
@@ -240,9 +304,13 @@ Remove-Item -Path "{msi_path}" -Force -ErrorAction SilentlyContinue
 # Clean up this script
 Remove-Item -Path $MyInvocation.MyCommand.Path -Force -ErrorAction SilentlyContinue
 
+# Clean up the staging directory the MSI and this script lived in
+Remove-Item -Path "{staging_dir}" -Recurse -Force -ErrorAction SilentlyContinue
+
 exit
 "#,
             msi_path = msi_path_str.replace('\\', "\\\\"),
+            staging_dir = staging_dir_str.replace('\\', "\\\\"),
         );
 
         tokio::fs::write(&script_path, &script_content).await
@@ -286,6 +354,9 @@ exit
         let current_exe_str = current_exe.display().to_string();
         let backup_path_str = backup_path.display().to_string();
         let update_path_str = update_path.display().to_string();
+        let staging_dir_str = update_path.parent()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
 
         let script_content = format!(
             r#"@echo off
@@ -336,13 +407,15 @@ if %SERVICE_WAS_RUNNING% == 1 (
 
 echo Update completed successfully.
 
-:: Delete this script
+:: Delete this script, then the staging directory it lived in
 del "%~f0" > nul 2>&1
+rmdir /s /q "{staging_dir}" > nul 2>&1
 exit
 "#,
             current_exe = current_exe_str,
             backup_path = backup_path_str,
             update_path = update_path_str,
+            staging_dir = staging_dir_str,
         );
 
         tokio::fs::write(&script_path, &script_content).await
@@ -386,8 +459,11 @@ exit
         tokio::fs::set_permissions(current_exe, perms).await
             .map_err(|e| UpdateError::Install(format!("Failed to set permissions: {}", e)))?;
 
-        // Clean up downloaded file
+        // Clean up downloaded file and its per-run staging directory
         let _ = tokio::fs::remove_file(update_path).await;
+        if let Some(staging_dir) = update_path.parent() {
+            let _ = tokio::fs::remove_dir_all(staging_dir).await;
+        }
 
         tracing::info!("Update installed. Please restart the application.");
 
@@ -422,3 +498,68 @@ impl Default for UpdateInstaller {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[test]
+    fn test_is_known_executable_magic_accepts_elf_pe_macho() {
+        assert!(is_known_executable_magic(&[0x7f, b'E', b'L', b'F']));
+        assert!(is_known_executable_magic(&[b'M', b'Z', 0x90, 0x00]));
+        assert!(is_known_executable_magic(&[0xfe, 0xed, 0xfa, 0xce]));
+        assert!(is_known_executable_magic(&[0xfe, 0xed, 0xfa, 0xcf]));
+        assert!(is_known_executable_magic(&[0xca, 0xfe, 0xba, 0xbe]));
+    }
+
+    #[test]
+    fn test_is_known_executable_magic_rejects_html_error_page() {
+        assert!(!is_known_executable_magic(b"<htm"));
+        assert!(!is_known_executable_magic(b"{\"er"));
+        assert!(!is_known_executable_magic(&[0u8; 4]));
+    }
+
+    async fn write_temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, uuid::Uuid::new_v4()));
+        let mut file = tokio::fs::File::create(&path).await.unwrap();
+        file.write_all(contents).await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_validate_binary_rejects_truncated_download() {
+        let installer = UpdateInstaller::new();
+        let path = write_temp_file("truncated", b"\x7fELF").await;
+
+        let result = installer.validate_binary(&path).await;
+
+        assert!(result.is_err());
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn test_validate_binary_rejects_html_error_page() {
+        let installer = UpdateInstaller::new();
+        let body = format!("<html><body>{}</body></html>", "x".repeat(MIN_BINARY_SIZE as usize));
+        let path = write_temp_file("error-page", body.as_bytes()).await;
+
+        let result = installer.validate_binary(&path).await;
+
+        assert!(result.is_err());
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn test_validate_binary_accepts_plausible_elf() {
+        let installer = UpdateInstaller::new();
+        let mut contents = vec![0x7f, b'E', b'L', b'F'];
+        contents.resize(MIN_BINARY_SIZE as usize, 0);
+        let path = write_temp_file("valid-elf", &contents).await;
+
+        let result = installer.validate_binary(&path).await;
+
+        assert!(result.is_ok());
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
@@ -30,11 +30,12 @@ mod version;
 mod downloader;
 mod installer;
 
-pub use version::{VersionChecker, VersionInfo, UpdateChannel, GitHubRelease, GitHubAsset};
-pub use downloader::UpdateDownloader;
+pub use version::{VersionChecker, VersionInfo, VersionManifest, UpdateChannel, GitHubRelease, GitHubAsset};
+pub use downloader::{UpdateDownloader, DEFAULT_STALE_AGE};
 pub use installer::{UpdateInstaller, ServiceStatus, check_service_status, check_service_ready_for_install};
 
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Errors that can occur during the update process
@@ -80,6 +81,26 @@ pub struct UpdateConfig {
     /// Current version of the application
     pub current_version: String,
 
+    /// GitHub API token sent as an `Authorization: Bearer` header on every
+    /// GitHub Releases API request and release asset download. Unauthenticated
+    /// requests are capped at 60/hour per source IP; a token raises that to
+    /// 5000/hour, which matters for fleets of gateways that share one egress
+    /// IP. Also required to check releases or download assets from a
+    /// private repository at all.
+    pub github_token: Option<String>,
+
+    /// GitHub REST API base URL, `https://api.github.com` by default. Point
+    /// this at a GitHub Enterprise instance's API base (e.g.
+    /// `https://github.example.com/api/v3`) to check for updates against an
+    /// internal-only repository.
+    pub api_base_url: String,
+
+    /// URL of a static [`VersionManifest`] JSON document listing available
+    /// versions, for air-gapped sites that mirror releases on an internal
+    /// server instead of reaching GitHub at all. Takes priority over both
+    /// the GitHub and legacy `version_check_url` sources when set.
+    pub manifest_url: Option<String>,
+
     // Legacy fields for backwards compatibility
     /// URL to check for updates (returns JSON with version info)
     /// Deprecated: Use github_owner and github_repo instead
@@ -102,6 +123,9 @@ impl Default for UpdateConfig {
             prefer_msi: false,
             temp_dir: std::env::temp_dir().join("gateway-updates"),
             current_version: env!("CARGO_PKG_VERSION").to_string(),
+            github_token: None,
+            api_base_url: version::DEFAULT_GITHUB_API_BASE_URL.to_string(),
+            manifest_url: None,
             version_check_url: String::new(),
             download_base_url: String::new(),
         }
@@ -130,6 +154,27 @@ impl UpdateConfig {
         self
     }
 
+    /// Set the GitHub API token to authenticate update checks with (raises
+    /// the rate limit from 60 to 5000 requests/hour)
+    pub fn with_github_token(mut self, token: Option<String>) -> Self {
+        self.github_token = token;
+        self
+    }
+
+    /// Set the GitHub REST API base URL, for GitHub Enterprise deployments
+    pub fn with_api_base_url(mut self, api_base_url: impl Into<String>) -> Self {
+        self.api_base_url = api_base_url.into();
+        self
+    }
+
+    /// Set the static manifest URL, for offline update distribution to
+    /// sites that can't reach GitHub at all. Takes priority over GitHub and
+    /// legacy `version_check_url` configuration when set.
+    pub fn with_manifest_url(mut self, manifest_url: impl Into<String>) -> Self {
+        self.manifest_url = Some(manifest_url.into());
+        self
+    }
+
     /// Set the temporary directory for downloads
     pub fn with_temp_dir(mut self, temp_dir: PathBuf) -> Self {
         self.temp_dir = temp_dir;
@@ -154,13 +199,17 @@ impl AutoUpdater {
     /// Create a new AutoUpdater with the given configuration
     #[allow(deprecated)]
     pub fn new(config: UpdateConfig) -> Self {
-        let version_checker = if config.is_github_configured() {
+        let version_checker = if let Some(ref manifest_url) = config.manifest_url {
+            VersionChecker::new_manifest(manifest_url.clone())
+        } else if config.is_github_configured() {
             VersionChecker::new_github(
                 config.github_owner.clone(),
                 config.github_repo.clone(),
             )
             .with_channel(config.update_channel.clone())
             .with_prefer_msi(config.prefer_msi)
+            .with_github_token(config.github_token.clone())
+            .with_api_base_url(config.api_base_url.clone())
         } else {
             VersionChecker::new(config.version_check_url.clone())
         };
@@ -168,7 +217,8 @@ impl AutoUpdater {
         let downloader = UpdateDownloader::new(
             config.download_base_url.clone(),
             config.temp_dir.clone(),
-        );
+        )
+        .with_github_token(config.github_token.clone());
         let installer = UpdateInstaller::new();
 
         Self {
@@ -181,6 +231,7 @@ impl AutoUpdater {
 
     /// Check if an update is available
     pub async fn check_for_update(&self) -> Result<Option<VersionInfo>, UpdateError> {
+        metrics::counter!("update_checks_total").increment(1);
         let latest = self.version_checker.get_latest_version().await?;
 
         if self.is_newer_version(&latest.version) {
@@ -200,8 +251,15 @@ impl AutoUpdater {
         self.version_checker.list_releases(include_prerelease).await
     }
 
+    /// True if `release` has an asset matching the current platform
+    pub fn has_platform_asset(&self, release: &GitHubRelease) -> bool {
+        self.version_checker.has_platform_asset(release)
+    }
+
     /// Download and install an update
     pub async fn update(&self) -> Result<(), UpdateError> {
+        self.cleanup_stale_downloads().await;
+
         let version_info = self.check_for_update().await?
             .ok_or(UpdateError::NoUpdate)?;
 
@@ -216,6 +274,8 @@ impl AutoUpdater {
 
     /// Download and install a specific version
     pub async fn update_to_version(&self, version_info: &VersionInfo) -> Result<(), UpdateError> {
+        self.cleanup_stale_downloads().await;
+
         tracing::info!("Downloading version {}", version_info.version);
         let update_path = self.downloader.download(version_info).await?;
 
@@ -225,6 +285,17 @@ impl AutoUpdater {
         Ok(())
     }
 
+    /// Sweep abandoned staging directories left behind by prior failed or
+    /// interrupted update runs. Best-effort: a failure here shouldn't block
+    /// the update that's about to run.
+    async fn cleanup_stale_downloads(&self) {
+        match self.downloader.cleanup_stale(DEFAULT_STALE_AGE).await {
+            Ok(0) => {}
+            Ok(removed) => tracing::info!("Cleaned up {} stale update staging director{}", removed, if removed == 1 { "y" } else { "ies" }),
+            Err(e) => tracing::warn!("Failed to clean up stale update staging directories: {}", e),
+        }
+    }
+
     /// Get version info for a specific tag
     pub async fn get_version_by_tag(&self, tag: &str) -> Result<VersionInfo, UpdateError> {
         self.version_checker.get_version_by_tag(tag).await
@@ -250,30 +321,101 @@ impl AutoUpdater {
 
     /// Compare versions to check if the remote version is newer
     fn is_newer_version(&self, remote_version: &str) -> bool {
-        use std::cmp::Ordering;
+        compare_versions(&self.config.current_version, remote_version) == std::cmp::Ordering::Less
+    }
 
-        let parse_version = |v: &str| -> Vec<u32> {
-            v.trim_start_matches('v')
-                .split('.')
-                .filter_map(|s| s.parse().ok())
-                .collect()
-        };
+    /// True if `tag_version` is older than the currently running version,
+    /// i.e. installing it would be a downgrade. Used by `--update-to` to
+    /// require confirmation before rolling back to a pinned older tag.
+    pub fn is_downgrade(&self, tag_version: &str) -> bool {
+        compare_versions(&self.config.current_version, tag_version) == std::cmp::Ordering::Greater
+    }
+}
+
+/// Default time a [`CachedUpdateCheck`] trusts its cached result before
+/// re-checking GitHub. The `Health` RPC may be polled by monitoring every
+/// few seconds, so without a cache each poll would count against GitHub's
+/// (60/hour unauthenticated) rate limit.
+pub const DEFAULT_UPDATE_CHECK_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Wraps an [`AutoUpdater`] with a time-based cache over
+/// [`AutoUpdater::check_for_update`], so callers that need update
+/// availability on every request (e.g. the `Health` gRPC handler) don't hit
+/// GitHub's Releases API each time. A failed refresh keeps serving the last
+/// known-good result (if any) rather than caching the failure, so a
+/// transient network hiccup doesn't report "up to date" until the TTL
+/// happens to land on a working check.
+pub struct CachedUpdateCheck {
+    updater: AutoUpdater,
+    ttl: Duration,
+    cached: tokio::sync::Mutex<Option<(std::time::Instant, Option<VersionInfo>)>>,
+}
+
+impl CachedUpdateCheck {
+    /// Create a cache with the default TTL ([`DEFAULT_UPDATE_CHECK_TTL`]).
+    pub fn new(updater: AutoUpdater) -> Self {
+        Self::with_ttl(updater, DEFAULT_UPDATE_CHECK_TTL)
+    }
 
-        let current = parse_version(&self.config.current_version);
-        let remote = parse_version(remote_version);
+    /// Create a cache that trusts its last result for `ttl` before
+    /// re-checking.
+    pub fn with_ttl(updater: AutoUpdater, ttl: Duration) -> Self {
+        Self {
+            updater,
+            ttl,
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
 
-        for (c, r) in current.iter().zip(remote.iter()) {
-            match c.cmp(r) {
-                Ordering::Less => return true,
-                Ordering::Greater => return false,
-                Ordering::Equal => continue,
+    /// Return the latest known update-availability result, refreshing it
+    /// from GitHub first if the cache is empty or older than the TTL.
+    /// `Some(info)` means an update is available; `None` means up to date
+    /// (or the check has never succeeded).
+    pub async fn check(&self) -> Option<VersionInfo> {
+        let mut cached = self.cached.lock().await;
+        let is_stale = cached
+            .as_ref()
+            .map(|(checked_at, _)| checked_at.elapsed() >= self.ttl)
+            .unwrap_or(true);
+
+        if is_stale {
+            match self.updater.check_for_update().await {
+                Ok(result) => *cached = Some((std::time::Instant::now(), result)),
+                Err(e) => {
+                    tracing::warn!("Background update check failed: {}", e);
+                }
             }
         }
 
-        remote.len() > current.len()
+        cached.as_ref().and_then(|(_, info)| info.clone())
     }
 }
 
+/// Compare two version strings (e.g. "1.2.3", "v1.2.3"), numeric
+/// component-by-component, tolerating a leading `v`.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let parse = |v: &str| -> Vec<u32> {
+        v.trim_start_matches('v')
+            .split('.')
+            .filter_map(|s| s.parse().ok())
+            .collect()
+    };
+
+    let a_parts = parse(a);
+    let b_parts = parse(b);
+
+    for (x, y) in a_parts.iter().zip(b_parts.iter()) {
+        match x.cmp(y) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+
+    a_parts.len().cmp(&b_parts.len())
+}
+
 /// Format update information for display
 pub fn format_update_info(version: &VersionInfo, current: &str) -> String {
     let mut output = String::new();
@@ -324,6 +466,20 @@ mod tests {
         assert!(updater.is_newer_version("1.0.1"));
     }
 
+    #[test]
+    fn test_is_downgrade() {
+        let config = UpdateConfig {
+            current_version: "1.2.0".to_string(),
+            ..Default::default()
+        };
+        let updater = AutoUpdater::new(config);
+
+        assert!(updater.is_downgrade("1.0.0"));
+        assert!(updater.is_downgrade("v1.1.9"));
+        assert!(!updater.is_downgrade("1.2.0"));
+        assert!(!updater.is_downgrade("1.3.0"));
+    }
+
     #[test]
     fn test_update_config_new_github() {
         let config = UpdateConfig::new_github("owner", "repo");
@@ -384,4 +540,52 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_cached_update_check_serves_cached_value_within_ttl() {
+        // 接続不可なホストを指定しているため、仮に再検証が走ってもエラーになり
+        // キャッシュ済みの値は上書きされない。ここではTTL内のcheck()が
+        // キャッシュ済みの値を返すことのみを確認する
+        let config = UpdateConfig::new_github("owner", "repo")
+            .with_api_base_url("http://127.0.0.1:1".to_string());
+        let cache = CachedUpdateCheck::with_ttl(AutoUpdater::new(config), Duration::from_secs(3600));
+
+        *cache.cached.lock().await = Some((
+            std::time::Instant::now(),
+            Some(VersionInfo {
+                version: "9.9.9".to_string(),
+                download_url: "https://example.com/gateway".to_string(),
+                checksum: None,
+                release_notes: None,
+                mandatory: false,
+            }),
+        ));
+
+        let result = cache.check().await;
+        assert_eq!(result.unwrap().version, "9.9.9");
+    }
+
+    #[tokio::test]
+    async fn test_cached_update_check_rechecks_after_ttl_expires() {
+        // TTLをほぼ0にして、次のcheck()が再検証を試みることを確認する。
+        // 接続不可なホストなのでcheck_for_updateはエラーになり、キャッシュ済みの
+        // 値は（失敗した再検証では上書きされず）そのまま残るはず
+        let config = UpdateConfig::new_github("owner", "repo")
+            .with_api_base_url("http://127.0.0.1:1".to_string());
+        let cache = CachedUpdateCheck::with_ttl(AutoUpdater::new(config), Duration::from_millis(1));
+
+        *cache.cached.lock().await = Some((
+            std::time::Instant::now() - Duration::from_secs(10),
+            Some(VersionInfo {
+                version: "1.2.3".to_string(),
+                download_url: "https://example.com/gateway".to_string(),
+                checksum: None,
+                release_notes: None,
+                mandatory: false,
+            }),
+        ));
+
+        let result = cache.check().await;
+        assert_eq!(result.unwrap().version, "1.2.3");
+    }
 }
@@ -25,6 +25,18 @@ pub struct VersionInfo {
     pub mandatory: bool,
 }
 
+/// A list of [`VersionInfo`] entries served from a single static URL, e.g.
+/// mirrored onto an internal server for air-gapped sites that can't reach
+/// GitHub. Each entry's `download_url` may be relative to the manifest
+/// URL's directory, resolved the same way [`UpdateDownloader`] resolves
+/// relative URLs against `download_base_url`.
+///
+/// [`UpdateDownloader`]: super::downloader::UpdateDownloader
+#[derive(Clone, Debug, Deserialize)]
+pub struct VersionManifest {
+    pub versions: Vec<VersionInfo>,
+}
+
 /// GitHub Release asset information
 #[derive(Clone, Debug, Deserialize)]
 pub struct GitHubAsset {
@@ -77,6 +89,11 @@ impl std::str::FromStr for UpdateChannel {
     }
 }
 
+/// Default GitHub REST API base URL. Overridden via
+/// [`VersionChecker::with_api_base_url`] to point at a GitHub Enterprise
+/// instance (e.g. `https://github.example.com/api/v3`).
+pub const DEFAULT_GITHUB_API_BASE_URL: &str = "https://api.github.com";
+
 /// Checks for available updates from GitHub Releases
 pub struct VersionChecker {
     github_owner: String,
@@ -86,6 +103,18 @@ pub struct VersionChecker {
     client: reqwest::Client,
     /// Legacy URL for backwards compatibility
     version_check_url: Option<String>,
+    /// URL of a static [`VersionManifest`] JSON document, for offline update
+    /// distribution to sites that can't reach GitHub at all.
+    manifest_url: Option<String>,
+    /// Optional GitHub API token, sent as `Authorization: Bearer` on every
+    /// request. Raises the rate limit from 60 to 5000 requests/hour, which
+    /// matters for fleets of gateways sharing one egress IP. Also required
+    /// to download release assets from a private repository.
+    github_token: Option<String>,
+    /// GitHub REST API base URL, `https://api.github.com` by default.
+    /// Pointed at a GitHub Enterprise instance's API base to check for
+    /// updates against an internal-only repository.
+    api_base_url: String,
 }
 
 impl VersionChecker {
@@ -98,6 +127,9 @@ impl VersionChecker {
             prefer_msi: false,
             client: reqwest::Client::new(),
             version_check_url: None,
+            manifest_url: None,
+            github_token: None,
+            api_base_url: DEFAULT_GITHUB_API_BASE_URL.to_string(),
         }
     }
 
@@ -110,6 +142,27 @@ impl VersionChecker {
             prefer_msi: false,
             client: reqwest::Client::new(),
             version_check_url: Some(version_check_url),
+            manifest_url: None,
+            github_token: None,
+            api_base_url: DEFAULT_GITHUB_API_BASE_URL.to_string(),
+        }
+    }
+
+    /// Create a new VersionChecker backed by a static [`VersionManifest`]
+    /// URL instead of GitHub, for air-gapped sites mirroring releases on an
+    /// internal server. Unlike the legacy `version_check_url`, the manifest
+    /// can list multiple versions and resolves relative asset URLs.
+    pub fn new_manifest(manifest_url: String) -> Self {
+        Self {
+            github_owner: String::new(),
+            github_repo: String::new(),
+            update_channel: UpdateChannel::default(),
+            prefer_msi: false,
+            client: reqwest::Client::new(),
+            version_check_url: None,
+            manifest_url: Some(manifest_url),
+            github_token: None,
+            api_base_url: DEFAULT_GITHUB_API_BASE_URL.to_string(),
         }
     }
 
@@ -125,8 +178,41 @@ impl VersionChecker {
         self
     }
 
+    /// Set the GitHub API token to authenticate requests with (from
+    /// `GITHUB_TOKEN`). Unauthenticated requests to the GitHub REST API are
+    /// capped at 60/hour per source IP; a token raises that to 5000/hour.
+    /// Required to check private repositories at all, since even metadata
+    /// reads 404 without it.
+    pub fn with_github_token(mut self, token: Option<String>) -> Self {
+        self.github_token = token;
+        self
+    }
+
+    /// Set the GitHub REST API base URL, e.g. to point at a GitHub
+    /// Enterprise instance instead of the default `api.github.com`.
+    pub fn with_api_base_url(mut self, api_base_url: impl Into<String>) -> Self {
+        self.api_base_url = api_base_url.into();
+        self
+    }
+
+    /// Apply the configured GitHub token, if any, as an `Authorization:
+    /// Bearer` header.
+    fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.github_token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
     /// Get the latest version information from GitHub Releases
     pub async fn get_latest_version(&self) -> Result<VersionInfo, UpdateError> {
+        // Use static manifest if configured (offline/air-gapped sites)
+        if let Some(ref url) = self.manifest_url {
+            if !url.is_empty() {
+                return self.get_latest_version_manifest(url).await;
+            }
+        }
+
         // Use legacy URL if configured (backwards compatibility)
         if let Some(ref url) = self.version_check_url {
             if !url.is_empty() {
@@ -169,8 +255,8 @@ impl VersionChecker {
     /// Get the latest stable release (excludes pre-releases)
     async fn get_latest_stable_release(&self) -> Result<GitHubRelease, UpdateError> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases/latest",
-            self.github_owner, self.github_repo
+            "{}/repos/{}/{}/releases/latest",
+            self.api_base_url, self.github_owner, self.github_repo
         );
 
         self.fetch_release(&url).await
@@ -179,14 +265,16 @@ impl VersionChecker {
     /// Get the latest release including pre-releases
     async fn get_latest_release_including_prerelease(&self) -> Result<GitHubRelease, UpdateError> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases",
-            self.github_owner, self.github_repo
+            "{}/repos/{}/{}/releases",
+            self.api_base_url, self.github_owner, self.github_repo
         );
 
-        let response = self.client
-            .get(&url)
-            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
-            .header("Accept", "application/vnd.github+json")
+        let response = self.authorize(
+            self.client
+                .get(&url)
+                .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+                .header("Accept", "application/vnd.github+json")
+        )
             .send()
             .await?;
 
@@ -207,10 +295,12 @@ impl VersionChecker {
 
     /// Fetch a single release from the given URL
     async fn fetch_release(&self, url: &str) -> Result<GitHubRelease, UpdateError> {
-        let response = self.client
-            .get(url)
-            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
-            .header("Accept", "application/vnd.github+json")
+        let response = self.authorize(
+            self.client
+                .get(url)
+                .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+                .header("Accept", "application/vnd.github+json")
+        )
             .send()
             .await?;
 
@@ -224,6 +314,14 @@ impl VersionChecker {
             .map_err(|e| UpdateError::VersionCheck(format!("Failed to parse release: {}", e)))
     }
 
+    /// True if `release` has an asset matching the current platform (and
+    /// the configured `prefer_msi` preference). Lets callers like
+    /// `--list-releases` show whether a listed release can actually be
+    /// installed, without going through the full download flow.
+    pub fn has_platform_asset(&self, release: &GitHubRelease) -> bool {
+        self.select_asset(release).is_ok()
+    }
+
     /// Select the appropriate asset for the current platform
     fn select_asset<'a>(&self, release: &'a GitHubRelease) -> Result<&'a GitHubAsset, UpdateError> {
         let (os, arch) = get_platform_info();
@@ -341,9 +439,11 @@ impl VersionChecker {
             .find(|a| a.name == checksum_filename)
             .ok_or_else(|| UpdateError::VersionCheck("Checksum file not found".to_string()))?;
 
-        let response = self.client
-            .get(&checksum_asset.browser_download_url)
-            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+        let response = self.authorize(
+            self.client
+                .get(&checksum_asset.browser_download_url)
+                .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+        )
             .send()
             .await?;
 
@@ -380,6 +480,55 @@ impl VersionChecker {
         Ok(version_info)
     }
 
+    /// Fetch and parse the configured static manifest, resolving each
+    /// entry's `download_url` against the manifest URL's directory.
+    async fn fetch_manifest(&self, manifest_url: &str) -> Result<Vec<VersionInfo>, UpdateError> {
+        let response = self.client
+            .get(manifest_url)
+            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(UpdateError::VersionCheck(
+                format!("Server returned status: {}", response.status())
+            ));
+        }
+
+        let manifest: VersionManifest = response.json().await
+            .map_err(|e| UpdateError::VersionCheck(format!("Failed to parse manifest: {}", e)))?;
+
+        Ok(manifest.versions.into_iter()
+            .map(|mut v| {
+                v.download_url = resolve_relative_url(manifest_url, &v.download_url);
+                v
+            })
+            .collect())
+    }
+
+    /// Get the latest version from a static manifest (backwards the highest
+    /// version number wins, not list order).
+    async fn get_latest_version_manifest(&self, manifest_url: &str) -> Result<VersionInfo, UpdateError> {
+        let versions = self.fetch_manifest(manifest_url).await?;
+
+        versions.into_iter()
+            .fold(None, |latest, version| match latest {
+                Some(ref current) if !version_is_newer(&current.version, &version.version) => latest,
+                _ => Some(version),
+            })
+            .ok_or_else(|| UpdateError::VersionCheck("Manifest contained no versions".to_string()))
+    }
+
+    /// List all versions in the configured static manifest, for e.g.
+    /// `--list-releases`-style tooling.
+    pub async fn list_manifest_versions(&self) -> Result<Vec<VersionInfo>, UpdateError> {
+        let manifest_url = self.manifest_url.as_deref()
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| UpdateError::VersionCheck("No manifest URL configured".to_string()))?;
+
+        self.fetch_manifest(manifest_url).await
+    }
+
     /// Get a specific release by tag name
     pub async fn get_release_by_tag(&self, tag: &str) -> Result<GitHubRelease, UpdateError> {
         if self.github_owner.is_empty() || self.github_repo.is_empty() {
@@ -389,8 +538,8 @@ impl VersionChecker {
         }
 
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases/tags/{}",
-            self.github_owner, self.github_repo, tag
+            "{}/repos/{}/{}/releases/tags/{}",
+            self.api_base_url, self.github_owner, self.github_repo, tag
         );
 
         self.fetch_release(&url).await
@@ -424,14 +573,16 @@ impl VersionChecker {
         }
 
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases",
-            self.github_owner, self.github_repo
+            "{}/repos/{}/{}/releases",
+            self.api_base_url, self.github_owner, self.github_repo
         );
 
-        let response = self.client
-            .get(&url)
-            .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
-            .header("Accept", "application/vnd.github+json")
+        let response = self.authorize(
+            self.client
+                .get(&url)
+                .header("User-Agent", format!("gateway/{}", env!("CARGO_PKG_VERSION")))
+                .header("Accept", "application/vnd.github+json")
+        )
             .send()
             .await?;
 
@@ -450,6 +601,48 @@ impl VersionChecker {
     }
 }
 
+/// Resolve a manifest entry's `download_url` against the manifest URL's
+/// directory. Absolute URLs pass through unchanged; everything else is
+/// joined onto the manifest URL's parent path, mirroring how
+/// [`UpdateDownloader`] resolves relative URLs against `download_base_url`.
+///
+/// [`UpdateDownloader`]: super::downloader::UpdateDownloader
+fn resolve_relative_url(manifest_url: &str, download_url: &str) -> String {
+    if download_url.starts_with("http") {
+        download_url.to_string()
+    } else {
+        let base = manifest_url.rsplit_once('/').map(|(base, _)| base).unwrap_or(manifest_url);
+        format!("{}/{}", base, download_url)
+    }
+}
+
+/// True if `candidate` is a newer version than `base` (e.g. "1.0.1" is
+/// newer than "1.0.0"). Matches the numeric, `v`-prefix-tolerant comparison
+/// `AutoUpdater` uses against the locally running version.
+fn version_is_newer(base: &str, candidate: &str) -> bool {
+    use std::cmp::Ordering;
+
+    let parse = |v: &str| -> Vec<u32> {
+        v.trim_start_matches('v')
+            .split('.')
+            .filter_map(|s| s.parse().ok())
+            .collect()
+    };
+
+    let base_parts = parse(base);
+    let candidate_parts = parse(candidate);
+
+    for (b, c) in base_parts.iter().zip(candidate_parts.iter()) {
+        match b.cmp(c) {
+            Ordering::Less => return true,
+            Ordering::Greater => return false,
+            Ordering::Equal => continue,
+        }
+    }
+
+    candidate_parts.len() > base_parts.len()
+}
+
 /// Get the current platform information (OS, architecture)
 fn get_platform_info() -> (String, String) {
     let os = if cfg!(target_os = "windows") {
@@ -512,4 +705,128 @@ mod tests {
         let result = checker.get_latest_version().await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_with_github_token_sets_authorization_header() {
+        let checker = VersionChecker::new_github("owner".to_string(), "repo".to_string())
+            .with_github_token(Some("secret-token".to_string()));
+
+        let request = checker
+            .authorize(checker.client.get("https://api.github.com/repos/owner/repo"))
+            .build()
+            .unwrap();
+
+        let auth = request.headers().get("authorization").unwrap().to_str().unwrap();
+        assert_eq!(auth, "Bearer secret-token");
+    }
+
+    #[test]
+    fn test_without_github_token_omits_authorization_header() {
+        let checker = VersionChecker::new_github("owner".to_string(), "repo".to_string());
+
+        let request = checker
+            .authorize(checker.client.get("https://api.github.com/repos/owner/repo"))
+            .build()
+            .unwrap();
+
+        assert!(request.headers().get("authorization").is_none());
+    }
+
+    #[test]
+    fn test_version_manifest_deserializes_version_list() {
+        let json = r#"{"versions":[
+            {"version":"1.0.0","download_url":"gateway-1.0.0.exe"},
+            {"version":"1.2.0","download_url":"gateway-1.2.0.exe"}
+        ]}"#;
+
+        let manifest: VersionManifest = serde_json::from_str(json).unwrap();
+
+        assert_eq!(manifest.versions.len(), 2);
+        assert_eq!(manifest.versions[1].version, "1.2.0");
+    }
+
+    #[test]
+    fn test_resolve_relative_url_resolves_against_manifest_directory() {
+        let resolved = resolve_relative_url(
+            "https://internal.example.com/updates/manifest.json",
+            "gateway-1.2.3.exe",
+        );
+
+        assert_eq!(resolved, "https://internal.example.com/updates/gateway-1.2.3.exe");
+    }
+
+    #[test]
+    fn test_resolve_relative_url_passes_through_absolute_url() {
+        let resolved = resolve_relative_url(
+            "https://internal.example.com/updates/manifest.json",
+            "https://cdn.example.com/gateway-1.2.3.exe",
+        );
+
+        assert_eq!(resolved, "https://cdn.example.com/gateway-1.2.3.exe");
+    }
+
+    #[test]
+    fn test_version_is_newer() {
+        assert!(version_is_newer("1.0.0", "1.0.1"));
+        assert!(version_is_newer("1.0.0", "v1.1.0"));
+        assert!(!version_is_newer("1.0.1", "1.0.0"));
+        assert!(!version_is_newer("1.0.0", "1.0.0"));
+    }
+
+    #[tokio::test]
+    async fn test_new_manifest_without_url_errors() {
+        let checker = VersionChecker::new_manifest(String::new());
+        let result = checker.get_latest_version().await;
+        assert!(result.is_err());
+    }
+
+    fn sample_asset(name: &str) -> GitHubAsset {
+        GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+            size: 1024,
+            content_type: "application/octet-stream".to_string(),
+        }
+    }
+
+    fn sample_release(assets: Vec<GitHubAsset>) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: "v1.0.0".to_string(),
+            name: None,
+            body: None,
+            prerelease: false,
+            draft: false,
+            assets,
+            published_at: None,
+        }
+    }
+
+    #[test]
+    fn test_has_platform_asset_true_when_matching_asset_present() {
+        let checker = VersionChecker::new_github("owner".to_string(), "repo".to_string());
+        let (os, arch) = get_platform_info();
+        let filename = if os == "windows" {
+            format!("gateway-{}-{}.exe", os, arch)
+        } else {
+            format!("gateway-{}-{}", os, arch)
+        };
+        let release = sample_release(vec![sample_asset(&filename)]);
+
+        assert!(checker.has_platform_asset(&release));
+    }
+
+    #[test]
+    fn test_has_platform_asset_false_when_no_matching_asset() {
+        let checker = VersionChecker::new_github("owner".to_string(), "repo".to_string());
+        let release = sample_release(vec![sample_asset("unrelated-file.txt")]);
+
+        assert!(!checker.has_platform_asset(&release));
+    }
+
+    #[tokio::test]
+    async fn test_list_manifest_versions_without_manifest_source_errors() {
+        let checker = VersionChecker::new_github("owner".to_string(), "repo".to_string());
+        let result = checker.list_manifest_versions().await;
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,130 @@
+//! End-to-end test of the signaling handshake against a [`MockSignalingServer`],
+//! standing in for the live cf-wbrtc-auth server so the signaling layer can be
+//! exercised (and safely refactored) without network access.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use gateway_lib::p2p::{
+    AppRegisteredPayload, AuthErrorPayload, AuthOKPayload, AuthenticatedSignalingClient,
+    MockSignalingServer, ReconnectConfig, SignalingConfig, SignalingEventHandler,
+};
+use tokio::sync::mpsc;
+
+/// Forwards every event to a channel the test can await on, in order.
+struct RecordingHandler {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+#[async_trait]
+impl SignalingEventHandler for RecordingHandler {
+    async fn on_authenticated(&self, payload: AuthOKPayload) {
+        let _ = self.tx.send(format!("authenticated:{}", payload.user_id));
+    }
+    async fn on_auth_error(&self, payload: AuthErrorPayload) {
+        let _ = self.tx.send(format!("auth_error:{}", payload.error));
+    }
+    async fn on_app_registered(&self, payload: AppRegisteredPayload) {
+        let _ = self.tx.send(format!("app_registered:{}", payload.app_id));
+    }
+    async fn on_offer(&self, sdp: String, request_id: Option<String>) {
+        let _ = self.tx.send(format!("offer:{}:{:?}", sdp, request_id));
+    }
+    async fn on_answer(&self, _sdp: String, _app_id: Option<String>) {}
+    async fn on_ice(&self, candidate: serde_json::Value) {
+        let _ = self.tx.send(format!("ice:{}", candidate));
+    }
+    async fn on_error(&self, message: String) {
+        let _ = self.tx.send(format!("error:{}", message));
+    }
+    async fn on_connected(&self) {
+        let _ = self.tx.send("connected".to_string());
+    }
+    async fn on_disconnected(&self) {
+        let _ = self.tx.send("disconnected".to_string());
+    }
+}
+
+async fn next_event(rx: &mut mpsc::UnboundedReceiver<String>) -> String {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("event should arrive within 5s")
+        .expect("event channel should not close")
+}
+
+#[tokio::test]
+async fn test_full_handshake_against_mock_signaling_server() {
+    let server = MockSignalingServer::bind().await;
+    let server_url = server.url();
+
+    let config = SignalingConfig {
+        server_url,
+        api_key: "test-api-key".to_string(),
+        app_name: "IntegrationTestApp".to_string(),
+        capabilities: vec!["scrape".to_string()],
+        reconnect: ReconnectConfig::disabled(),
+        ..Default::default()
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut client = AuthenticatedSignalingClient::new(config);
+    client.set_event_handler(Arc::new(RecordingHandler { tx }));
+
+    let accept = tokio::spawn(async move { server.accept("test-api-key").await });
+
+    client.connect().await.expect("connect should succeed against the mock server");
+    assert_eq!(next_event(&mut rx).await, "connected");
+
+    let mut conn = accept
+        .await
+        .expect("accept task should not panic")
+        .expect("apiKey should have been accepted");
+
+    conn.send_auth_ok("user-1", "app").await;
+    assert_eq!(next_event(&mut rx).await, "authenticated:user-1");
+
+    client.register_app().await.expect("register_app should send over the open socket");
+    conn.send_app_registered("app-42").await;
+    assert_eq!(next_event(&mut rx).await, "app_registered:app-42");
+    assert_eq!(client.get_app_id().await, "app-42");
+
+    conn.send_offer("v=0 mock-sdp-offer", Some("req-1")).await;
+    assert_eq!(
+        next_event(&mut rx).await,
+        "offer:v=0 mock-sdp-offer:Some(\"req-1\")"
+    );
+
+    conn.send_ice(serde_json::json!({"candidate": "candidate:1 1 UDP 1 1.2.3.4 9 typ host"}))
+        .await;
+    assert_eq!(
+        next_event(&mut rx).await,
+        "ice:{\"candidate\":\"candidate:1 1 UDP 1 1.2.3.4 9 typ host\"}"
+    );
+}
+
+#[tokio::test]
+async fn test_mismatched_api_key_is_rejected_by_server() {
+    let server = MockSignalingServer::bind().await;
+    let server_url = server.url();
+
+    let config = SignalingConfig {
+        server_url,
+        api_key: "wrong-key".to_string(),
+        app_name: "IntegrationTestApp".to_string(),
+        reconnect: ReconnectConfig::disabled(),
+        ..Default::default()
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut client = AuthenticatedSignalingClient::new(config);
+    client.set_event_handler(Arc::new(RecordingHandler { tx }));
+
+    let accept = tokio::spawn(async move { server.accept("expected-key").await });
+
+    client.connect().await.expect("connect should succeed even though auth will fail");
+    assert_eq!(next_event(&mut rx).await, "connected");
+
+    let accepted = accept.await.expect("accept task should not panic");
+    assert!(accepted.is_none(), "server should reject a mismatched apiKey");
+}
@@ -33,6 +33,9 @@ pub struct Claims {
     pub iat: i64,
     /// Issuer
     pub iss: String,
+    /// Audience this token was minted for, if any
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub aud: Option<String>,
 }
 
 impl Claims {
@@ -45,9 +48,16 @@ impl Claims {
             exp: now + expires_in_secs,
             iat: now,
             iss: issuer.into(),
+            aud: None,
         }
     }
 
+    /// Set the audience this token is minted for.
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.aud = Some(audience.into());
+        self
+    }
+
     /// Check if the claims have expired.
     pub fn is_expired(&self) -> bool {
         chrono::Utc::now().timestamp() > self.exp
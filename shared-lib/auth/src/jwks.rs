@@ -0,0 +1,361 @@
+//! Fetching and caching a JWKS (JSON Web Key Set) document so a service can
+//! verify tokens minted by a central auth service without ever holding its
+//! private key.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use base64::Engine;
+use error::AuthError;
+use jwt::{Header, PKeyWithDigest, Token, VerifyWithKey};
+use openssl::bn::BigNum;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Public};
+use openssl::rsa::Rsa;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::claims::Claims;
+use crate::jwt::claims_from_map;
+
+/// A single RSA key from a JWKS document.
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    kid: String,
+    n: String,
+    e: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwksDocument {
+    keys: Vec<Jwk>,
+}
+
+struct Cache {
+    keys: HashMap<String, Jwk>,
+    fetched_at: i64,
+}
+
+/// Fetches and caches a JWKS document from `jwks_url`, matching tokens'
+/// `kid` header against cached keys. The cache refreshes once `ttl` has
+/// elapsed, and also refreshes early on an unknown `kid` so a key rotated
+/// in on the auth service is picked up without waiting out the TTL.
+pub struct JwksProvider {
+    client: Client,
+    jwks_url: String,
+    issuer: String,
+    audience: Option<String>,
+    ttl: Duration,
+    cache: RwLock<Option<Cache>>,
+}
+
+impl JwksProvider {
+    /// Create a new provider. `issuer` is enforced on every decoded token,
+    /// same as [`crate::JwtConfig::issuer`].
+    pub fn new(jwks_url: impl Into<String>, issuer: impl Into<String>, ttl: Duration) -> Self {
+        Self {
+            client: Client::new(),
+            jwks_url: jwks_url.into(),
+            issuer: issuer.into(),
+            audience: None,
+            ttl,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Require decoded tokens to carry this audience.
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    async fn fetch(&self) -> Result<(), AuthError> {
+        tracing::info!("Fetching JWKS from {}", self.jwks_url);
+
+        let response = self.client.get(&self.jwks_url).send().await.map_err(|e| {
+            tracing::error!("Failed to fetch JWKS from {}: {}", self.jwks_url, e);
+            AuthError::InvalidToken
+        })?;
+
+        if !response.status().is_success() {
+            tracing::error!(
+                "JWKS endpoint {} returned {}",
+                self.jwks_url,
+                response.status()
+            );
+            return Err(AuthError::InvalidToken);
+        }
+
+        let doc: JwksDocument = response.json().await.map_err(|e| {
+            tracing::error!("Failed to parse JWKS document from {}: {}", self.jwks_url, e);
+            AuthError::InvalidToken
+        })?;
+
+        let keys = doc.keys.into_iter().map(|k| (k.kid.clone(), k)).collect();
+        let mut cache = self.cache.write().await;
+        *cache = Some(Cache {
+            keys,
+            fetched_at: chrono::Utc::now().timestamp(),
+        });
+        Ok(())
+    }
+
+    /// Look up the key for `kid`, refreshing the cache first if it's
+    /// expired or doesn't know about `kid` yet.
+    async fn key_for(&self, kid: &str) -> Result<Jwk, AuthError> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(cache) = cache.as_ref() {
+                let fresh =
+                    chrono::Utc::now().timestamp() - cache.fetched_at < self.ttl.as_secs() as i64;
+                if fresh {
+                    if let Some(jwk) = cache.keys.get(kid) {
+                        return Ok(jwk.clone());
+                    }
+                }
+            }
+        }
+
+        self.fetch().await?;
+
+        let cache = self.cache.read().await;
+        cache
+            .as_ref()
+            .and_then(|c| c.keys.get(kid))
+            .cloned()
+            .ok_or_else(|| {
+                tracing::warn!("No JWKS key found for kid {} at {}", kid, self.jwks_url);
+                AuthError::InvalidToken
+            })
+    }
+}
+
+fn rsa_public_key_from_jwk(jwk: &Jwk) -> Result<PKey<Public>, AuthError> {
+    let decode = |field: &str| -> Result<BigNum, AuthError> {
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(field)
+            .map_err(|e| {
+                tracing::error!("Failed to base64-decode JWKS key component: {}", e);
+                AuthError::InvalidToken
+            })?;
+        BigNum::from_slice(&bytes).map_err(|e| {
+            tracing::error!("Failed to parse JWKS key component: {}", e);
+            AuthError::InvalidToken
+        })
+    };
+
+    let n = decode(&jwk.n)?;
+    let e = decode(&jwk.e)?;
+
+    let rsa = Rsa::from_public_components(n, e).map_err(|e| {
+        tracing::error!("Failed to build RSA key from JWKS components: {}", e);
+        AuthError::InvalidToken
+    })?;
+
+    PKey::from_rsa(rsa).map_err(|e| {
+        tracing::error!("Failed to wrap RSA key from JWKS: {}", e);
+        AuthError::InvalidToken
+    })
+}
+
+/// Decode and validate a JWT token against `provider`: the token's `kid`
+/// header selects which cached JWKS key to verify with, refreshing the
+/// cache on an unknown `kid` to handle key rotation.
+pub async fn decode_token_with_jwks(token: &str, provider: &JwksProvider) -> Result<Claims, AuthError> {
+    let unverified: Token<Header, BTreeMap<String, serde_json::Value>, _> =
+        Token::parse_unverified(token).map_err(|e| {
+            tracing::warn!("Failed to parse JWT header: {}", e);
+            AuthError::InvalidToken
+        })?;
+
+    let kid = unverified.header().key_id.clone().ok_or_else(|| {
+        tracing::warn!("JWT is missing a kid header, can't select a JWKS key");
+        AuthError::InvalidToken
+    })?;
+
+    let jwk = provider.key_for(&kid).await?;
+    let public_key = rsa_public_key_from_jwk(&jwk)?;
+    let key = PKeyWithDigest {
+        digest: MessageDigest::sha256(),
+        key: public_key,
+    };
+
+    let verified = unverified.verify_with_key(&key).map_err(|e| {
+        tracing::warn!("Failed to verify JWT against JWKS key {}: {}", kid, e);
+        AuthError::InvalidToken
+    })?;
+
+    claims_from_map(
+        verified.claims().clone(),
+        &provider.issuer,
+        provider.audience.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claims::Role;
+    use jwt::{AlgorithmType, SignWithKey};
+    use openssl::pkey::Private;
+    use openssl::rsa::Rsa;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    struct RsaFixture {
+        private_pem: String,
+        jwk: Jwk,
+    }
+
+    fn rsa_fixture(kid: &str) -> RsaFixture {
+        let rsa = Rsa::generate(2048).expect("failed to generate RSA key");
+        let n = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(rsa.n().to_vec());
+        let e = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(rsa.e().to_vec());
+        let private_pem = String::from_utf8(rsa.private_key_to_pem().unwrap()).unwrap();
+        RsaFixture {
+            private_pem,
+            jwk: Jwk { kid: kid.to_string(), n, e },
+        }
+    }
+
+    fn sign_with_kid(claims: &BTreeMap<String, serde_json::Value>, private_pem: &str, kid: &str) -> String {
+        let header = Header {
+            algorithm: AlgorithmType::Rs256,
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        };
+        let private_key: PKey<Private> =
+            PKey::private_key_from_pem(private_pem.as_bytes()).unwrap();
+        let key = PKeyWithDigest { digest: MessageDigest::sha256(), key: private_key };
+        Token::new(header, claims.clone())
+            .sign_with_key(&key)
+            .unwrap()
+            .as_str()
+            .to_string()
+    }
+
+    fn claims_map(sub: &str, issuer: &str) -> BTreeMap<String, serde_json::Value> {
+        let claims = Claims::new(sub, Role::User, issuer, 3600);
+        let mut map = BTreeMap::new();
+        map.insert("sub".to_string(), serde_json::json!(claims.sub));
+        map.insert("role".to_string(), serde_json::to_value(&claims.role).unwrap());
+        map.insert("exp".to_string(), serde_json::json!(claims.exp));
+        map.insert("iat".to_string(), serde_json::json!(claims.iat));
+        map.insert("iss".to_string(), serde_json::json!(claims.iss));
+        map
+    }
+
+    /// Spins up a tiny raw-HTTP server on localhost that serves `bodies` in
+    /// order (one per accepted connection, repeating the last body once
+    /// exhausted), standing in for a real JWKS endpoint in tests.
+    async fn start_mock_jwks(bodies: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut bodies = bodies.into_iter();
+            let mut last_body = String::new();
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { break };
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                if let Some(next) = bodies.next() {
+                    last_body = next;
+                }
+                let body = last_body.clone();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        format!("http://{}/", addr)
+    }
+
+    fn jwks_body(jwks: &[&Jwk]) -> String {
+        serde_json::json!({
+            "keys": jwks.iter().map(|k| serde_json::json!({"kid": k.kid, "n": k.n, "e": k.e, "kty": "RSA"})).collect::<Vec<_>>()
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_decode_token_with_jwks_verifies_matching_key() {
+        let fixture = rsa_fixture("key-1");
+        let url = start_mock_jwks(vec![jwks_body(&[&fixture.jwk])]).await;
+        let provider = JwksProvider::new(url, "test-issuer", Duration::from_secs(300));
+
+        let claims = claims_map("user123", "test-issuer");
+        let token = sign_with_kid(&claims, &fixture.private_pem, "key-1");
+
+        let decoded = decode_token_with_jwks(&token, &provider)
+            .await
+            .expect("token should verify against the matching JWKS key");
+
+        assert_eq!(decoded.sub, "user123");
+    }
+
+    #[tokio::test]
+    async fn test_decode_token_with_jwks_refreshes_on_unknown_kid() {
+        let old_fixture = rsa_fixture("key-1");
+        let new_fixture = rsa_fixture("key-2");
+        let url = start_mock_jwks(vec![
+            jwks_body(&[&old_fixture.jwk]),
+            jwks_body(&[&old_fixture.jwk, &new_fixture.jwk]),
+        ])
+        .await;
+        let provider = JwksProvider::new(url, "test-issuer", Duration::from_secs(300));
+
+        // Prime the cache with the first (stale) document, which doesn't
+        // have "key-2" yet.
+        let claims = claims_map("user123", "test-issuer");
+        let old_token = sign_with_kid(&claims, &old_fixture.private_pem, "key-1");
+        decode_token_with_jwks(&old_token, &provider)
+            .await
+            .expect("token signed with key-1 should verify");
+
+        // A token signed with the newly rotated-in key should trigger a
+        // refresh instead of failing outright on an unknown kid.
+        let new_token = sign_with_kid(&claims, &new_fixture.private_pem, "key-2");
+        let decoded = decode_token_with_jwks(&new_token, &provider)
+            .await
+            .expect("unknown kid should trigger a refresh that picks up the rotated key");
+
+        assert_eq!(decoded.sub, "user123");
+    }
+
+    #[tokio::test]
+    async fn test_decode_token_with_jwks_rejects_unknown_kid_after_refresh() {
+        let fixture = rsa_fixture("key-1");
+        let url = start_mock_jwks(vec![jwks_body(&[&fixture.jwk])]).await;
+        let provider = JwksProvider::new(url, "test-issuer", Duration::from_secs(300));
+
+        let claims = claims_map("user123", "test-issuer");
+        let token = sign_with_kid(&claims, &fixture.private_pem, "does-not-exist");
+
+        let result = decode_token_with_jwks(&token, &provider).await;
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_decode_token_with_jwks_enforces_audience_when_configured() {
+        let fixture = rsa_fixture("key-1");
+        let url = start_mock_jwks(vec![jwks_body(&[&fixture.jwk])]).await;
+        let provider = JwksProvider::new(url, "test-issuer", Duration::from_secs(300))
+            .with_audience("expense-service");
+
+        let claims = claims_map("user123", "test-issuer");
+        let token = sign_with_kid(&claims, &fixture.private_pem, "key-1");
+
+        let result = decode_token_with_jwks(&token, &provider).await;
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+}
@@ -2,7 +2,9 @@
 
 use error::AuthError;
 use hmac::{Hmac, Mac};
-use jwt::{SignWithKey, VerifyWithKey};
+use jwt::{PKeyWithDigest, SignWithKey, VerifyWithKey};
+use openssl::hash::MessageDigest;
+use openssl::pkey::{Id, PKey};
 use sha2::Sha256;
 use std::collections::BTreeMap;
 
@@ -10,15 +12,32 @@ use crate::claims::{Claims, Role};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// RSA/ECDSA key material for [`JwtConfig`], kept as PEM so the config stays
+/// plain `Debug`/`Clone` data instead of holding parsed `openssl` types.
+#[derive(Debug, Clone)]
+struct RsaKeys {
+    private_pem: String,
+    public_pem: String,
+}
+
 /// JWT configuration.
 #[derive(Debug, Clone)]
 pub struct JwtConfig {
-    /// Secret key for signing tokens
+    /// Secret key for signing tokens (HMAC). Unused once [`Self::with_rsa_pem`]
+    /// has been called.
     pub secret: String,
-    /// Token issuer
+    /// Token issuer, always enforced on decode
     pub issuer: String,
+    /// Expected token audience. Opt-in: when `None`, `decode_token` doesn't
+    /// check `aud` at all, so single-service deployments aren't forced to
+    /// set one.
+    pub audience: Option<String>,
     /// Token validity duration in seconds
     pub expires_in_secs: i64,
+    /// RSA/ECDSA key pair, if set via [`Self::with_rsa_pem`]. Opt-in: when
+    /// `None`, `encode_token`/`decode_token` sign and verify with `secret`
+    /// (HMAC) as before.
+    rsa: Option<RsaKeys>,
 }
 
 impl JwtConfig {
@@ -27,45 +46,130 @@ impl JwtConfig {
         Self {
             secret: secret.into(),
             issuer: issuer.into(),
+            audience: None,
             expires_in_secs,
+            rsa: None,
         }
     }
+
+    /// Require decoded tokens to carry this audience.
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    /// Sign and verify with an RSA or ECDSA key pair (PEM-encoded) instead
+    /// of the HMAC `secret`. `encode_token` signs with `private_pem`;
+    /// `decode_token` verifies with `public_pem`, so only whoever holds the
+    /// private key can mint tokens that other services can still verify.
+    pub fn with_rsa_pem(mut self, private_pem: impl Into<String>, public_pem: impl Into<String>) -> Self {
+        self.rsa = Some(RsaKeys {
+            private_pem: private_pem.into(),
+            public_pem: public_pem.into(),
+        });
+        self
+    }
 }
 
-/// Encode claims into a JWT token.
-pub fn encode_token(claims: &Claims, secret: &str) -> Result<String, AuthError> {
-    let key = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|e| {
-        tracing::error!("Failed to create HMAC key: {}", e);
-        AuthError::TokenCreationFailed
-    })?;
+/// Checks that `id` is one this crate can drive through [`PKeyWithDigest`]
+/// (RSA or EC) before handing the key to the `jwt` crate, which otherwise
+/// panics on an unsupported key type instead of returning an error.
+fn check_asymmetric_key_type(id: Id) -> Result<(), ()> {
+    if matches!(id, Id::RSA | Id::EC) {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
 
+/// Encode claims into a JWT token, signing with `config`'s RSA/ECDSA private
+/// key if one is set via [`JwtConfig::with_rsa_pem`], or its HMAC `secret`
+/// otherwise.
+pub fn encode_token(claims: &Claims, config: &JwtConfig) -> Result<String, AuthError> {
     let mut token_claims: BTreeMap<String, serde_json::Value> = BTreeMap::new();
     token_claims.insert("sub".to_string(), serde_json::json!(claims.sub));
     token_claims.insert("role".to_string(), serde_json::to_value(&claims.role).unwrap());
     token_claims.insert("exp".to_string(), serde_json::json!(claims.exp));
     token_claims.insert("iat".to_string(), serde_json::json!(claims.iat));
     token_claims.insert("iss".to_string(), serde_json::json!(claims.iss));
+    if let Some(aud) = &claims.aud {
+        token_claims.insert("aud".to_string(), serde_json::json!(aud));
+    }
 
-    token_claims.sign_with_key(&key).map_err(|e| {
-        tracing::error!("Failed to encode JWT: {}", e);
-        AuthError::TokenCreationFailed
-    })
+    match &config.rsa {
+        Some(rsa) => {
+            let private_key = PKey::private_key_from_pem(rsa.private_pem.as_bytes()).map_err(|e| {
+                tracing::error!("Failed to parse RSA/ECDSA private key: {}", e);
+                AuthError::TokenCreationFailed
+            })?;
+            check_asymmetric_key_type(private_key.id()).map_err(|_| {
+                tracing::error!("Unsupported private key type: {:?}", private_key.id());
+                AuthError::TokenCreationFailed
+            })?;
+            let key = PKeyWithDigest { digest: MessageDigest::sha256(), key: private_key };
+            token_claims.sign_with_key(&key).map_err(|e| {
+                tracing::error!("Failed to encode JWT: {}", e);
+                AuthError::TokenCreationFailed
+            })
+        }
+        None => {
+            let key = HmacSha256::new_from_slice(config.secret.as_bytes()).map_err(|e| {
+                tracing::error!("Failed to create HMAC key: {}", e);
+                AuthError::TokenCreationFailed
+            })?;
+            token_claims.sign_with_key(&key).map_err(|e| {
+                tracing::error!("Failed to encode JWT: {}", e);
+                AuthError::TokenCreationFailed
+            })
+        }
+    }
 }
 
-/// Decode and validate a JWT token.
-pub fn decode_token(token: &str, secret: &str, issuer: &str) -> Result<Claims, AuthError> {
-    let key = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|e| {
-        tracing::error!("Failed to create HMAC key: {}", e);
-        AuthError::InvalidToken
-    })?;
+/// Decode and validate a JWT token against `config`, enforcing its issuer
+/// and, if set, its audience. Verifies with `config`'s RSA/ECDSA public key
+/// if one is set via [`JwtConfig::with_rsa_pem`], or its HMAC `secret`
+/// otherwise.
+pub fn decode_token(token: &str, config: &JwtConfig) -> Result<Claims, AuthError> {
+    let token_claims: BTreeMap<String, serde_json::Value> = match &config.rsa {
+        Some(rsa) => {
+            let public_key = PKey::public_key_from_pem(rsa.public_pem.as_bytes()).map_err(|e| {
+                tracing::error!("Failed to parse RSA/ECDSA public key: {}", e);
+                AuthError::InvalidToken
+            })?;
+            check_asymmetric_key_type(public_key.id()).map_err(|_| {
+                tracing::error!("Unsupported public key type: {:?}", public_key.id());
+                AuthError::InvalidToken
+            })?;
+            let key = PKeyWithDigest { digest: MessageDigest::sha256(), key: public_key };
+            token.verify_with_key(&key).map_err(|e| {
+                tracing::warn!("Failed to decode JWT: {}", e);
+                AuthError::InvalidToken
+            })?
+        }
+        None => {
+            let key = HmacSha256::new_from_slice(config.secret.as_bytes()).map_err(|e| {
+                tracing::error!("Failed to create HMAC key: {}", e);
+                AuthError::InvalidToken
+            })?;
+            token.verify_with_key(&key).map_err(|e| {
+                tracing::warn!("Failed to decode JWT: {}", e);
+                AuthError::InvalidToken
+            })?
+        }
+    };
 
-    let token_claims: BTreeMap<String, serde_json::Value> =
-        token.verify_with_key(&key).map_err(|e| {
-            tracing::warn!("Failed to decode JWT: {}", e);
-            AuthError::InvalidToken
-        })?;
+    claims_from_map(token_claims, &config.issuer, config.audience.as_deref())
+}
 
-    // Extract claims
+/// Build and validate [`Claims`] out of a decoded token's claim set,
+/// enforcing `issuer` and, if set, `audience`. Shared by [`decode_token`]
+/// and [`crate::jwks::decode_token_with_jwks`] since both need the same
+/// claim extraction and enforcement once the signature itself is verified.
+pub(crate) fn claims_from_map(
+    token_claims: BTreeMap<String, serde_json::Value>,
+    issuer: &str,
+    audience: Option<&str>,
+) -> Result<Claims, AuthError> {
     let sub = token_claims
         .get("sub")
         .and_then(|v| v.as_str())
@@ -93,13 +197,26 @@ pub fn decode_token(token: &str, secret: &str, issuer: &str) -> Result<Claims, A
         .ok_or(AuthError::InvalidToken)?
         .to_string();
 
+    let aud = token_claims
+        .get("aud")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
     // Validate issuer
     if iss != issuer {
         tracing::warn!("Invalid issuer: expected {}, got {}", issuer, iss);
         return Err(AuthError::InvalidToken);
     }
 
-    let claims = Claims { sub, role, exp, iat, iss };
+    // Validate audience, only if this deployment opted in to checking it
+    if let Some(expected_aud) = audience {
+        if aud.as_deref() != Some(expected_aud) {
+            tracing::warn!("Invalid audience: expected {}, got {:?}", expected_aud, aud);
+            return Err(AuthError::InvalidToken);
+        }
+    }
+
+    let claims = Claims { sub, role, exp, iat, iss, aud };
 
     if claims.is_expired() {
         return Err(AuthError::TokenExpired);
@@ -114,15 +231,104 @@ mod tests {
 
     #[test]
     fn test_encode_decode_token() {
-        let secret = "test-secret-key";
-        let issuer = "test-issuer";
-        let claims = Claims::new("user123", Role::User, issuer, 3600);
+        let config = JwtConfig::new("test-secret-key", "test-issuer", 3600);
+        let claims = Claims::new("user123", Role::User, "test-issuer", 3600);
 
-        let token = encode_token(&claims, secret).expect("Failed to encode");
-        let decoded = decode_token(&token, secret, issuer).expect("Failed to decode");
+        let token = encode_token(&claims, &config).expect("Failed to encode");
+        let decoded = decode_token(&token, &config).expect("Failed to decode");
 
         assert_eq!(decoded.sub, "user123");
         assert_eq!(decoded.role, Role::User);
-        assert_eq!(decoded.iss, issuer);
+        assert_eq!(decoded.iss, "test-issuer");
+        assert_eq!(decoded.aud, None);
+    }
+
+    #[test]
+    fn test_decode_token_rejects_wrong_issuer() {
+        let encode_config = JwtConfig::new("test-secret-key", "service-a", 3600);
+        let decode_config = JwtConfig::new("test-secret-key", "service-b", 3600);
+        let claims = Claims::new("user123", Role::User, "service-a", 3600);
+
+        let token = encode_token(&claims, &encode_config).expect("Failed to encode");
+        let result = decode_token(&token, &decode_config);
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[test]
+    fn test_decode_token_enforces_audience_when_configured() {
+        let config = JwtConfig::new("test-secret-key", "test-issuer", 3600)
+            .with_audience("expense-service");
+        let claims = Claims::new("user123", Role::User, "test-issuer", 3600)
+            .with_audience("timecard-service");
+
+        let token = encode_token(&claims, &config).expect("Failed to encode");
+        let result = decode_token(&token, &config);
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[test]
+    fn test_decode_token_skips_audience_check_when_not_configured() {
+        let config = JwtConfig::new("test-secret-key", "test-issuer", 3600);
+        let claims = Claims::new("user123", Role::User, "test-issuer", 3600)
+            .with_audience("timecard-service");
+
+        let token = encode_token(&claims, &config).expect("Failed to encode");
+        let decoded = decode_token(&token, &config).expect("Failed to decode");
+
+        assert_eq!(decoded.aud, Some("timecard-service".to_string()));
+    }
+
+    #[test]
+    fn test_decode_token_accepts_matching_audience() {
+        let config = JwtConfig::new("test-secret-key", "test-issuer", 3600)
+            .with_audience("timecard-service");
+        let claims = Claims::new("user123", Role::User, "test-issuer", 3600)
+            .with_audience("timecard-service");
+
+        let token = encode_token(&claims, &config).expect("Failed to encode");
+        let decoded = decode_token(&token, &config).expect("Failed to decode");
+
+        assert_eq!(decoded.aud, Some("timecard-service".to_string()));
+    }
+
+    fn rsa_pem_pair() -> (String, String) {
+        use openssl::rsa::Rsa;
+
+        let rsa = Rsa::generate(2048).expect("failed to generate RSA key");
+        let private_pem = String::from_utf8(rsa.private_key_to_pem().unwrap()).unwrap();
+        let public_pem = String::from_utf8(rsa.public_key_to_pem().unwrap()).unwrap();
+        (private_pem, public_pem)
+    }
+
+    #[test]
+    fn test_encode_decode_token_with_rsa() {
+        let (private_pem, public_pem) = rsa_pem_pair();
+        let config = JwtConfig::new("unused-secret", "test-issuer", 3600)
+            .with_rsa_pem(private_pem, public_pem);
+        let claims = Claims::new("user123", Role::User, "test-issuer", 3600);
+
+        let token = encode_token(&claims, &config).expect("Failed to encode");
+        let decoded = decode_token(&token, &config).expect("Failed to decode");
+
+        assert_eq!(decoded.sub, "user123");
+        assert_eq!(decoded.iss, "test-issuer");
+    }
+
+    #[test]
+    fn test_decode_token_with_rsa_rejects_wrong_public_key() {
+        let (private_pem, _) = rsa_pem_pair();
+        let (_, other_public_pem) = rsa_pem_pair();
+        let encode_config = JwtConfig::new("unused-secret", "test-issuer", 3600)
+            .with_rsa_pem(private_pem, other_public_pem.clone());
+        let decode_config = JwtConfig::new("unused-secret", "test-issuer", 3600)
+            .with_rsa_pem(String::new(), other_public_pem);
+        let claims = Claims::new("user123", Role::User, "test-issuer", 3600);
+
+        let token = encode_token(&claims, &encode_config).expect("Failed to encode");
+        let result = decode_token(&token, &decode_config);
+
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
     }
 }
@@ -4,6 +4,8 @@
 
 mod jwt;
 mod claims;
+mod jwks;
 
 pub use jwt::{encode_token, decode_token, JwtConfig};
 pub use claims::{Claims, Role};
+pub use jwks::{decode_token_with_jwks, JwksProvider};
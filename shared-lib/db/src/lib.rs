@@ -1,12 +1,29 @@
 //! Database utilities and connection pooling for microservices.
 //!
-//! This crate provides MySQL connection pool management using sqlx.
+//! This crate provides MySQL connection pool management using sqlx, with
+//! optional Postgres support behind the `postgres` feature (see [`pg`]).
 
+#[cfg(feature = "mysql")]
 mod pool;
 mod config;
+#[cfg(feature = "mysql")]
+mod query;
+#[cfg(feature = "mysql")]
+mod migrate;
+#[cfg(feature = "postgres")]
+mod pg;
 
-pub use pool::{create_pool, DbPool};
+#[cfg(feature = "mysql")]
+pub use pool::{create_pool, create_pool_and_migrate, DbPool};
 pub use config::DbConfig;
+#[cfg(feature = "mysql")]
+pub use query::{DbPoolExt, TransactionFuture};
+#[cfg(feature = "mysql")]
+pub use migrate::DbPoolMigrateExt;
+#[cfg(feature = "postgres")]
+pub use pg::{create_pg_pool, PgPool};
 
 // Re-export sqlx types for convenience
-pub use sqlx::{self, MySql, Row};
+#[cfg(feature = "mysql")]
+pub use sqlx::MySql;
+pub use sqlx::{self, Row};
@@ -0,0 +1,118 @@
+//! Schema migration helpers over [`DbPool`] (behind the `mysql` feature).
+//!
+//! Wraps `sqlx::migrate::Migrator` so every service gets the same error
+//! mapping and tracing instead of calling `sqlx::migrate!` directly and
+//! handling `MigrateError` on its own.
+
+use std::collections::HashSet;
+
+use error::DatabaseError;
+use sqlx::migrate::{Migrate, Migrator};
+
+use crate::DbPool;
+
+/// Extension method for applying a [`Migrator`]'s migrations to a [`DbPool`].
+#[allow(async_fn_in_trait)]
+pub trait DbPoolMigrateExt {
+    /// Apply any of `migrator`'s migrations that haven't run yet, logging
+    /// each one as applied or already-up-to-date (skipped).
+    async fn run_migrations(&self, migrator: &Migrator) -> Result<(), DatabaseError>;
+}
+
+impl DbPoolMigrateExt for DbPool {
+    async fn run_migrations(&self, migrator: &Migrator) -> Result<(), DatabaseError> {
+        let mut conn = self
+            .acquire()
+            .await
+            .map_err(|e| DatabaseError::ConnectionFailed(e.to_string()))?;
+
+        let already_applied: HashSet<i64> = conn
+            .list_applied_migrations()
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?
+            .into_iter()
+            .map(|m| m.version)
+            .collect();
+        drop(conn);
+
+        migrator
+            .run(self)
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        for migration in migrator.iter() {
+            if already_applied.contains(&migration.version) {
+                tracing::debug!(
+                    version = migration.version,
+                    description = %migration.description,
+                    "migration already applied, skipped"
+                );
+            } else {
+                tracing::info!(
+                    version = migration.version,
+                    description = %migration.description,
+                    "migration applied"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::mysql::MySqlPoolOptions;
+
+    /// Integration test against a real MySQL database: a pending migration
+    /// is applied, and re-running the same migrator is a no-op.
+    /// Run with: TEST_DATABASE_URL=mysql://... cargo test run_migrations -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_run_migrations_applies_once_then_skips() {
+        let url = std::env::var("TEST_DATABASE_URL")
+            .expect("TEST_DATABASE_URL must be set for this test");
+        let pool = MySqlPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .expect("failed to connect to test database");
+
+        let dir = tempfile_dir();
+        std::fs::write(
+            dir.join("20240101000000_create_migrate_smoke_test.sql"),
+            "CREATE TABLE IF NOT EXISTS migrate_smoke_test (id BIGINT PRIMARY KEY);",
+        )
+        .unwrap();
+
+        let migrator = Migrator::new(dir.as_path()).await.unwrap();
+
+        pool.run_migrations(&migrator)
+            .await
+            .expect("first run should apply the migration");
+
+        pool.run_migrations(&migrator)
+            .await
+            .expect("second run should skip the already-applied migration");
+
+        sqlx::query("DROP TABLE migrate_smoke_test")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = 20240101000000")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn tempfile_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "db-migrate-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
@@ -1,10 +1,12 @@
-//! Database connection pool management.
+//! MySQL connection pool management (behind the `mysql` feature).
 
+use sqlx::migrate::Migrator;
 use sqlx::mysql::MySqlPoolOptions;
 use sqlx::MySqlPool;
 use std::time::Duration;
 
 use crate::config::DbConfig;
+use crate::migrate::DbPoolMigrateExt;
 use error::DatabaseError;
 
 /// Type alias for MySQL connection pool.
@@ -12,12 +14,7 @@ pub type DbPool = MySqlPool;
 
 /// Create a new database connection pool.
 pub async fn create_pool(config: &DbConfig) -> Result<DbPool, DatabaseError> {
-    tracing::info!(
-        "Creating database pool: {}:{}/{}",
-        config.host,
-        config.port,
-        config.database
-    );
+    tracing::info!("Creating database pool: {}", config.masked_url());
 
     let pool = MySqlPoolOptions::new()
         .max_connections(config.max_connections)
@@ -34,6 +31,18 @@ pub async fn create_pool(config: &DbConfig) -> Result<DbPool, DatabaseError> {
     Ok(pool)
 }
 
+/// Create a database connection pool and apply `migrator`'s migrations to
+/// it before returning, so a service can't start serving against a
+/// not-yet-migrated schema.
+pub async fn create_pool_and_migrate(
+    config: &DbConfig,
+    migrator: &Migrator,
+) -> Result<DbPool, DatabaseError> {
+    let pool = create_pool(config).await?;
+    pool.run_migrations(migrator).await?;
+    Ok(pool)
+}
+
 /// Check if the database connection is healthy.
 pub async fn health_check(pool: &DbPool) -> Result<(), DatabaseError> {
     sqlx::query("SELECT 1")
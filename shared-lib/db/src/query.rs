@@ -0,0 +1,206 @@
+//! Prepared-statement helpers over [`DbPool`].
+//!
+//! Repositories were re-typing the same `sqlx::query_as(...).bind(...)`
+//! boilerplate and `.map_err(...)` call at every call site. This isn't a
+//! full ORM -- just thin helpers that run a query and keep sqlx error
+//! translation to [`DatabaseError`] in one place.
+
+use error::DatabaseError;
+use sqlx::mysql::{MySqlArguments, MySqlQueryResult, MySqlRow};
+use sqlx::query::{Query, QueryAs};
+use sqlx::{FromRow, MySql, Transaction};
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::DbPool;
+
+/// A transaction body passed to [`DbPoolExt::with_transaction`]. Boxed
+/// because the closure borrows the `Transaction` for the duration of the
+/// returned future, which a plain generic `Fut` can't express without
+/// tying it to a named lifetime the trait doesn't have.
+pub type TransactionFuture<'c, T> =
+    Pin<Box<dyn Future<Output = Result<T, DatabaseError>> + Send + 'c>>;
+
+/// Extension methods for running prepared queries against a [`DbPool`] with
+/// consistent error mapping.
+#[allow(async_fn_in_trait)]
+pub trait DbPoolExt {
+    /// Run `sql` through `bind` and return at most one row mapped into `T`.
+    async fn fetch_optional<T, F>(&self, sql: &str, bind: F) -> Result<Option<T>, DatabaseError>
+    where
+        T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+        F: for<'q> FnOnce(
+            QueryAs<'q, MySql, T, MySqlArguments>,
+        ) -> QueryAs<'q, MySql, T, MySqlArguments>;
+
+    /// Run `sql` through `bind` and return every matching row mapped into `T`.
+    async fn fetch_all<T, F>(&self, sql: &str, bind: F) -> Result<Vec<T>, DatabaseError>
+    where
+        T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+        F: for<'q> FnOnce(
+            QueryAs<'q, MySql, T, MySqlArguments>,
+        ) -> QueryAs<'q, MySql, T, MySqlArguments>;
+
+    /// Run a statement that doesn't return rows, e.g. an INSERT, UPDATE, or
+    /// DELETE.
+    async fn execute<F>(&self, sql: &str, bind: F) -> Result<MySqlQueryResult, DatabaseError>
+    where
+        F: for<'q> FnOnce(
+            Query<'q, MySql, MySqlArguments>,
+        ) -> Query<'q, MySql, MySqlArguments>;
+
+    /// Run `body` inside a transaction, committing on `Ok` and rolling back
+    /// on `Err`.
+    async fn with_transaction<T, F>(&self, body: F) -> Result<T, DatabaseError>
+    where
+        F: for<'c> FnOnce(&'c mut Transaction<'_, MySql>) -> TransactionFuture<'c, T>;
+}
+
+impl DbPoolExt for DbPool {
+    async fn fetch_optional<T, F>(&self, sql: &str, bind: F) -> Result<Option<T>, DatabaseError>
+    where
+        T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+        F: for<'q> FnOnce(
+            QueryAs<'q, MySql, T, MySqlArguments>,
+        ) -> QueryAs<'q, MySql, T, MySqlArguments>,
+    {
+        bind(sqlx::query_as::<_, T>(sql))
+            .fetch_optional(self)
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e.to_string()))
+    }
+
+    async fn fetch_all<T, F>(&self, sql: &str, bind: F) -> Result<Vec<T>, DatabaseError>
+    where
+        T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+        F: for<'q> FnOnce(
+            QueryAs<'q, MySql, T, MySqlArguments>,
+        ) -> QueryAs<'q, MySql, T, MySqlArguments>,
+    {
+        bind(sqlx::query_as::<_, T>(sql))
+            .fetch_all(self)
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e.to_string()))
+    }
+
+    async fn execute<F>(&self, sql: &str, bind: F) -> Result<MySqlQueryResult, DatabaseError>
+    where
+        F: for<'q> FnOnce(
+            Query<'q, MySql, MySqlArguments>,
+        ) -> Query<'q, MySql, MySqlArguments>,
+    {
+        bind(sqlx::query(sql))
+            .execute(self)
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e.to_string()))
+    }
+
+    async fn with_transaction<T, F>(&self, body: F) -> Result<T, DatabaseError>
+    where
+        F: for<'c> FnOnce(&'c mut Transaction<'_, MySql>) -> TransactionFuture<'c, T>,
+    {
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::TransactionFailed(e.to_string()))?;
+
+        match body(&mut tx).await {
+            Ok(value) => {
+                tx.commit()
+                    .await
+                    .map_err(|e| DatabaseError::TransactionFailed(e.to_string()))?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = tx.rollback().await;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::mysql::MySqlPoolOptions;
+
+    async fn test_pool() -> DbPool {
+        let url = std::env::var("TEST_DATABASE_URL")
+            .expect("TEST_DATABASE_URL must be set for this test");
+        MySqlPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .expect("failed to connect to test database")
+    }
+
+    /// Integration test against a real MySQL database: an early `Err`
+    /// return from the closure must roll the transaction back, leaving no
+    /// trace of the write it started.
+    /// Run with: TEST_DATABASE_URL=mysql://... cargo test with_transaction -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_with_transaction_rolls_back_on_early_return() {
+        let pool = test_pool().await;
+
+        let result: Result<(), DatabaseError> = pool
+            .with_transaction(|tx| {
+                Box::pin(async move {
+                    sqlx::query(
+                        "INSERT INTO timecard_entries (employee_id, entry_date) VALUES (?, ?)",
+                    )
+                    .bind("EMP_TX_ROLLBACK")
+                    .bind(chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+                    Err(DatabaseError::QueryFailed("forced failure".to_string()))
+                })
+            })
+            .await;
+
+        assert!(result.is_err());
+
+        let row: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM timecard_entries WHERE employee_id = 'EMP_TX_ROLLBACK'",
+        )
+        .fetch_optional(&pool)
+        .await
+        .expect("select failed");
+
+        assert!(row.is_none(), "rolled-back insert should not be visible");
+    }
+
+    /// Integration test against a real MySQL database: an `Ok` return
+    /// commits the transaction.
+    /// Run with: TEST_DATABASE_URL=mysql://... cargo test with_transaction -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_with_transaction_commits_on_ok() {
+        let pool = test_pool().await;
+
+        pool.with_transaction(|tx| {
+            Box::pin(async move {
+                sqlx::query("INSERT INTO timecard_entries (employee_id, entry_date) VALUES (?, ?)")
+                    .bind("EMP_TX_COMMIT")
+                    .bind(chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+                Ok(())
+            })
+        })
+        .await
+        .expect("transaction should commit");
+
+        let row: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM timecard_entries WHERE employee_id = 'EMP_TX_COMMIT'",
+        )
+        .fetch_optional(&pool)
+        .await
+        .expect("select failed");
+
+        assert!(row.is_some(), "committed insert should be visible");
+    }
+}
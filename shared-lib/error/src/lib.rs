@@ -2,6 +2,8 @@
 //!
 //! This crate provides unified error handling across all services.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -22,6 +24,22 @@ pub enum AppError {
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// A catch-all for unexpected failures, preserving the original error
+    /// chain (via `source()`) for server-side logs while `Display` only
+    /// ever renders a generic message, so it's safe to show to API
+    /// consumers.
+    #[error("Internal server error")]
+    InternalWithSource(#[from] anyhow::Error),
+}
+
+impl AppError {
+    /// Wrap an arbitrary error as an internal error, preserving its chain
+    /// via `source()` for logging while keeping the client-facing message
+    /// generic.
+    pub fn with_source(source: impl Into<anyhow::Error>) -> Self {
+        Self::InternalWithSource(source.into())
+    }
 }
 
 /// Authentication-related errors.
@@ -65,6 +83,44 @@ pub enum DatabaseError {
     TransactionFailed(String),
 }
 
+impl DatabaseError {
+    /// Whether the operation that produced this error is worth retrying.
+    ///
+    /// `ConnectionFailed` is always retryable, since it reflects a transient
+    /// infrastructure problem. `DuplicateEntry` and `NotFound` never are,
+    /// since retrying can't change the outcome. `TransactionFailed` is
+    /// retryable when the underlying MySQL error code identifies it as a
+    /// deadlock or lock wait timeout (the transaction lost a race rather
+    /// than hitting a structural problem), not retryable for any other
+    /// MySQL error code, and retryable by default when the message carries
+    /// no error code at all. `QueryFailed` is never retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DatabaseError::ConnectionFailed(_) => true,
+            DatabaseError::TransactionFailed(message) => {
+                mysql_error_code(message).is_none_or(is_retryable_mysql_error_code)
+            }
+            DatabaseError::QueryFailed(_) => false,
+            DatabaseError::NotFound => false,
+            DatabaseError::DuplicateEntry(_) => false,
+        }
+    }
+}
+
+/// Extracts the numeric MySQL error code from a message produced by sqlx,
+/// whose `Error::Database` variant formats as
+/// `"error returned from database: {number} ({sqlstate}): {message}"`.
+fn mysql_error_code(message: &str) -> Option<u16> {
+    message.split_whitespace().find_map(|word| word.parse().ok())
+}
+
+/// MySQL error codes that mean the transaction simply lost a race
+/// (1213 = deadlock, 1205 = lock wait timeout exceeded) rather than hitting
+/// a problem that retrying wouldn't fix.
+fn is_retryable_mysql_error_code(code: u16) -> bool {
+    matches!(code, 1213 | 1205)
+}
+
 /// Error response for API clients.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -75,6 +131,10 @@ pub struct ErrorResponse {
     /// Optional additional details
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<String>,
+    /// Correlation id (e.g. the request's `x-request-id`) for matching this
+    /// response to the server log line that explains it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
 }
 
 impl ErrorResponse {
@@ -84,6 +144,7 @@ impl ErrorResponse {
             code: code.into(),
             message: message.into(),
             details: None,
+            trace_id: None,
         }
     }
 
@@ -92,6 +153,97 @@ impl ErrorResponse {
         self.details = Some(details.into());
         self
     }
+
+    /// Attach the request's correlation id (e.g. its `x-request-id` header
+    /// or tracing span field) so support can find the matching log line.
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Create an error response whose message is rendered from `locales`'
+    /// template for `code`/`locale`, substituting `{key}` placeholders in
+    /// the template with the matching entry from `params`.
+    ///
+    /// Falls back to the built-in English message for `code` when
+    /// `locales` is `None`, or has no template registered for this
+    /// `code`/`locale` pair, so passing `None` keeps the default
+    /// (English, untemplated) behavior unchanged.
+    pub fn localized(
+        code: impl Into<String>,
+        locale: &str,
+        params: &HashMap<String, String>,
+        locales: Option<&ErrorLocales>,
+    ) -> Self {
+        let code = code.into();
+        let message = locales
+            .and_then(|locales| locales.template(locale, &code))
+            .map(|template| render_template(template, params))
+            .unwrap_or_else(|| default_message(&code).to_string());
+        Self::new(code, message)
+    }
+}
+
+/// A table of per-locale error message templates, keyed by the
+/// [`ErrorResponse`] `code` they render, loaded once at startup and passed
+/// to [`ErrorResponse::localized`] wherever a localized response is built.
+///
+/// Templates may reference `{key}` placeholders, substituted from the
+/// `params` passed to `localized` at render time.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorLocales {
+    templates: HashMap<String, HashMap<String, String>>,
+}
+
+impl ErrorLocales {
+    /// Create an empty locale table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the message template to use for `code` under `locale`.
+    pub fn register(&mut self, locale: impl Into<String>, code: impl Into<String>, template: impl Into<String>) -> &mut Self {
+        self.templates.entry(locale.into()).or_default().insert(code.into(), template.into());
+        self
+    }
+
+    fn template(&self, locale: &str, code: &str) -> Option<&str> {
+        self.templates.get(locale)?.get(code).map(String::as_str)
+    }
+}
+
+/// Substitutes each `{key}` placeholder in `template` with its matching
+/// entry from `params`; placeholders with no matching entry are left as-is.
+fn render_template(template: &str, params: &HashMap<String, String>) -> String {
+    let mut rendered = template.to_string();
+    for (key, value) in params {
+        rendered = rendered.replace(&format!("{{{key}}}"), value);
+    }
+    rendered
+}
+
+/// The built-in English message for a known [`ErrorResponse`] `code`, used
+/// as the fallback when no locale table (or no matching template) is
+/// available. Mirrors the code/message pairs in the `AuthError`/
+/// `DatabaseError` -> `ErrorResponse` conversions below.
+fn default_message(code: &str) -> &'static str {
+    const DEFAULT_MESSAGES: &[(&str, &str)] = &[
+        ("AUTH_INVALID_CREDENTIALS", "Invalid credentials"),
+        ("AUTH_INVALID_TOKEN", "Invalid token"),
+        ("AUTH_TOKEN_EXPIRED", "Token has expired"),
+        ("AUTH_TOKEN_CREATION_FAILED", "Failed to create token"),
+        ("AUTH_UNAUTHORIZED", "Unauthorized"),
+        ("AUTH_FORBIDDEN", "Access forbidden"),
+        ("DB_CONNECTION_FAILED", "Database connection failed"),
+        ("DB_QUERY_FAILED", "Database query failed"),
+        ("DB_NOT_FOUND", "Record not found"),
+        ("DB_DUPLICATE_ENTRY", "Duplicate entry"),
+        ("DB_TRANSACTION_FAILED", "Transaction failed"),
+    ];
+    DEFAULT_MESSAGES
+        .iter()
+        .find_map(|&(known_code, message)| (known_code == code).then_some(message))
+        .unwrap_or("An unexpected error occurred")
 }
 
 impl From<AuthError> for ErrorResponse {
@@ -123,3 +275,46 @@ impl From<DatabaseError> for ErrorResponse {
 
 /// Result type alias using AppError.
 pub type Result<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localized_falls_back_to_english_when_no_locale_table() {
+        let response = ErrorResponse::localized("AUTH_INVALID_CREDENTIALS", "ja", &HashMap::new(), None);
+
+        assert_eq!(response.message, "Invalid credentials");
+    }
+
+    #[test]
+    fn localized_falls_back_to_english_when_locale_has_no_template() {
+        let locales = ErrorLocales::new();
+
+        let response = ErrorResponse::localized("AUTH_INVALID_CREDENTIALS", "ja", &HashMap::new(), Some(&locales));
+
+        assert_eq!(response.message, "Invalid credentials");
+    }
+
+    #[test]
+    fn localized_renders_registered_template() {
+        let mut locales = ErrorLocales::new();
+        locales.register("ja", "AUTH_INVALID_CREDENTIALS", "認証情報が無効です");
+
+        let response = ErrorResponse::localized("AUTH_INVALID_CREDENTIALS", "ja", &HashMap::new(), Some(&locales));
+
+        assert_eq!(response.message, "認証情報が無効です");
+    }
+
+    #[test]
+    fn localized_substitutes_params_into_template() {
+        let mut locales = ErrorLocales::new();
+        locales.register("en", "DB_DUPLICATE_ENTRY", "{field} is already taken");
+        let mut params = HashMap::new();
+        params.insert("field".to_string(), "email".to_string());
+
+        let response = ErrorResponse::localized("DB_DUPLICATE_ENTRY", "en", &params, Some(&locales));
+
+        assert_eq!(response.message, "email is already taken");
+    }
+}
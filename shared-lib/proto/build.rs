@@ -11,8 +11,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Enable file descriptor set for reflection if the feature is enabled
     // Note: In build.rs, features are checked via environment variables
-    if env::var("CARGO_FEATURE_REFLECTION").is_ok() {
-        config = config.file_descriptor_set_path(out_dir.join("gateway_descriptor.bin"));
+    let reflection_enabled = env::var("CARGO_FEATURE_REFLECTION").is_ok();
+    if reflection_enabled {
+        config = config.file_descriptor_set_path(out_dir.join("combined_descriptor.bin"));
     }
 
     // Collect proto files to compile based on features
@@ -26,9 +27,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         protos.push("proto/scraper.proto");
     }
 
-    // if env::var("CARGO_FEATURE_TIMECARD").is_ok() {
-    //     protos.push("proto/timecard.proto");
-    // }
+    if env::var("CARGO_FEATURE_TIMECARD").is_ok() {
+        protos.push("proto/timecard.proto");
+    }
 
     if env::var("CARGO_FEATURE_PDF").is_ok() {
         protos.push("proto/pdf.proto");
@@ -38,9 +39,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if protos.is_empty() {
         protos.push("proto/gateway.proto");
         protos.push("proto/scraper.proto");
+        protos.push("proto/timecard.proto");
         protos.push("proto/pdf.proto");
     }
 
+    // The reflection server bridges gateway, scraper, and pdf requests alike
+    // (see gateway/src/p2p/grpc_handler.rs), so its descriptor set must cover
+    // all three regardless of which individual proto features a consumer
+    // enabled - otherwise `FileContainingSymbol` lookups for services that
+    // weren't independently feature-enabled would come back empty.
+    if reflection_enabled {
+        for reflected in ["proto/gateway.proto", "proto/scraper.proto", "proto/pdf.proto"] {
+            if !protos.contains(&reflected) {
+                protos.push(reflected);
+            }
+        }
+    }
+
     config.compile_protos(&protos, &["proto"])?;
 
     Ok(())
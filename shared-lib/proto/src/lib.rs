@@ -10,23 +10,65 @@
 //! - `reflection`: Enable gRPC reflection support
 
 /// Gateway proto definitions
+#[cfg(feature = "gateway")]
 pub mod gateway {
     tonic::include_proto!("gateway");
 }
 
 /// Scraper proto definitions (front-compatible)
+#[cfg(feature = "scraper")]
 pub mod scraper {
     tonic::include_proto!("scraper");
 }
 
 /// PDF generator proto definitions
+#[cfg(feature = "pdf")]
 pub mod pdf {
     tonic::include_proto!("pdf");
 }
 
+/// Timecard service proto definitions
+#[cfg(feature = "timecard")]
+pub mod timecard {
+    tonic::include_proto!("timecard");
+}
+
 // Re-export commonly used types for convenience
+#[cfg(feature = "gateway")]
 pub use gateway::*;
 
-/// File descriptor set for gRPC reflection
+/// Combined file descriptor set for gRPC reflection, covering the gateway,
+/// scraper, and pdf services regardless of which of those proto features a
+/// consumer enabled - see the comment in `build.rs` for why.
 #[cfg(feature = "reflection")]
-pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gateway_descriptor.bin"));
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/combined_descriptor.bin"));
+
+#[cfg(all(test, feature = "reflection"))]
+mod tests {
+    use super::FILE_DESCRIPTOR_SET;
+    use prost::Message;
+    use prost_types::FileDescriptorSet;
+
+    fn service_full_names(set: &FileDescriptorSet) -> Vec<String> {
+        set.file
+            .iter()
+            .flat_map(|f| {
+                let package = f.package.clone().unwrap_or_default();
+                f.service.iter().map(move |s| {
+                    let name = s.name.clone().unwrap_or_default();
+                    format!("{}.{}", package, name)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_combined_descriptor_resolves_all_three_services() {
+        let set = FileDescriptorSet::decode(FILE_DESCRIPTOR_SET).expect("valid descriptor set");
+        let names = service_full_names(&set);
+
+        assert!(names.contains(&"gateway.GatewayService".to_string()), "{:?}", names);
+        assert!(names.contains(&"scraper.ETCScraper".to_string()), "{:?}", names);
+        assert!(names.contains(&"pdf.PdfGenerator".to_string()), "{:?}", names);
+    }
+}
@@ -0,0 +1,237 @@
+//! gRPC service implementation
+//!
+//! Implements the tonic-generated `TimecardService` trait from
+//! `proto::timecard`, translating requests into calls against
+//! [`crate::service::TimecardService`]. The gateway registers
+//! [`TimecardGrpcService`] directly on its `Server`/`Routes` for
+//! InProcess calls.
+
+use tonic::{Request, Response, Status};
+
+use proto::timecard::timecard_service_server::TimecardService as TimecardServiceTrait;
+use proto::timecard::{
+    ClockInRequest, ClockInResponse, ClockOutRequest, ClockOutResponse, DailyHours as ProtoDailyHours,
+    ListEntriesRequest, ListEntriesResponse, SummarizeRequest, SummarizeResponse,
+    TimecardEntry as ProtoTimecardEntry,
+};
+
+use crate::models::{DailyHours, TimecardEntry, TimecardSummary};
+use crate::service::{ServiceError, TimecardService};
+
+/// gRPC front-end for [`TimecardService`]
+pub struct TimecardGrpcService {
+    service: TimecardService,
+}
+
+impl TimecardGrpcService {
+    /// Create a new gRPC service backed by a fresh [`TimecardService`]
+    pub fn new() -> Self {
+        Self {
+            service: TimecardService::new(),
+        }
+    }
+}
+
+impl Default for TimecardGrpcService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_proto_entry(entry: TimecardEntry) -> ProtoTimecardEntry {
+    ProtoTimecardEntry {
+        id: entry.id.unwrap_or_default(),
+        employee_id: entry.employee_id,
+        date: entry.date.to_string(),
+        clock_in: entry
+            .clock_in
+            .map(|t| t.format("%H:%M").to_string())
+            .unwrap_or_default(),
+        clock_out: entry
+            .clock_out
+            .map(|t| t.format("%H:%M").to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn to_proto_daily_hours(daily: DailyHours) -> ProtoDailyHours {
+    ProtoDailyHours {
+        date: daily.date.to_string(),
+        worked_hours: daily.worked_hours,
+    }
+}
+
+fn to_summarize_response(summary: TimecardSummary) -> SummarizeResponse {
+    SummarizeResponse {
+        employee_id: summary.employee_id,
+        start_date: summary.start_date.to_string(),
+        end_date: summary.end_date.to_string(),
+        total_hours: summary.total_hours,
+        overtime_hours: summary.overtime_hours,
+        daily_breakdown: summary
+            .daily_breakdown
+            .into_iter()
+            .map(to_proto_daily_hours)
+            .collect(),
+    }
+}
+
+fn to_status(err: ServiceError) -> Status {
+    match err {
+        ServiceError::NotFound { .. } => Status::not_found(err.to_string()),
+        ServiceError::InvalidTimeFormat(_)
+        | ServiceError::InvalidTimeRange
+        | ServiceError::AlreadyClockedIn { .. }
+        | ServiceError::NoOpenEntry { .. } => Status::invalid_argument(err.to_string()),
+        ServiceError::RepositoryError(_) => Status::internal(err.to_string()),
+    }
+}
+
+#[tonic::async_trait]
+impl TimecardServiceTrait for TimecardGrpcService {
+    async fn clock_in(
+        &self,
+        request: Request<ClockInRequest>,
+    ) -> Result<Response<ClockInResponse>, Status> {
+        let req = request.into_inner();
+
+        let entry = self
+            .service
+            .clock_in(&req.employee_id, &req.date, &req.time)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(ClockInResponse {
+            entry: Some(to_proto_entry(entry)),
+        }))
+    }
+
+    async fn clock_out(
+        &self,
+        request: Request<ClockOutRequest>,
+    ) -> Result<Response<ClockOutResponse>, Status> {
+        let req = request.into_inner();
+
+        let entry = self
+            .service
+            .clock_out(&req.employee_id, &req.date, &req.time)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(ClockOutResponse {
+            entry: Some(to_proto_entry(entry)),
+        }))
+    }
+
+    async fn list_entries(
+        &self,
+        request: Request<ListEntriesRequest>,
+    ) -> Result<Response<ListEntriesResponse>, Status> {
+        let req = request.into_inner();
+
+        let entries = self
+            .service
+            .get_entries_in_range(&req.employee_id, &req.start_date, &req.end_date)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(ListEntriesResponse {
+            entries: entries.into_iter().map(to_proto_entry).collect(),
+        }))
+    }
+
+    async fn summarize(
+        &self,
+        request: Request<SummarizeRequest>,
+    ) -> Result<Response<SummarizeResponse>, Status> {
+        let req = request.into_inner();
+
+        let summary = self
+            .service
+            .summarize(&req.employee_id, &req.start_date, &req.end_date)
+            .await
+            .map_err(to_status)?;
+
+        Ok(Response::new(to_summarize_response(summary)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clock_in_then_list_entries() {
+        let grpc = TimecardGrpcService::new();
+
+        let resp = grpc
+            .clock_in(Request::new(ClockInRequest {
+                employee_id: "EMP001".to_string(),
+                date: "2024-01-15".to_string(),
+                time: "09:00".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.entry.unwrap().clock_in, "09:00");
+
+        let resp = grpc
+            .list_entries(Request::new(ListEntriesRequest {
+                employee_id: "EMP001".to_string(),
+                start_date: "2024-01-01".to_string(),
+                end_date: "2024-01-31".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_clock_out_without_open_entry_is_invalid_argument() {
+        let grpc = TimecardGrpcService::new();
+
+        let status = grpc
+            .clock_out(Request::new(ClockOutRequest {
+                employee_id: "EMP001".to_string(),
+                date: "2024-01-15".to_string(),
+                time: "18:00".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_after_clock_in_and_out() {
+        let grpc = TimecardGrpcService::new();
+
+        grpc.clock_in(Request::new(ClockInRequest {
+            employee_id: "EMP001".to_string(),
+            date: "2024-01-15".to_string(),
+            time: "09:00".to_string(),
+        }))
+        .await
+        .unwrap();
+        grpc.clock_out(Request::new(ClockOutRequest {
+            employee_id: "EMP001".to_string(),
+            date: "2024-01-15".to_string(),
+            time: "18:00".to_string(),
+        }))
+        .await
+        .unwrap();
+
+        let resp = grpc
+            .summarize(Request::new(SummarizeRequest {
+                employee_id: "EMP001".to_string(),
+                start_date: "2024-01-01".to_string(),
+                end_date: "2024-01-31".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(resp.daily_breakdown.len(), 1);
+        assert!((resp.total_hours - 9.0).abs() < 0.01);
+    }
+}
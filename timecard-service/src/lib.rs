@@ -3,11 +3,13 @@
 //! This crate provides timecard management functionality.
 //! It exposes services via tower::Service for InProcess calls from the gateway.
 
+pub mod grpc;
 pub mod models;
 pub mod repository;
 pub mod service;
 
-pub use models::{Timecard, TimecardEntry};
+pub use grpc::TimecardGrpcService;
+pub use models::{DailyHours, Timecard, TimecardEntry, TimecardSummary};
 pub use service::TimecardService;
 
 /// Service configuration
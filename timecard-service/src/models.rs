@@ -35,11 +35,16 @@ impl TimecardEntry {
         }
     }
 
-    /// Calculate working hours for this entry
+    /// Calculate working hours for this entry. A `clock_out` that's not
+    /// later than `clock_in` is treated as a shift spanning midnight (e.g.
+    /// 22:00 -> 02:00), not a negative duration.
     pub fn working_hours(&self) -> Option<f64> {
         match (self.clock_in, self.clock_out) {
             (Some(clock_in), Some(clock_out)) => {
-                let duration = clock_out.signed_duration_since(clock_in);
+                let mut duration = clock_out.signed_duration_since(clock_in);
+                if duration <= chrono::Duration::zero() {
+                    duration += chrono::Duration::hours(24);
+                }
                 let break_duration = chrono::Duration::minutes(self.break_minutes.unwrap_or(0) as i64);
                 let working_duration = duration - break_duration;
                 Some(working_duration.num_minutes() as f64 / 60.0)
@@ -49,6 +54,26 @@ impl TimecardEntry {
     }
 }
 
+/// Worked hours for a single day, as returned by
+/// `TimecardRepository::daily_hours`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyHours {
+    pub date: NaiveDate,
+    pub worked_hours: f64,
+}
+
+/// Aggregated totals over a date range, for managers who want weekly or
+/// monthly summaries instead of raw punches. See `TimecardService::summarize`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimecardSummary {
+    pub employee_id: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub total_hours: f64,
+    pub overtime_hours: f64,
+    pub daily_breakdown: Vec<DailyHours>,
+}
+
 /// Timecard representing a collection of entries for an employee
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Timecard {
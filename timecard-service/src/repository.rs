@@ -3,10 +3,13 @@
 //! Database operations for timecard management.
 
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use thiserror::Error;
 
-use crate::models::TimecardEntry;
+use crate::models::{DailyHours, TimecardEntry};
+
+use db::DbPool;
+use error::DatabaseError;
 
 /// Repository errors
 #[derive(Error, Debug)]
@@ -21,6 +24,16 @@ pub enum RepositoryError {
     ValidationError(String),
 }
 
+/// Outcome of [`TimecardRepository::clock_in`]: whether it recorded a new
+/// clock-in, or found that another entry for the employee was already
+/// open. Returned explicitly rather than raising a generic error, so
+/// [`crate::service::TimecardService::clock_in`] can build its
+/// `AlreadyClockedIn` error from the entry that won the race.
+pub enum ClockInOutcome {
+    ClockedIn(TimecardEntry),
+    AlreadyOpen(TimecardEntry),
+}
+
 /// Timecard repository trait for database operations
 #[allow(async_fn_in_trait)]
 pub trait TimecardRepository: Send + Sync {
@@ -39,6 +52,36 @@ pub trait TimecardRepository: Send + Sync {
         end_date: NaiveDate,
     ) -> Result<Vec<TimecardEntry>>;
 
+    /// Find a timecard entry by its row ID
+    async fn find_by_id(&self, id: i64) -> Result<Option<TimecardEntry>>;
+
+    /// Find the employee's most recent entry that has been clocked in but
+    /// not yet clocked out, if any.
+    async fn find_open_entry(&self, employee_id: &str) -> Result<Option<TimecardEntry>>;
+
+    /// Worked hours per day in `[start_date, end_date]`, aggregated at the
+    /// data source (a single grouped query for `MySqlRepository`) rather
+    /// than by loading every row and summing in application code.
+    async fn daily_hours(
+        &self,
+        employee_id: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<DailyHours>>;
+
+    /// Record a clock-in for `employee_id` on `date` at `time`, atomically
+    /// checking for and rejecting a pre-existing open entry as part of the
+    /// same operation - unlike calling [`TimecardRepository::find_open_entry`]
+    /// and then [`TimecardRepository::create`]/[`TimecardRepository::update`]
+    /// separately, two concurrent calls for the same employee can't both
+    /// observe "no open entry" and both succeed.
+    async fn clock_in(
+        &self,
+        employee_id: &str,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<ClockInOutcome>;
+
     /// Create a new timecard entry
     async fn create(&self, entry: &TimecardEntry) -> Result<TimecardEntry>;
 
@@ -99,6 +142,82 @@ impl TimecardRepository for InMemoryRepository {
             .collect())
     }
 
+    async fn find_by_id(&self, id: i64) -> Result<Option<TimecardEntry>> {
+        let entries = self.entries.read().unwrap();
+        Ok(entries.iter().find(|e| e.id == Some(id)).cloned())
+    }
+
+    async fn find_open_entry(&self, employee_id: &str) -> Result<Option<TimecardEntry>> {
+        let entries = self.entries.read().unwrap();
+        Ok(entries
+            .iter()
+            .filter(|e| e.employee_id == employee_id && e.clock_in.is_some() && e.clock_out.is_none())
+            .max_by_key(|e| e.date)
+            .cloned())
+    }
+
+    async fn clock_in(
+        &self,
+        employee_id: &str,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<ClockInOutcome> {
+        // Held for the whole check-then-write below, unlike find_open_entry
+        // followed by a separate create/update call, which would leave a
+        // window for two concurrent clock-ins to both see no open entry.
+        let mut entries = self.entries.write().unwrap();
+
+        if let Some(open) = entries
+            .iter()
+            .filter(|e| e.employee_id == employee_id && e.clock_in.is_some() && e.clock_out.is_none())
+            .max_by_key(|e| e.date)
+            .cloned()
+        {
+            return Ok(ClockInOutcome::AlreadyOpen(open));
+        }
+
+        if let Some(existing) = entries
+            .iter_mut()
+            .find(|e| e.employee_id == employee_id && e.date == date)
+        {
+            existing.clock_in = Some(time);
+            existing.updated_at = Some(chrono::Utc::now());
+            return Ok(ClockInOutcome::ClockedIn(existing.clone()));
+        }
+
+        let mut new_entry = TimecardEntry::new(employee_id.to_string(), date);
+        new_entry.clock_in = Some(time);
+        new_entry.id = Some(
+            self.next_id
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+        );
+        new_entry.created_at = Some(chrono::Utc::now());
+        new_entry.updated_at = Some(chrono::Utc::now());
+        entries.push(new_entry.clone());
+        Ok(ClockInOutcome::ClockedIn(new_entry))
+    }
+
+    async fn daily_hours(
+        &self,
+        employee_id: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<DailyHours>> {
+        let entries = self.entries.read().unwrap();
+        let mut daily: Vec<DailyHours> = entries
+            .iter()
+            .filter(|e| e.employee_id == employee_id && e.date >= start_date && e.date <= end_date)
+            .filter_map(|e| {
+                e.working_hours().map(|worked_hours| DailyHours {
+                    date: e.date,
+                    worked_hours,
+                })
+            })
+            .collect();
+        daily.sort_by_key(|d| d.date);
+        Ok(daily)
+    }
+
     async fn create(&self, entry: &TimecardEntry) -> Result<TimecardEntry> {
         let mut entries = self.entries.write().unwrap();
         let mut new_entry = entry.clone();
@@ -138,6 +257,290 @@ impl TimecardRepository for InMemoryRepository {
     }
 }
 
+/// Production repository backed by MySQL via `db::DbPool`. Rows live in the
+/// `timecard_entries` table described in `schema.sql` at the crate root.
+pub struct MySqlRepository {
+    pool: DbPool,
+}
+
+impl MySqlRepository {
+    /// Wrap an existing pool, e.g. one built with `db::create_pool`.
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Row shape returned by `timecard_entries` queries, mapped into
+/// [`TimecardEntry`] below.
+#[derive(sqlx::FromRow)]
+struct TimecardEntryRow {
+    id: i64,
+    employee_id: String,
+    entry_date: NaiveDate,
+    clock_in: Option<NaiveTime>,
+    clock_out: Option<NaiveTime>,
+    break_minutes: Option<i32>,
+    notes: Option<String>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<TimecardEntryRow> for TimecardEntry {
+    fn from(row: TimecardEntryRow) -> Self {
+        Self {
+            id: Some(row.id),
+            employee_id: row.employee_id,
+            date: row.entry_date,
+            clock_in: row.clock_in,
+            clock_out: row.clock_out,
+            break_minutes: row.break_minutes,
+            notes: row.notes,
+            created_at: Some(row.created_at.and_utc()),
+            updated_at: Some(row.updated_at.and_utc()),
+        }
+    }
+}
+
+const SELECT_COLUMNS: &str =
+    "id, employee_id, entry_date, clock_in, clock_out, break_minutes, notes, created_at, updated_at";
+
+/// Row shape returned by the grouped query backing `daily_hours`.
+#[derive(sqlx::FromRow)]
+struct DailyHoursRow {
+    entry_date: NaiveDate,
+    worked_seconds: i64,
+}
+
+impl TimecardRepository for MySqlRepository {
+    async fn find_by_employee_and_date(
+        &self,
+        employee_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<TimecardEntry>> {
+        let row = sqlx::query_as::<_, TimecardEntryRow>(&format!(
+            "SELECT {SELECT_COLUMNS} FROM timecard_entries WHERE employee_id = ? AND entry_date = ?"
+        ))
+        .bind(employee_id)
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        Ok(row.map(TimecardEntry::from))
+    }
+
+    async fn find_by_employee_and_range(
+        &self,
+        employee_id: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<TimecardEntry>> {
+        let rows = sqlx::query_as::<_, TimecardEntryRow>(&format!(
+            "SELECT {SELECT_COLUMNS} FROM timecard_entries \
+             WHERE employee_id = ? AND entry_date BETWEEN ? AND ? ORDER BY entry_date ASC"
+        ))
+        .bind(employee_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        Ok(rows.into_iter().map(TimecardEntry::from).collect())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<TimecardEntry>> {
+        let row = sqlx::query_as::<_, TimecardEntryRow>(&format!(
+            "SELECT {SELECT_COLUMNS} FROM timecard_entries WHERE id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        Ok(row.map(TimecardEntry::from))
+    }
+
+    async fn find_open_entry(&self, employee_id: &str) -> Result<Option<TimecardEntry>> {
+        let row = sqlx::query_as::<_, TimecardEntryRow>(&format!(
+            "SELECT {SELECT_COLUMNS} FROM timecard_entries \
+             WHERE employee_id = ? AND clock_in IS NOT NULL AND clock_out IS NULL \
+             ORDER BY entry_date DESC LIMIT 1"
+        ))
+        .bind(employee_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        Ok(row.map(TimecardEntry::from))
+    }
+
+    async fn clock_in(
+        &self,
+        employee_id: &str,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<ClockInOutcome> {
+        // No explicit transaction or row lock needed: uq_open_entry_per_employee
+        // (see schema.sql) makes the database itself reject a second
+        // concurrent open entry for the same employee, so the INSERT/UPDATE
+        // below either succeeds outright or fails with a unique-constraint
+        // violation we can recognize.
+        let existing = self.find_by_employee_and_date(employee_id, date).await?;
+        let now = chrono::Utc::now().naive_utc();
+
+        let write_result = match &existing {
+            Some(entry) => {
+                sqlx::query("UPDATE timecard_entries SET clock_in = ?, updated_at = ? WHERE id = ?")
+                    .bind(time)
+                    .bind(now)
+                    .bind(entry.id)
+                    .execute(&self.pool)
+                    .await
+            }
+            None => sqlx::query(
+                "INSERT INTO timecard_entries (employee_id, entry_date, clock_in, created_at, updated_at) \
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(employee_id)
+            .bind(date)
+            .bind(time)
+            .bind(now)
+            .bind(now)
+            .execute(&self.pool)
+            .await,
+        };
+
+        match write_result {
+            Ok(_) => {
+                let entry = self
+                    .find_by_employee_and_date(employee_id, date)
+                    .await?
+                    .ok_or_else(|| {
+                        RepositoryError::DatabaseError("clock_in did not persist a row".to_string())
+                    })?;
+                Ok(ClockInOutcome::ClockedIn(entry))
+            }
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                let open = self.find_open_entry(employee_id).await?.ok_or_else(|| {
+                    RepositoryError::DatabaseError(
+                        "unique-constraint conflict during clock_in but no open entry found".to_string(),
+                    )
+                })?;
+                Ok(ClockInOutcome::AlreadyOpen(open))
+            }
+            Err(e) => Err(DatabaseError::QueryFailed(e.to_string()).into()),
+        }
+    }
+
+    async fn daily_hours(
+        &self,
+        employee_id: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<DailyHours>> {
+        let rows = sqlx::query_as::<_, DailyHoursRow>(
+            "SELECT entry_date, \
+                CAST(SUM( \
+                    TIME_TO_SEC( \
+                        CASE WHEN clock_out < clock_in \
+                            THEN ADDTIME(clock_out, '24:00:00') \
+                            ELSE clock_out \
+                        END \
+                    ) - TIME_TO_SEC(clock_in) \
+                    - COALESCE(break_minutes, 0) * 60 \
+                ) AS SIGNED) AS worked_seconds \
+             FROM timecard_entries \
+             WHERE employee_id = ? AND entry_date BETWEEN ? AND ? \
+                AND clock_in IS NOT NULL AND clock_out IS NOT NULL \
+             GROUP BY entry_date \
+             ORDER BY entry_date ASC",
+        )
+        .bind(employee_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DailyHours {
+                date: row.entry_date,
+                worked_hours: row.worked_seconds as f64 / 3600.0,
+            })
+            .collect())
+    }
+
+    async fn create(&self, entry: &TimecardEntry) -> Result<TimecardEntry> {
+        let now = chrono::Utc::now().naive_utc();
+        let result = sqlx::query(
+            "INSERT INTO timecard_entries \
+                (employee_id, entry_date, clock_in, clock_out, break_minutes, notes, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&entry.employee_id)
+        .bind(entry.date)
+        .bind(entry.clock_in)
+        .bind(entry.clock_out)
+        .bind(entry.break_minutes)
+        .bind(&entry.notes)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        self.find_by_id(result.last_insert_id() as i64)
+            .await?
+            .ok_or_else(|| {
+                RepositoryError::DatabaseError("insert did not return a row".to_string()).into()
+            })
+    }
+
+    async fn update(&self, entry: &TimecardEntry) -> Result<TimecardEntry> {
+        let id = entry.id.ok_or_else(|| {
+            RepositoryError::ValidationError("cannot update an entry without an id".to_string())
+        })?;
+
+        let result = sqlx::query(
+            "UPDATE timecard_entries \
+             SET clock_in = ?, clock_out = ?, break_minutes = ?, notes = ?, updated_at = ? \
+             WHERE id = ?",
+        )
+        .bind(entry.clock_in)
+        .bind(entry.clock_out)
+        .bind(entry.break_minutes)
+        .bind(&entry.notes)
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound(format!("Entry with id {}", id)).into());
+        }
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Entry with id {}", id)).into())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        let result = sqlx::query("DELETE FROM timecard_entries WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DatabaseError::QueryFailed(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound(format!("Entry with id {}", id)).into());
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +575,103 @@ mod tests {
         assert!(updated.clock_in.is_some());
         assert!(updated.clock_out.is_some());
     }
+
+    #[tokio::test]
+    async fn test_find_by_id_missing() {
+        let repo = InMemoryRepository::new();
+        assert!(repo.find_by_id(999).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_open_entry() {
+        let repo = InMemoryRepository::new();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let mut entry = TimecardEntry::new("EMP001".to_string(), date);
+        entry.clock_in = Some(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        let created = repo.create(&entry).await.unwrap();
+
+        let open = repo.find_open_entry("EMP001").await.unwrap();
+        assert_eq!(open.unwrap().id, created.id);
+
+        let mut closed = created;
+        closed.clock_out = Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        repo.update(&closed).await.unwrap();
+
+        assert!(repo.find_open_entry("EMP001").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_daily_hours() {
+        let repo = InMemoryRepository::new();
+
+        let mut day1 = TimecardEntry::new(
+            "EMP001".to_string(),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        );
+        day1.clock_in = Some(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        day1.clock_out = Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        repo.create(&day1).await.unwrap();
+
+        let mut day2 = TimecardEntry::new(
+            "EMP001".to_string(),
+            NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
+        );
+        day2.clock_in = Some(NaiveTime::from_hms_opt(22, 0, 0).unwrap());
+        day2.clock_out = Some(NaiveTime::from_hms_opt(2, 0, 0).unwrap());
+        repo.create(&day2).await.unwrap();
+
+        let daily = repo
+            .daily_hours(
+                "EMP001",
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(daily.len(), 2);
+        assert!((daily[0].worked_hours - 9.0).abs() < 0.01);
+        assert!((daily[1].worked_hours - 4.0).abs() < 0.01);
+    }
+
+    /// Integration test against a real MySQL database with the
+    /// `timecard_entries` table from `schema.sql` already applied.
+    /// Run with: TEST_DATABASE_URL=mysql://... cargo test mysql_repository -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_mysql_repository_crud() {
+        let url = std::env::var("TEST_DATABASE_URL")
+            .expect("TEST_DATABASE_URL must be set for this test");
+
+        let pool = sqlx::mysql::MySqlPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .expect("failed to connect to test database");
+
+        let repo = MySqlRepository::new(pool);
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let mut entry = TimecardEntry::new("EMP_IT_001".to_string(), date);
+        entry.clock_in = Some(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+        let created = repo.create(&entry).await.expect("create failed");
+        let id = created.id.expect("created entry has an id");
+
+        let found = repo.find_by_id(id).await.expect("find_by_id failed");
+        assert_eq!(found.unwrap().employee_id, "EMP_IT_001");
+
+        let mut to_update = created.clone();
+        to_update.clock_out = Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        let updated = repo.update(&to_update).await.expect("update failed");
+        assert!(updated.clock_out.is_some());
+
+        let range = repo
+            .find_by_employee_and_range("EMP_IT_001", date, date)
+            .await
+            .expect("find_by_employee_and_range failed");
+        assert_eq!(range.len(), 1);
+
+        repo.delete(id).await.expect("delete failed");
+        assert!(repo.find_by_id(id).await.unwrap().is_none());
+    }
 }
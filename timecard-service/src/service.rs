@@ -7,8 +7,12 @@ use anyhow::Result;
 use chrono::{NaiveDate, NaiveTime};
 use thiserror::Error;
 
-use crate::models::TimecardEntry;
-use crate::repository::{InMemoryRepository, TimecardRepository};
+use crate::models::{DailyHours, TimecardEntry, TimecardSummary};
+use crate::repository::{ClockInOutcome, InMemoryRepository, TimecardRepository};
+
+/// Default daily overtime threshold used by [`TimecardService::summarize`]
+/// when none is configured via [`TimecardService::with_overtime_daily_threshold_hours`].
+const DEFAULT_OVERTIME_DAILY_THRESHOLD_HOURS: f64 = 8.0;
 
 /// Service errors
 #[derive(Error, Debug)]
@@ -22,6 +26,16 @@ pub enum ServiceError {
     #[error("Clock out time must be after clock in time")]
     InvalidTimeRange,
 
+    #[error("Employee {employee_id} already has an open entry on {date} (clocked in at {clock_in})")]
+    AlreadyClockedIn {
+        employee_id: String,
+        date: String,
+        clock_in: String,
+    },
+
+    #[error("Employee {employee_id} has no open entry to clock out of")]
+    NoOpenEntry { employee_id: String },
+
     #[error("Repository error: {0}")]
     RepositoryError(String),
 }
@@ -29,6 +43,7 @@ pub enum ServiceError {
 /// Timecard service for business operations
 pub struct TimecardService {
     repository: InMemoryRepository,
+    overtime_daily_threshold_hours: f64,
 }
 
 impl TimecardService {
@@ -36,9 +51,26 @@ impl TimecardService {
     pub fn new() -> Self {
         Self {
             repository: InMemoryRepository::new(),
+            overtime_daily_threshold_hours: DEFAULT_OVERTIME_DAILY_THRESHOLD_HOURS,
         }
     }
 
+    /// Override the daily threshold beyond which worked hours count as
+    /// overtime in [`TimecardService::summarize`]. Defaults to
+    /// [`DEFAULT_OVERTIME_DAILY_THRESHOLD_HOURS`].
+    pub fn with_overtime_daily_threshold_hours(mut self, hours: f64) -> Self {
+        self.overtime_daily_threshold_hours = hours;
+        self
+    }
+
+    /// Confirm the service is responsive to InProcess calls. This service
+    /// has no external dependency of its own beyond the in-memory
+    /// repository, so it is healthy once constructed; it exists so
+    /// `ServiceRouter::health_all` has something concrete to call.
+    pub async fn health(&self) -> bool {
+        true
+    }
+
     /// Get timecard entry for an employee on a specific date
     pub async fn get_entry(
         &self,
@@ -58,6 +90,17 @@ impl TimecardService {
             })
     }
 
+    /// Get the employee's open entry (clocked in, not yet clocked out), if any
+    pub async fn current_open_entry(
+        &self,
+        employee_id: &str,
+    ) -> Result<Option<TimecardEntry>, ServiceError> {
+        self.repository
+            .find_open_entry(employee_id)
+            .await
+            .map_err(|e| ServiceError::RepositoryError(e.to_string()))
+    }
+
     /// Clock in for an employee
     pub async fn clock_in(
         &self,
@@ -71,29 +114,25 @@ impl TimecardService {
         let parsed_time = NaiveTime::parse_from_str(time, "%H:%M")
             .map_err(|_| ServiceError::InvalidTimeFormat(time.to_string()))?;
 
-        // Check if entry exists
-        let existing = self
+        // repository.clock_in checks for and rejects an existing open
+        // entry as part of the same atomic operation, so two concurrent
+        // calls for the same employee can't both see "no open entry" and
+        // both succeed.
+        match self
             .repository
-            .find_by_employee_and_date(employee_id, parsed_date)
+            .clock_in(employee_id, parsed_date, parsed_time)
             .await
-            .map_err(|e| ServiceError::RepositoryError(e.to_string()))?;
-
-        match existing {
-            Some(mut entry) => {
-                entry.clock_in = Some(parsed_time);
-                self.repository
-                    .update(&entry)
-                    .await
-                    .map_err(|e| ServiceError::RepositoryError(e.to_string()))
-            }
-            None => {
-                let mut entry = TimecardEntry::new(employee_id.to_string(), parsed_date);
-                entry.clock_in = Some(parsed_time);
-                self.repository
-                    .create(&entry)
-                    .await
-                    .map_err(|e| ServiceError::RepositoryError(e.to_string()))
-            }
+            .map_err(|e| ServiceError::RepositoryError(e.to_string()))?
+        {
+            ClockInOutcome::ClockedIn(entry) => Ok(entry),
+            ClockInOutcome::AlreadyOpen(open_entry) => Err(ServiceError::AlreadyClockedIn {
+                employee_id: employee_id.to_string(),
+                date: open_entry.date.to_string(),
+                clock_in: open_entry
+                    .clock_in
+                    .map(|t| t.format("%H:%M").to_string())
+                    .unwrap_or_default(),
+            }),
         }
     }
 
@@ -110,16 +149,21 @@ impl TimecardService {
         let parsed_time = NaiveTime::parse_from_str(time, "%H:%M")
             .map_err(|_| ServiceError::InvalidTimeFormat(time.to_string()))?;
 
-        // Get existing entry
-        let mut entry = self
+        // There must be an open entry (clocked in, not yet clocked out) to close
+        let existing = self
             .repository
             .find_by_employee_and_date(employee_id, parsed_date)
             .await
-            .map_err(|e| ServiceError::RepositoryError(e.to_string()))?
-            .ok_or_else(|| ServiceError::NotFound {
-                employee_id: employee_id.to_string(),
-                date: date.to_string(),
-            })?;
+            .map_err(|e| ServiceError::RepositoryError(e.to_string()))?;
+
+        let mut entry = match existing {
+            Some(e) if e.clock_in.is_some() && e.clock_out.is_none() => e,
+            _ => {
+                return Err(ServiceError::NoOpenEntry {
+                    employee_id: employee_id.to_string(),
+                })
+            }
+        };
 
         // Validate time range
         if let Some(clock_in) = entry.clock_in {
@@ -129,10 +173,22 @@ impl TimecardService {
         }
 
         entry.clock_out = Some(parsed_time);
-        self.repository
+        let entry = self
+            .repository
             .update(&entry)
             .await
-            .map_err(|e| ServiceError::RepositoryError(e.to_string()))
+            .map_err(|e| ServiceError::RepositoryError(e.to_string()))?;
+
+        if let Some(hours) = entry.working_hours() {
+            tracing::info!(
+                employee_id,
+                date,
+                hours,
+                "clocked out; worked duration computed"
+            );
+        }
+
+        Ok(entry)
     }
 
     /// Create a complete timecard entry
@@ -185,6 +241,45 @@ impl TimecardService {
             .await
             .map_err(|e| ServiceError::RepositoryError(e.to_string()))
     }
+
+    /// Summarize worked hours for an employee over `[from, to]`: total
+    /// hours, overtime beyond `overtime_daily_threshold_hours` per day, and
+    /// a per-day breakdown. Delegates the aggregation to the repository
+    /// layer (a single grouped query for `MySqlRepository`) rather than
+    /// loading every entry and summing here.
+    pub async fn summarize(
+        &self,
+        employee_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<TimecardSummary, ServiceError> {
+        let parsed_from = NaiveDate::parse_from_str(from, "%Y-%m-%d")
+            .map_err(|_| ServiceError::InvalidTimeFormat(from.to_string()))?;
+
+        let parsed_to = NaiveDate::parse_from_str(to, "%Y-%m-%d")
+            .map_err(|_| ServiceError::InvalidTimeFormat(to.to_string()))?;
+
+        let daily_breakdown: Vec<DailyHours> = self
+            .repository
+            .daily_hours(employee_id, parsed_from, parsed_to)
+            .await
+            .map_err(|e| ServiceError::RepositoryError(e.to_string()))?;
+
+        let total_hours: f64 = daily_breakdown.iter().map(|d| d.worked_hours).sum();
+        let overtime_hours: f64 = daily_breakdown
+            .iter()
+            .map(|d| (d.worked_hours - self.overtime_daily_threshold_hours).max(0.0))
+            .sum();
+
+        Ok(TimecardSummary {
+            employee_id: employee_id.to_string(),
+            start_date: parsed_from,
+            end_date: parsed_to,
+            total_hours,
+            overtime_hours,
+            daily_breakdown,
+        })
+    }
 }
 
 impl Default for TimecardService {
@@ -197,6 +292,12 @@ impl Default for TimecardService {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_health_is_true() {
+        let service = TimecardService::new();
+        assert!(service.health().await);
+    }
+
     #[tokio::test]
     async fn test_clock_in_and_out() {
         let service = TimecardService::new();
@@ -237,4 +338,91 @@ mod tests {
 
         assert!(matches!(result, Err(ServiceError::InvalidTimeRange)));
     }
+
+    #[tokio::test]
+    async fn test_double_clock_in_is_rejected() {
+        let service = TimecardService::new();
+
+        service.clock_in("EMP001", "2024-01-15", "09:00").await.unwrap();
+
+        let result = service.clock_in("EMP001", "2024-01-16", "09:00").await;
+
+        assert!(matches!(result, Err(ServiceError::AlreadyClockedIn { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_orphan_clock_out_is_rejected() {
+        let service = TimecardService::new();
+
+        let result = service.clock_out("EMP001", "2024-01-15", "18:00").await;
+
+        assert!(matches!(result, Err(ServiceError::NoOpenEntry { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_clock_out_after_already_closed_is_rejected() {
+        let service = TimecardService::new();
+
+        service.clock_in("EMP001", "2024-01-15", "09:00").await.unwrap();
+        service.clock_out("EMP001", "2024-01-15", "18:00").await.unwrap();
+
+        let result = service.clock_out("EMP001", "2024-01-15", "19:00").await;
+
+        assert!(matches!(result, Err(ServiceError::NoOpenEntry { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_current_open_entry() {
+        let service = TimecardService::new();
+
+        assert!(service.current_open_entry("EMP001").await.unwrap().is_none());
+
+        service.clock_in("EMP001", "2024-01-15", "09:00").await.unwrap();
+        let open = service.current_open_entry("EMP001").await.unwrap().unwrap();
+        assert_eq!(open.date.to_string(), "2024-01-15");
+
+        service.clock_out("EMP001", "2024-01-15", "18:00").await.unwrap();
+        assert!(service.current_open_entry("EMP001").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_computes_total_and_overtime() {
+        let service = TimecardService::new().with_overtime_daily_threshold_hours(8.0);
+
+        service
+            .create_entry("EMP001", "2024-01-15", "09:00", "18:00")
+            .await
+            .unwrap();
+        service
+            .create_entry("EMP001", "2024-01-16", "09:00", "20:00")
+            .await
+            .unwrap();
+
+        let summary = service
+            .summarize("EMP001", "2024-01-01", "2024-01-31")
+            .await
+            .unwrap();
+
+        assert_eq!(summary.daily_breakdown.len(), 2);
+        assert!((summary.total_hours - 20.0).abs() < 0.01);
+        assert!((summary.overtime_hours - 4.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_defaults_threshold_to_eight_hours() {
+        let service = TimecardService::new();
+
+        service
+            .create_entry("EMP001", "2024-01-15", "09:00", "17:00")
+            .await
+            .unwrap();
+
+        let summary = service
+            .summarize("EMP001", "2024-01-01", "2024-01-31")
+            .await
+            .unwrap();
+
+        assert!((summary.total_hours - 8.0).abs() < 0.01);
+        assert!((summary.overtime_hours).abs() < 0.01);
+    }
 }